@@ -0,0 +1,41 @@
+use google_cognitive_apis::dialogflow::agents_client::AgentsClient;
+use log::*;
+use std::env;
+use std::fs;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("agents client example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+    let parent = AgentsClient::get_agent_parent_string("<<gcp project id>>");
+
+    let mut agents_client = AgentsClient::create(credentials).await.unwrap();
+
+    // backup current agent, then restore it from backup and retrain
+    agents_client
+        .export_agent_to_file(parent.clone(), "/tmp/agent_backup.zip", None, None)
+        .await
+        .unwrap();
+    info!("agent exported into /tmp/agent_backup.zip");
+
+    agents_client
+        .restore_agent_from_file(parent.clone(), "/tmp/agent_backup.zip", None, None)
+        .await
+        .unwrap();
+    info!("agent restored");
+
+    agents_client
+        .train_agent_wait(parent.clone(), Some(2000), None)
+        .await
+        .unwrap();
+    info!("agent trained");
+
+    let validation_result = agents_client
+        .get_validation_result(parent, None, None)
+        .await
+        .unwrap();
+    info!("validation result {:#?}", validation_result);
+}
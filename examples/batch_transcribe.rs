@@ -0,0 +1,58 @@
+//! Batch transcription command. Usage:
+//!
+//! cargo run --example batch_transcribe -- <manifest file or directory> <output.jsonl> [state file] [recognition config json file]
+//!
+//! Interrupted batch can be resumed by running the command again with the same state file.
+use google_cognitive_apis::api::grpc::google::cloud::speechtotext::v1::RecognitionConfig;
+use google_cognitive_apis::api::rest::google::cloud::speechtotext::v1::deserialize_recognition_config;
+use google_cognitive_apis::speechtotext::batch::{BatchConfig, BatchManifest, BatchTranscriber};
+use log::*;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("batch transcription example");
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!(
+            "usage: {} <manifest file or directory> <output.jsonl> [state file] [recognition config json file]",
+            args[0]
+        );
+        std::process::exit(1);
+    }
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+
+    let manifest = if Path::new(&args[1]).is_dir() {
+        BatchManifest::from_dir(&args[1], &["wav", "flac", "raw"]).unwrap()
+    } else {
+        BatchManifest::from_file(&args[1]).unwrap()
+    };
+
+    let recognition_config: RecognitionConfig = match args.get(4) {
+        Some(config_file) => {
+            deserialize_recognition_config(&fs::read_to_string(config_file).unwrap())
+                .unwrap()
+                .into()
+        }
+        None => deserialize_recognition_config(r#"{"languageCode": "en-US"}"#)
+            .unwrap()
+            .into(),
+    };
+
+    let mut config = BatchConfig::new(recognition_config, &args[2]);
+    config.state_file = args.get(3).map(|state_file| state_file.into());
+    config.concurrency = 8;
+    config.requests_per_minute = Some(300);
+
+    let summary = BatchTranscriber::new(credentials, config)
+        .run(manifest, None)
+        .await
+        .unwrap();
+    info!("batch done {:?}", summary);
+}
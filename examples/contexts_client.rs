@@ -0,0 +1,59 @@
+use google_cognitive_apis::api::grpc::google::cloud::dialogflow::v2beta1::Context;
+use google_cognitive_apis::dialogflow::contexts_client::ContextsClient;
+use google_cognitive_apis::dialogflow::sessions_client::SessionsClient;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CallerInfo {
+    caller_id: String,
+    account_type: String,
+}
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("contexts client example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+
+    let guid = "8d58ca66-8977-4d14-8664-c48388b283b8";
+    let session = ContextsClient::get_session_string("<<gcp project id>>", guid);
+    let context_name = ContextsClient::get_context_string("<<gcp project id>>", guid, "caller");
+
+    let sessions_client = SessionsClient::create(credentials).await.unwrap();
+    let mut contexts_client = sessions_client.contexts_client();
+
+    let caller_info = CallerInfo {
+        caller_id: "+420123456789".to_owned(),
+        account_type: "premium".to_owned(),
+    };
+    let context = Context::with_parameters(context_name.clone(), 5, &caller_info).unwrap();
+
+    let context = contexts_client
+        .create_context(session.clone(), context, None)
+        .await
+        .unwrap();
+    info!("created context {:?}", context);
+
+    let context = contexts_client
+        .set_context_lifespan(context_name, 2, None)
+        .await
+        .unwrap();
+    let params: Option<CallerInfo> = context.parameters().unwrap();
+    info!("updated context {:?}, parameters {:?}", context, params);
+
+    let contexts = contexts_client
+        .list_all_contexts(session.clone(), None)
+        .await
+        .unwrap();
+    info!("active contexts {:#?}", contexts);
+
+    contexts_client
+        .delete_all_contexts(session, None)
+        .await
+        .unwrap();
+}
@@ -47,7 +47,7 @@ async fn main() {
         .unwrap();
 
     match recognizer
-        .long_running_recognize(long_running_request)
+        .long_running_recognize(long_running_request, None)
         .await
     {
         Err(err) => {
@@ -58,7 +58,7 @@ async fn main() {
             info!("long_running_operation ok {:?}", long_running_operation);
 
             let llo_response: Option<LongRunningRecognizeResponse> = recognizer
-                .long_running_wait(long_running_operation, None)
+                .long_running_wait(long_running_operation, None, None)
                 .await
                 .unwrap();
 
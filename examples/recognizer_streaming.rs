@@ -54,7 +54,7 @@ async fn main() {
     let mut result_receiver = recognizer.get_streaming_result_receiver(None);
 
     tokio::spawn(async move {
-        let recognition_result = recognizer.streaming_recognize().await;
+        let recognition_result = recognizer.streaming_recognize(None).await;
 
         match recognition_result {
             Err(err) => error!("streaming_recognize error {:?}", err),
@@ -54,7 +54,7 @@ async fn main() {
     // See also method drop_audio_sink
     let audio_sender = recognizer.take_audio_sink().unwrap();
 
-    let stream = recognizer.streaming_recognize_async_stream().await;
+    let stream = recognizer.streaming_recognize_async_stream(None).await;
     pin_mut!(stream); // needed for iteration
 
     tokio::spawn(async move {
@@ -0,0 +1,99 @@
+use google_cognitive_apis::api::grpc::google::cloud::speechtotext::v1::{
+    recognition_config::AudioEncoding, RecognitionConfig, StreamingRecognitionConfig,
+};
+use google_cognitive_apis::speechtotext::recognizer::Recognizer;
+use google_cognitive_apis::streaming::CancellationToken;
+
+use log::*;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("cancellable streaming recognizer example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+    let streaming_config = StreamingRecognitionConfig {
+        config: Some(RecognitionConfig {
+            encoding: AudioEncoding::Linear16 as i32,
+            sample_rate_hertz: 8000,
+            audio_channel_count: 1,
+            enable_separate_recognition_per_channel: false,
+            language_code: "en-US".to_string(),
+            max_alternatives: 1,
+            profanity_filter: false,
+            speech_contexts: vec![],
+            enable_word_time_offsets: false,
+            enable_automatic_punctuation: false,
+            diarization_config: None,
+            metadata: None,
+            model: "".to_string(),
+            use_enhanced: false,
+        }),
+        single_utterance: false,
+        interim_results: true,
+    };
+
+    let mut recognizer =
+        Recognizer::create_streaming_recognizer(credentials, streaming_config, None)
+            .await
+            .unwrap();
+
+    // audio sink can be kept (e.g. microphone capture running in loop),
+    // sending is stopped by cancellation token instead of dropping the sink
+    let audio_sender = recognizer.get_audio_sink().unwrap();
+    recognizer.drop_audio_sink();
+
+    let mut result_receiver = recognizer.get_streaming_result_receiver(None);
+
+    let token = CancellationToken::new();
+    let session_token = token.clone();
+
+    tokio::spawn(async move {
+        match recognizer
+            .streaming_recognize_cancellable(session_token, None)
+            .await
+        {
+            Err(err) => error!("streaming_recognize_cancellable error {:?}", err),
+            Ok(outcome) => info!("streaming_recognize_cancellable outcome {:?}", outcome),
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut file = File::open("/tmp/hello_rust_8.wav").unwrap();
+        let chunk_size = 1024;
+
+        loop {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            let n = file
+                .by_ref()
+                .take(chunk_size as u64)
+                .read_to_end(&mut chunk)
+                .unwrap();
+            if n == 0 {
+                break;
+            }
+
+            let streaming_request = Recognizer::streaming_request_from_bytes(chunk);
+
+            if audio_sender.send(streaming_request).await.is_err() {
+                break;
+            }
+
+            if n < chunk_size {
+                break;
+            }
+        }
+        // stop sending audio and wait up to 2 seconds for final results
+        token.shutdown(Duration::from_secs(2));
+    });
+
+    // result channel is closed once streaming session finishes
+    while let Some(reco_result) = result_receiver.recv().await {
+        info!("recognition result {:?}", reco_result);
+    }
+}
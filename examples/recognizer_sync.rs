@@ -46,7 +46,7 @@ async fn main() {
         .await
         .unwrap();
 
-    match recognizer.recognize(recognize_request).await {
+    match recognizer.recognize(recognize_request, None).await {
         Err(err) => {
             error!("recognize error {:?}", err);
         }
@@ -0,0 +1,99 @@
+use google_cognitive_apis::api::grpc::google::cloud::speechtotext::v2::{
+    explicit_decoding_config::AudioEncoding, recognition_config::DecodingConfig,
+    recognize_request::AudioSource, CreateRecognizerRequest, ExplicitDecodingConfig,
+    RecognitionConfig, RecognitionFeatures, RecognizeRequest, Recognizer as RecognizerResource,
+};
+use google_cognitive_apis::speechtotext::recognizer_v2::{
+    get_location_string, get_recognizer_string, Recognizer,
+};
+use log::*;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("v2 recognizer example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+    let project = "my-project";
+    let location = "europe-west4";
+
+    let mut file = File::open("/tmp/hello_rust_8.wav").unwrap();
+    let mut audio_bytes = Vec::new();
+    file.read_to_end(&mut audio_bytes).unwrap();
+
+    let config = RecognitionConfig {
+        decoding_config: Some(DecodingConfig::ExplicitDecodingConfig(
+            ExplicitDecodingConfig {
+                encoding: AudioEncoding::Linear16 as i32,
+                sample_rate_hertz: 8000,
+                audio_channel_count: 1,
+            },
+        )),
+        model: "chirp".to_string(),
+        language_codes: vec!["en-US".to_string()],
+        features: Some(RecognitionFeatures {
+            enable_automatic_punctuation: true,
+            ..Default::default()
+        }),
+        transcript_normalization: None,
+        translation_config: None,
+    };
+
+    let mut recognizer = Recognizer::create_synchronous_recognizer(credentials, location)
+        .await
+        .unwrap();
+
+    // create recognizer resource with default recognition config
+    let operation = recognizer
+        .create_recognizer(
+            CreateRecognizerRequest {
+                recognizer: Some(RecognizerResource {
+                    display_name: "example recognizer".to_string(),
+                    default_recognition_config: Some(config.clone()),
+                    ..Default::default()
+                }),
+                validate_only: false,
+                recognizer_id: "example-recognizer".to_string(),
+                parent: get_location_string(project, location),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let created = recognizer
+        .recognizer_operation_wait(operation, None, None)
+        .await
+        .unwrap();
+    info!("created recognizer {:?}", created);
+
+    let recognizers = recognizer
+        .list_all_recognizers(get_location_string(project, location), false, None)
+        .await
+        .unwrap();
+    info!(
+        "recognizers in location {}: {}",
+        location,
+        recognizers.len()
+    );
+
+    // recognize using implicit recognizer & inline config
+    let recognize_request = RecognizeRequest {
+        recognizer: get_recognizer_string(project, location, "_"),
+        config: Some(config),
+        config_mask: None,
+        audio_source: Some(AudioSource::Content(audio_bytes)),
+    };
+
+    match recognizer.recognize(recognize_request, None).await {
+        Err(err) => {
+            error!("recognize error {:?}", err);
+        }
+        Ok(recognize_response) => {
+            info!("recognize_response {:?}", recognize_response);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use google_cognitive_apis::api::grpc::google::cloud::dialogflow::v2beta1::{
+    query_input::Input, DetectIntentRequest, QueryInput, SessionEntityType, TextInput,
+};
+use google_cognitive_apis::dialogflow::session_entity_types_client::SessionEntityTypesClient;
+use google_cognitive_apis::dialogflow::sessions_client::SessionsClient;
+use log::*;
+use std::env;
+use std::fs;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("session entity types example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+
+    let guid = "8d58ca66-8977-4d14-8664-c48388b283b8";
+    let session = SessionsClient::get_session_string("<<gcp project id>>", guid);
+    let contacts_entity_type =
+        SessionEntityTypesClient::get_session_entity_type_string(&session, "contact");
+
+    let mut sessions_client = SessionsClient::create(credentials).await.unwrap();
+    let mut session_entity_types_client = sessions_client.session_entity_types_client();
+
+    // session entity type stored in dialogflow for whole session
+    let contacts = SessionEntityType::override_entities(contacts_entity_type.clone(), vec![])
+        .add_entity("Jane Doe", vec!["Jane".to_owned(), "Jane Doe".to_owned()])
+        .add_entity("John Smith", vec!["John".to_owned(), "Johnny".to_owned()]);
+    let contacts = session_entity_types_client
+        .create_session_entity_type(session.clone(), contacts, None)
+        .await
+        .unwrap();
+    info!("created session entity type {:?}", contacts);
+
+    // session entity type attached inline to single query
+    let accounts = SessionEntityType::supplement_entities(
+        SessionEntityTypesClient::get_session_entity_type_string(&session, "account"),
+        vec![],
+    )
+    .add_entity("savings", vec![]);
+
+    let mut request = DetectIntentRequest {
+        session: session.clone(),
+        query_params: None,
+        query_input: Some(QueryInput {
+            input: Some(Input::Text(TextInput {
+                text: "Send 100 dollars from savings to Johnny".to_owned(),
+                language_code: "en".to_owned(),
+            })),
+        }),
+        output_audio_config: None,
+        output_audio_config_mask: None,
+        input_audio: vec![],
+    };
+    request.add_session_entity_type(accounts);
+
+    match sessions_client.detect_intent(request, None).await {
+        Err(err) => {
+            error!("detect_intent error {:?}", err);
+        }
+        Ok(detect_intent_response) => {
+            info!("detect_intent_response {:?}", detect_intent_response);
+        }
+    }
+
+    session_entity_types_client
+        .delete_session_entity_type(contacts_entity_type, None)
+        .await
+        .unwrap();
+}
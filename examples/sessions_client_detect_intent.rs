@@ -33,7 +33,7 @@ async fn main() {
 
     let mut sessions_client = SessionsClient::create(credentials).await.unwrap();
 
-    match sessions_client.detect_intent(request).await {
+    match sessions_client.detect_intent(request, None).await {
         Err(err) => {
             error!("detect_intent error {:?}", err);
         }
@@ -60,7 +60,7 @@ async fn main() {
     let mut result_receiver = sessions_client.get_streaming_result_receiver(None);
 
     tokio::spawn(async move {
-        let recognition_result = sessions_client.streaming_detect_intent().await;
+        let recognition_result = sessions_client.streaming_detect_intent(None).await;
 
         match recognition_result {
             Err(err) => error!("streaming_detect_intent error {:?}", err),
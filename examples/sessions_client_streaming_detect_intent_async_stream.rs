@@ -60,7 +60,9 @@ async fn main() {
     // See also method drop_audio_sink
     let audio_sender = sessions_client.take_audio_sink().unwrap();
 
-    let stream = sessions_client.streaming_detect_intent_async_stream().await;
+    let stream = sessions_client
+        .streaming_detect_intent_async_stream(None)
+        .await;
     pin_mut!(stream); // needed for iteration
 
     tokio::spawn(async move {
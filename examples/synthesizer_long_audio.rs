@@ -0,0 +1,62 @@
+use google_cognitive_apis::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, AudioConfig, AudioEncoding, SynthesisInput,
+    SynthesizeLongAudioRequest, VoiceSelectionParams,
+};
+use google_cognitive_apis::texttospeech::synthesizer::Synthesizer;
+use log::*;
+use std::env;
+use std::fs;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("long audio synthetizer example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+    let text = fs::read_to_string("/tmp/chapter_1.txt").unwrap();
+
+    let mut synthesizer = Synthesizer::create(credentials).await.unwrap();
+
+    let mut operation = synthesizer
+        .synthesize_long_audio(
+            SynthesizeLongAudioRequest {
+                parent: "projects/my-project/locations/global".to_string(),
+                input: Some(SynthesisInput {
+                    input_source: Some(InputSource::Text(text)),
+                }),
+                audio_config: Some(AudioConfig {
+                    audio_encoding: AudioEncoding::Linear16 as i32,
+                    speaking_rate: 1f64,
+                    pitch: 0f64,
+                    volume_gain_db: 0f64,
+                    sample_rate_hertz: 24000,
+                    effects_profile_id: vec![],
+                }),
+                output_gcs_uri: "gs://my-bucket/chapter_1.wav".to_string(),
+                voice: Some(VoiceSelectionParams {
+                    language_code: "en-US".to_string(),
+                    name: "en-US-Standard-A".to_string(),
+                    ssml_gender: 0,
+                }),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    info!("long audio synthesis started: {}", operation.name());
+
+    while !operation.is_done() {
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        operation.refresh(None).await.unwrap();
+        if let Ok(Some(metadata)) = operation.metadata() {
+            info!("progress {:.1}%", metadata.progress_percentage);
+        }
+    }
+
+    match operation.wait(None, None).await {
+        Ok(_) => info!("audio written to gs://my-bucket/chapter_1.wav"),
+        Err(err) => error!("long audio synthesis error {:?}", err),
+    }
+}
@@ -0,0 +1,62 @@
+use futures_util::pin_mut;
+use futures_util::stream::StreamExt;
+use google_cognitive_apis::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, AudioConfig, AudioEncoding, SynthesisInput,
+    SynthesizeSpeechRequest, VoiceSelectionParams,
+};
+use google_cognitive_apis::texttospeech::synthesizer::Synthesizer;
+use log::*;
+use std::env;
+use std::fs::{self, File};
+use std::io::prelude::*;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("streaming synthetizer example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+
+    let synthesizer = Synthesizer::create(credentials).await.unwrap();
+
+    let request = SynthesizeSpeechRequest {
+        input: Some(SynthesisInput {
+            input_source: Some(InputSource::Text(
+                "Thank you for calling. Your order has been shipped yesterday. \
+                 It should arrive within three business days. Is there anything else I can help with?"
+                    .to_string(),
+            )),
+        }),
+        voice: Some(VoiceSelectionParams {
+            language_code: "en-US".to_string(),
+            name: "en-US-Standard-C".to_string(),
+            ssml_gender: 0,
+        }),
+        audio_config: Some(AudioConfig {
+            audio_encoding: AudioEncoding::Linear16 as i32,
+            speaking_rate: 1f64,
+            pitch: 0f64,
+            volume_gain_db: 0f64,
+            sample_rate_hertz: 16000,
+            effects_profile_id: vec![],
+        }),
+    };
+
+    // raw LINEAR16 samples (WAV headers are stripped from the chunks)
+    let mut file = File::create("/tmp/synthetized_audio.raw").unwrap();
+
+    let stream = synthesizer.synthesize_speech_stream(request, None, None);
+    pin_mut!(stream);
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        info!(
+            "chunk #{} ({} bytes): {}",
+            chunk.index,
+            chunk.audio.len(),
+            chunk.text
+        );
+        file.write_all(&chunk.audio).unwrap();
+    }
+}
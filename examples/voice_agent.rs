@@ -0,0 +1,123 @@
+use google_cognitive_apis::api::grpc::google::cloud::speechtotext::v1::{
+    recognition_config::AudioEncoding as RecognitionAudioEncoding, RecognitionConfig,
+    StreamingRecognitionConfig,
+};
+use google_cognitive_apis::api::grpc::google::cloud::texttospeech::v1::{
+    AudioConfig, AudioEncoding, VoiceSelectionParams,
+};
+use google_cognitive_apis::dialogflow::sessions_client::SessionsClient;
+use google_cognitive_apis::texttospeech::synthesizer::Synthesizer;
+use google_cognitive_apis::voice_agent::{
+    RecognizerSpeechToText, SessionsDialogService, SynthesizerTextToSpeech, VoiceAgent,
+    VoiceAgentConfig, VoiceAgentEvent,
+};
+use log::*;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[tokio::main]
+async fn main() {
+    env::set_var("RUST_LOG", "info");
+    env_logger::init();
+    info!("voice agent example");
+
+    let credentials = fs::read_to_string("/tmp/cred.json").unwrap();
+    let guid = "3ddb2bd5-08a5-4bd7-a7e4-f3a5dc9e3b3a";
+    let session = SessionsClient::get_session_string("<<gcp project id>>", guid);
+
+    let streaming_config = StreamingRecognitionConfig {
+        config: Some(RecognitionConfig {
+            encoding: RecognitionAudioEncoding::Linear16 as i32,
+            sample_rate_hertz: 8000,
+            audio_channel_count: 1,
+            enable_separate_recognition_per_channel: false,
+            language_code: "en-US".to_string(),
+            max_alternatives: 1,
+            profanity_filter: false,
+            speech_contexts: vec![],
+            enable_word_time_offsets: false,
+            enable_automatic_punctuation: false,
+            diarization_config: None,
+            metadata: None,
+            model: "".to_string(),
+            use_enhanced: false,
+        }),
+        single_utterance: true,
+        interim_results: true,
+    };
+
+    let sessions_client = SessionsClient::create(&credentials).await.unwrap();
+    let synthesizer = Synthesizer::create(&credentials).await.unwrap();
+    let speech_to_text = RecognizerSpeechToText::create(&credentials, streaming_config)
+        .await
+        .unwrap();
+
+    let mut agent = VoiceAgent::new(
+        speech_to_text,
+        SessionsDialogService::new(sessions_client, session, "en-US"),
+        SynthesizerTextToSpeech::new(
+            synthesizer,
+            VoiceSelectionParams {
+                language_code: "en-US".to_string(),
+                name: "".to_string(),
+                ssml_gender: 0,
+            },
+            AudioConfig {
+                audio_encoding: AudioEncoding::Linear16 as i32,
+                speaking_rate: 1f64,
+                pitch: 0f64,
+                volume_gain_db: 0f64,
+                sample_rate_hertz: 8000,
+                effects_profile_id: vec![],
+            },
+        ),
+        VoiceAgentConfig {
+            outbound_bytes_per_sec: Some(16000),
+            ..Default::default()
+        },
+    );
+
+    let (audio_sender, audio_receiver) = mpsc::channel::<Vec<u8>>(1000);
+    let (event_sender, mut event_receiver) = mpsc::channel::<VoiceAgentEvent>(1000);
+
+    tokio::spawn(async move {
+        let mut file = File::open("/tmp/hello_rust_8.wav").unwrap();
+        let chunk_size = 1024;
+
+        loop {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            let n = file
+                .by_ref()
+                .take(chunk_size as u64)
+                .read_to_end(&mut chunk)
+                .unwrap();
+            if n == 0 {
+                break;
+            }
+            audio_sender.send(chunk).await.unwrap();
+            // simulate real time audio (1024 bytes of LINEAR16 8kHz is 64ms)
+            tokio::time::sleep(Duration::from_millis(64)).await;
+        }
+    });
+
+    tokio::spawn(async move {
+        if let Err(err) = agent.run(audio_receiver, event_sender).await {
+            error!("voice agent error {:?}", err);
+        }
+    });
+
+    while let Some(event) = event_receiver.recv().await {
+        match event {
+            VoiceAgentEvent::Audio(audio) => info!("outbound audio: {} bytes", audio.len()),
+            VoiceAgentEvent::Intent {
+                display_name,
+                fulfillment_text,
+                ..
+            } => info!("intent: {} -> {}", display_name, fulfillment_text),
+            event => info!("event: {:?}", event),
+        }
+    }
+}
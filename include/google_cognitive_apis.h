@@ -0,0 +1,112 @@
+#ifndef GOOGLE_COGNITIVE_APIS_H
+#define GOOGLE_COGNITIVE_APIS_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit manually. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Call succeeded.
+#define GCAPI_OK 0
+
+// Invalid argument passed to the library (null pointer, invalid UTF-8, invalid JSON config).
+#define GCAPI_ERROR_INVALID_ARGUMENT 100
+
+// Library error without GRPC status code (e.g. connection or authentication error).
+#define GCAPI_ERROR_OTHER 101
+
+// Library panicked, the object which was used should be released.
+#define GCAPI_ERROR_PANIC 102
+
+// Streaming recognizer (v1 STT API).
+typedef struct GcapiRecognizer GcapiRecognizer;
+
+// Dialogflow sessions client.
+typedef struct GcapiSessionsClient GcapiSessionsClient;
+
+// Text-to-speech synthesizer.
+typedef struct GcapiSynthesizer GcapiSynthesizer;
+
+// Callback receiving streaming recognition results (first alternative of every result)
+// during gcapi_recognizer_poll. Transcript is valid only during the callback.
+typedef void (*GcapiRecognitionCallback)(void *user_data,
+                                         const char *transcript,
+                                         float confidence,
+                                         float stability,
+                                         bool is_final);
+
+// Returns message of the last error of the calling thread (or NULL).
+// Message is valid until next call of the library on the same thread.
+const char *gcapi_last_error_message(void);
+
+// Creates streaming recognizer and starts streaming recognition. Recognition config
+// is JSON of RecognitionConfig of REST API
+// (https://cloud.google.com/speech-to-text/docs/reference/rest/v1/RecognitionConfig).
+int32_t gcapi_recognizer_create(const char *credentials,
+                                const char *recognition_config_json,
+                                bool interim_results,
+                                bool single_utterance,
+                                GcapiRecognizer **out);
+
+// Pushes audio bytes into streaming recognition, blocks if the audio sink is full.
+int32_t gcapi_recognizer_push_audio(GcapiRecognizer *recognizer,
+                                    const uint8_t *audio,
+                                    size_t audio_len);
+
+// Closes the audio, API returns remaining results and closes the stream.
+int32_t gcapi_recognizer_close_audio(GcapiRecognizer *recognizer);
+
+// Delivers results received so far to the callback (does not block). Sets finished
+// to true once the stream is closed and all results were delivered. Returns error
+// if the streaming recognition failed.
+int32_t gcapi_recognizer_poll(GcapiRecognizer *recognizer,
+                              GcapiRecognitionCallback callback,
+                              void *user_data,
+                              bool *finished);
+
+// Releases recognizer, running streaming recognition is aborted.
+void gcapi_recognizer_free(GcapiRecognizer *recognizer);
+
+// Creates text-to-speech synthesizer.
+int32_t gcapi_synthesizer_create(const char *credentials, GcapiSynthesizer **out);
+
+// Synthesizes text (or SSML if ssml is true) into audio buffer which must be released
+// by gcapi_buffer_free. Voice name is optional (NULL), audio encoding is AudioEncoding
+// enum value of text-to-speech API (e.g. 1 = LINEAR16, 5 = MULAW), sample rate 0 means default.
+int32_t gcapi_synthesizer_synthesize(GcapiSynthesizer *synthesizer,
+                                     const char *text,
+                                     bool ssml,
+                                     const char *language_code,
+                                     const char *voice_name,
+                                     int32_t audio_encoding,
+                                     int32_t sample_rate_hertz,
+                                     uint8_t **audio_out,
+                                     size_t *audio_len_out);
+
+// Releases synthesizer.
+void gcapi_synthesizer_free(GcapiSynthesizer *synthesizer);
+
+// Creates dialogflow sessions client.
+int32_t gcapi_sessions_client_create(const char *credentials, GcapiSessionsClient **out);
+
+// Detects intent of the text. Session is full session path
+// (projects/<project>/agent/sessions/<session>). Fulfillment text and intent display
+// name (both optional, NULL if not needed) must be released by gcapi_string_free.
+int32_t gcapi_sessions_client_detect_intent_text(GcapiSessionsClient *sessions_client,
+                                                 const char *session,
+                                                 const char *text,
+                                                 const char *language_code,
+                                                 char **fulfillment_text_out,
+                                                 char **intent_out);
+
+// Releases sessions client.
+void gcapi_sessions_client_free(GcapiSessionsClient *sessions_client);
+
+// Releases audio buffer returned by gcapi_synthesizer_synthesize.
+void gcapi_buffer_free(uint8_t *buffer, size_t len);
+
+// Releases string returned by the library.
+void gcapi_string_free(char *value);
+
+#endif  /* GOOGLE_COGNITIVE_APIS_H */
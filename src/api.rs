@@ -1,3 +1,4 @@
 //! Contains definitions of structures for GRPC and REST APIs.
 pub mod grpc;
+pub mod protobuf;
 pub mod rest;
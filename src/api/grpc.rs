@@ -5,6 +5,8 @@ macro_rules! include_proto {
     };
 }
 
+// generated code is not linted
+#[allow(clippy::all)]
 pub mod google {
 
     pub mod rpc {
@@ -45,6 +47,11 @@ pub mod google {
                 #[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v1p1beta1",))]
                 include_proto!("google.cloud.speech.v1p1beta1");
             }
+
+            pub mod v2 {
+                #[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+                include_proto!("google.cloud.speech.v2");
+            }
         }
 
         pub mod dialogflow {
@@ -61,12 +68,15 @@ pub mod google {
 
             pub mod cx {
                 pub mod v3 {
-                    #[cfg(any(feature = "default", feature = "google-cloud-dialogflow-v2",))]
+                    #[cfg(any(feature = "default", feature = "google-cloud-dialogflow-cx-v3",))]
                     include_proto!("dialogflow_cx/google.cloud.dialogflow.cx.v3");
                 }
 
                 pub mod v3beta1 {
-                    #[cfg(any(feature = "default", feature = "google-cloud-dialogflow-v2",))]
+                    #[cfg(any(
+                        feature = "default",
+                        feature = "google-cloud-dialogflow-cx-v3beta1",
+                    ))]
                     include_proto!("dialogflow_cx/google.cloud.dialogflow.cx.v3beta1");
                 }
             }
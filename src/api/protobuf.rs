@@ -0,0 +1,183 @@
+//! Conversions between protobuf well-known types (prost_types::Struct, Value, ListValue)
+//! and serde_json::Value. Dialogflow uses protobuf Struct for query result parameters,
+//! diagnostic info, webhook payloads and context parameters. Functions in this module
+//! make it possible to work with them as regular JSON data or to (de)serialize them
+//! directly from/into user defined types.
+use crate::errors::{Error, Result};
+use prost_types::{value::Kind, ListValue, Struct, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Number as JsonNumber, Value as JsonValue};
+use std::collections::BTreeMap;
+
+/// Largest integer which can be represented by f64 without loss of precision (2^53).
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Converts protobuf Struct into JSON object.
+pub fn struct_to_json(proto_struct: &Struct) -> JsonValue {
+    let mut map = JsonMap::new();
+    for (key, val) in proto_struct.fields.iter() {
+        map.insert(key.to_string(), value_to_json(val));
+    }
+    JsonValue::Object(map)
+}
+
+/// Converts protobuf ListValue into JSON array.
+pub fn list_value_to_json(list_value: &ListValue) -> JsonValue {
+    JsonValue::Array(list_value.values.iter().map(value_to_json).collect())
+}
+
+/// Converts protobuf Value into JSON value.
+/// Protobuf numbers are always f64. Numbers without fractional part
+/// (within f64 safe integer range) are converted into JSON integers so that
+/// they can be deserialized into integer types (e.g. parameter "hangup_timeout": 20000).
+/// NaN and infinite numbers cannot be represented in JSON and are converted into null.
+pub fn value_to_json(value: &Value) -> JsonValue {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => JsonValue::Null,
+        Some(Kind::NumberValue(num)) => number_to_json(*num),
+        Some(Kind::StringValue(str_val)) => JsonValue::String(str_val.to_string()),
+        Some(Kind::BoolValue(bool_val)) => JsonValue::Bool(*bool_val),
+        Some(Kind::StructValue(struct_val)) => struct_to_json(struct_val),
+        Some(Kind::ListValue(list_val)) => list_value_to_json(list_val),
+    }
+}
+
+fn number_to_json(num: f64) -> JsonValue {
+    if num.is_finite() && num.fract() == 0.0 && num.abs() <= MAX_SAFE_INTEGER {
+        JsonValue::Number(JsonNumber::from(num as i64))
+    } else {
+        match JsonNumber::from_f64(num) {
+            Some(json_num) => JsonValue::Number(json_num),
+            None => JsonValue::Null,
+        }
+    }
+}
+
+/// Converts JSON value into protobuf Struct. Returns error if provided
+/// JSON value is not an object.
+pub fn json_to_struct(json_val: JsonValue) -> Result<Struct> {
+    match json_val {
+        JsonValue::Object(map) => Ok(json_map_to_struct(map)),
+        other => Err(Error::new(format!(
+            "json_to_struct: JSON object expected, got {}",
+            other
+        ))),
+    }
+}
+
+fn json_map_to_struct(map: JsonMap<String, JsonValue>) -> Struct {
+    let fields: BTreeMap<String, Value> = map
+        .into_iter()
+        .map(|(key, val)| (key, json_to_value(val)))
+        .collect();
+    Struct { fields }
+}
+
+/// Converts JSON array into protobuf ListValue.
+pub fn json_to_list_value(json_vals: Vec<JsonValue>) -> ListValue {
+    ListValue {
+        values: json_vals.into_iter().map(json_to_value).collect(),
+    }
+}
+
+/// Converts JSON value into protobuf Value. Since protobuf numbers
+/// are f64, integers bigger than 2^53 might lose precision.
+pub fn json_to_value(json_val: JsonValue) -> Value {
+    let kind = match json_val {
+        JsonValue::Null => Kind::NullValue(0),
+        JsonValue::Bool(bool_val) => Kind::BoolValue(bool_val),
+        JsonValue::Number(num) => Kind::NumberValue(num.as_f64().unwrap_or_default()),
+        JsonValue::String(str_val) => Kind::StringValue(str_val),
+        JsonValue::Array(arr) => Kind::ListValue(json_to_list_value(arr)),
+        JsonValue::Object(map) => Kind::StructValue(json_map_to_struct(map)),
+    };
+    Value { kind: Some(kind) }
+}
+
+/// Deserializes protobuf Struct into arbitrary user defined type.
+pub fn struct_to_type<T: DeserializeOwned>(proto_struct: &Struct) -> Result<T> {
+    Ok(serde_json::from_value(struct_to_json(proto_struct))?)
+}
+
+/// Serializes arbitrary user defined type into protobuf Struct.
+/// Returns error if type is not serialized into JSON object.
+pub fn type_to_struct<T: Serialize>(data: &T) -> Result<Struct> {
+    json_to_struct(serde_json::to_value(data)?)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use serde::Deserialize;
+
+    // cargo test -- --show-output test_json_struct_roundtrip
+    #[test]
+    fn test_json_struct_roundtrip() {
+        let json_val: JsonValue = serde_json::from_str(
+            r#"
+            {
+                "hangup_timeout": 20000,
+                "ratio": 0.25,
+                "foo": "bar",
+                "flag": true,
+                "nothing": null,
+                "list": [1, "two", [3.5], {"four": 4}],
+                "bar": {
+                    "barbar": "foobar"
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let proto_struct = json_to_struct(json_val.clone()).unwrap();
+        assert_eq!(
+            proto_struct.fields.get("hangup_timeout").unwrap().kind,
+            Some(Kind::NumberValue(20000.0))
+        );
+        assert_eq!(struct_to_json(&proto_struct), json_val);
+    }
+
+    // cargo test -- --show-output test_json_to_struct_not_object
+    #[test]
+    fn test_json_to_struct_not_object() {
+        assert!(json_to_struct(JsonValue::String("foo".to_string())).is_err());
+        assert!(json_to_struct(serde_json::json!([1, 2])).is_err());
+    }
+
+    // cargo test -- --show-output test_non_finite_number
+    #[test]
+    fn test_non_finite_number() {
+        let value = Value {
+            kind: Some(Kind::NumberValue(f64::NAN)),
+        };
+        assert_eq!(value_to_json(&value), JsonValue::Null);
+        let value = Value { kind: None };
+        assert_eq!(value_to_json(&value), JsonValue::Null);
+    }
+
+    // cargo test -- --show-output test_struct_to_type
+    #[test]
+    fn test_struct_to_type() {
+        #[derive(Debug, Deserialize, Serialize, PartialEq)]
+        struct Params {
+            hangup_timeout: u32,
+            hangup_play_msg: String,
+            tags: Vec<String>,
+        }
+
+        let params = Params {
+            hangup_timeout: 20000,
+            hangup_play_msg: "1".to_string(),
+            tags: vec!["a".to_string(), "b".to_string()],
+        };
+
+        let proto_struct = type_to_struct(&params).unwrap();
+        let params2: Params = struct_to_type(&proto_struct).unwrap();
+        assert_eq!(params, params2);
+
+        assert!(type_to_struct(&"not an object").is_err());
+    }
+}
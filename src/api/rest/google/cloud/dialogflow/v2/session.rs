@@ -1,4 +1,5 @@
 use crate::errors::{Error, Result};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::Value as JsonData;
 use std::collections::HashMap;
@@ -24,6 +25,19 @@ pub struct DetectIntentResponse {
     pub output_audio_config: Option<OutputAudioConfig>,
 }
 
+impl DetectIntentResponse {
+    /// Returns query result parameters deserialized into arbitrary user defined type.
+    /// Returns None if response does not contain any parameters.
+    pub fn parameters<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        if let Some(query_result) = &self.query_result {
+            if let Some(parameters) = &query_result.parameters {
+                return Ok(Some(serde_json::from_value(parameters.clone())?));
+            }
+        }
+        Ok(None)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct QueryResult {
     pub action: Option<String>,
@@ -196,4 +210,33 @@ mod tests {
         assert_eq!(params_map.get("foo").unwrap(), "bar");
         assert_eq!(params_map.get("bar").unwrap(), "{\"barbar\":\"foobar\"}");
     }
+
+    // cargo test -- --show-output test_detect_intent_resp_typed_params
+    #[test]
+    fn test_detect_intent_resp_typed_params() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Params {
+            hangup_timeout: String,
+            bar: std::collections::HashMap<String, String>,
+        }
+
+        let json_str = r#"
+            {
+              "responseId": "721c1b2a-0403-4124-aa4f-b63773ef5cc1-123456",
+              "queryResult": {
+                "parameters": {
+                  "hangup_timeout": "20000",
+                  "bar": {
+                    "barbar": "foobar"
+                  }
+                }
+              }
+            }
+            "#;
+
+        let detect_intent_resp = super::deserialize_detect_intent_response(json_str).unwrap();
+        let params: Params = detect_intent_resp.parameters().unwrap().unwrap();
+        assert_eq!(params.hangup_timeout, "20000");
+        assert_eq!(params.bar.get("barbar").unwrap(), "foobar");
+    }
 }
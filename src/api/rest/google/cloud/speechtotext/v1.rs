@@ -338,10 +338,7 @@ impl Into<GrpcRecognitionConfig> for RecognitionConfig {
                 _ => None,
             },
             model: self.model.to_string(),
-            use_enhanced: match self.use_enhanced {
-                Some(val) => val,
-                _ => false,
-            },
+            use_enhanced: self.use_enhanced.unwrap_or_default(),
         }
     }
 }
@@ -149,10 +149,7 @@ impl Into<GrpcRecognitionConfig> for RecognitionConfig {
             // So far not supported!
             metadata: None,
             model: self.model.to_string(),
-            use_enhanced: match self.use_enhanced {
-                Some(val) => val,
-                _ => false,
-            },
+            use_enhanced: self.use_enhanced.unwrap_or_default(),
             adaptation: match self.adaptation {
                 Some(adpt) => Some(adpt.into()),
                 _ => None,
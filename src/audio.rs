@@ -0,0 +1,70 @@
+//! Contains audio helpers used to connect telephony (SIP/RTP) audio with
+//! speech-to-text, dialogflow and text-to-speech APIs.
+
+/// G.711 μ-law/A-law codecs
+pub mod g711;
+
+/// LINEAR16 resampling
+pub mod resample;
+
+/// RTP packetization
+pub mod rtp;
+
+/// Voice activity detection
+pub mod vad;
+
+/// WAV header parsing
+pub mod wav;
+
+/// Converts little-endian LINEAR16 bytes into 16 bit samples.
+/// Trailing odd byte (if any) is ignored.
+pub fn linear16_from_bytes(bytes: &[u8]) -> Vec<i16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+/// Converts 16 bit samples into little-endian LINEAR16 bytes.
+pub fn linear16_to_bytes(samples: &[i16]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect()
+}
+
+/// Splits interleaved multi-channel samples into separate mono channels.
+/// Incomplete trailing frame (if any) is ignored.
+pub fn split_channels(samples: &[i16], channels: usize) -> Vec<Vec<i16>> {
+    let mut split = vec![Vec::with_capacity(samples.len() / channels.max(1)); channels];
+    for frame in samples.chunks_exact(channels.max(1)) {
+        for (channel, sample) in frame.iter().enumerate() {
+            if let Some(mono) = split.get_mut(channel) {
+                mono.push(*sample);
+            }
+        }
+    }
+    split
+}
+
+/// Converts text-to-speech LINEAR16 output (with or without WAV header)
+/// of given sample rate into 8kHz μ-law audio ready for RTP packetization.
+pub fn linear16_to_ulaw_8k(
+    audio_content: &[u8],
+    sample_rate_hertz: u32,
+) -> crate::errors::Result<Vec<u8>> {
+    let samples = linear16_from_bytes(wav::strip_wav_header(audio_content));
+    let samples = resample::resample(&samples, sample_rate_hertz, 8000)?;
+    Ok(g711::ulaw_encode(&samples))
+}
+
+/// Converts text-to-speech LINEAR16 output (with or without WAV header)
+/// of given sample rate into 8kHz A-law audio ready for RTP packetization.
+pub fn linear16_to_alaw_8k(
+    audio_content: &[u8],
+    sample_rate_hertz: u32,
+) -> crate::errors::Result<Vec<u8>> {
+    let samples = linear16_from_bytes(wav::strip_wav_header(audio_content));
+    let samples = resample::resample(&samples, sample_rate_hertz, 8000)?;
+    Ok(g711::alaw_encode(&samples))
+}
@@ -0,0 +1,167 @@
+//! G.711 μ-law (PCMU) and A-law (PCMA) codecs. Each 8 bit
+//! G.711 sample is expanded into one 16 bit LINEAR16 sample and vice versa.
+
+const ULAW_BIAS: i32 = 0x84;
+const ULAW_CLIP: i32 = 32635;
+
+/// A-law segment end points
+const ALAW_SEG_END: [i32; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+
+/// Encodes single 16 bit linear sample into μ-law.
+pub fn linear_to_ulaw(sample: i16) -> u8 {
+    let mut pcm = sample as i32;
+    let sign = if pcm < 0 {
+        pcm = -pcm;
+        0x80
+    } else {
+        0x00
+    };
+    if pcm > ULAW_CLIP {
+        pcm = ULAW_CLIP;
+    }
+    pcm += ULAW_BIAS;
+
+    let mut exponent = 7;
+    let mut mask = 0x4000;
+    while pcm & mask == 0 && exponent > 0 {
+        exponent -= 1;
+        mask >>= 1;
+    }
+    let mantissa = (pcm >> (exponent + 3)) & 0x0F;
+    !((sign | (exponent << 4) | mantissa) as u8)
+}
+
+/// Decodes single μ-law sample into 16 bit linear sample.
+pub fn ulaw_to_linear(ulaw: u8) -> i16 {
+    let ulaw = !ulaw as i32;
+    let exponent = (ulaw >> 4) & 0x07;
+    let mantissa = ulaw & 0x0F;
+    let sample = (((mantissa << 3) + ULAW_BIAS) << exponent) - ULAW_BIAS;
+    if ulaw & 0x80 != 0 {
+        -sample as i16
+    } else {
+        sample as i16
+    }
+}
+
+/// Encodes single 16 bit linear sample into A-law.
+pub fn linear_to_alaw(sample: i16) -> u8 {
+    let mut pcm = (sample as i32) >> 3;
+    let mask = if pcm >= 0 {
+        0xD5
+    } else {
+        pcm = -pcm - 1;
+        0x55
+    };
+
+    match ALAW_SEG_END.iter().position(|seg_end| pcm <= *seg_end) {
+        None => (0x7F ^ mask) as u8,
+        Some(seg) => {
+            let mut aval = (seg as i32) << 4;
+            if seg < 2 {
+                aval |= (pcm >> 1) & 0x0F;
+            } else {
+                aval |= (pcm >> seg) & 0x0F;
+            }
+            (aval ^ mask) as u8
+        }
+    }
+}
+
+/// Decodes single A-law sample into 16 bit linear sample.
+pub fn alaw_to_linear(alaw: u8) -> i16 {
+    let alaw = (alaw ^ 0x55) as i32;
+    let mut t = (alaw & 0x0F) << 4;
+    let seg = (alaw & 0x70) >> 4;
+    match seg {
+        0 => t += 8,
+        1 => t += 0x108,
+        _ => {
+            t += 0x108;
+            t <<= seg - 1;
+        }
+    }
+    if alaw & 0x80 != 0 {
+        t as i16
+    } else {
+        -t as i16
+    }
+}
+
+/// Encodes 16 bit linear samples into μ-law.
+pub fn ulaw_encode(samples: &[i16]) -> Vec<u8> {
+    samples
+        .iter()
+        .map(|sample| linear_to_ulaw(*sample))
+        .collect()
+}
+
+/// Decodes μ-law audio into 16 bit linear samples.
+pub fn ulaw_decode(ulaw: &[u8]) -> Vec<i16> {
+    ulaw.iter().map(|sample| ulaw_to_linear(*sample)).collect()
+}
+
+/// Encodes 16 bit linear samples into A-law.
+pub fn alaw_encode(samples: &[i16]) -> Vec<u8> {
+    samples
+        .iter()
+        .map(|sample| linear_to_alaw(*sample))
+        .collect()
+}
+
+/// Decodes A-law audio into 16 bit linear samples.
+pub fn alaw_decode(alaw: &[u8]) -> Vec<i16> {
+    alaw.iter().map(|sample| alaw_to_linear(*sample)).collect()
+}
+
+/// Decodes μ-law audio (e.g. RTP PCMU payload) into LINEAR16 bytes
+/// which can be sent directly to recognizer or dialogflow audio sink.
+pub fn ulaw_to_linear16_bytes(ulaw: &[u8]) -> Vec<u8> {
+    super::linear16_to_bytes(&ulaw_decode(ulaw))
+}
+
+/// Decodes A-law audio (e.g. RTP PCMA payload) into LINEAR16 bytes
+/// which can be sent directly to recognizer or dialogflow audio sink.
+pub fn alaw_to_linear16_bytes(alaw: &[u8]) -> Vec<u8> {
+    super::linear16_to_bytes(&alaw_decode(alaw))
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_g711_known_values
+    #[test]
+    fn test_g711_known_values() {
+        // silence
+        assert_eq!(linear_to_ulaw(0), 0xFF);
+        assert_eq!(linear_to_alaw(0), 0xD5);
+        assert_eq!(ulaw_to_linear(0xFF), 0);
+        assert_eq!(alaw_to_linear(0xD5), 8);
+        // clipping
+        assert_eq!(linear_to_ulaw(i16::MAX), 0x80);
+        assert_eq!(linear_to_ulaw(i16::MIN), 0x00);
+        assert_eq!(linear_to_alaw(i16::MAX), 0xAA);
+        assert_eq!(linear_to_alaw(i16::MIN), 0x2A);
+    }
+
+    // cargo test -- --show-output test_g711_roundtrip
+    #[test]
+    fn test_g711_roundtrip() {
+        // every G.711 code word must survive decode/encode roundtrip
+        for code in 0..=255u8 {
+            assert_eq!(linear_to_alaw(alaw_to_linear(code)), code);
+            // μ-law has two code words for zero (0x7F, 0xFF)
+            if code != 0x7F {
+                assert_eq!(linear_to_ulaw(ulaw_to_linear(code)), code);
+            }
+        }
+        // quantization error is bounded
+        for sample in (-32000i16..32000).step_by(97) {
+            let ulaw_err = (ulaw_to_linear(linear_to_ulaw(sample)) as i32 - sample as i32).abs();
+            let alaw_err = (alaw_to_linear(linear_to_alaw(sample)) as i32 - sample as i32).abs();
+            assert!(ulaw_err <= 1024, "ulaw {} err {}", sample, ulaw_err);
+            assert!(alaw_err <= 1024, "alaw {} err {}", sample, alaw_err);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! LINEAR16 resampling between sample rates used by telephony and
+//! Google APIs (8, 16, 24 and 48 kHz). Upsampling uses linear interpolation,
+//! downsampling averages input samples covered by each output sample
+//! (simple low-pass filter preventing the worst aliasing).
+use crate::errors::{Error, Result};
+
+/// Sample rates supported by resample.
+pub const SUPPORTED_SAMPLE_RATES: [u32; 4] = [8000, 16000, 24000, 48000];
+
+/// Resamples mono 16 bit samples from one sample rate into another.
+pub fn resample(samples: &[i16], from_hertz: u32, to_hertz: u32) -> Result<Vec<i16>> {
+    for rate in [from_hertz, to_hertz] {
+        if !SUPPORTED_SAMPLE_RATES.contains(&rate) {
+            return Err(Error::new(format!(
+                "resample: unsupported sample rate {}, supported rates are {:?}",
+                rate, SUPPORTED_SAMPLE_RATES
+            )));
+        }
+    }
+
+    if from_hertz == to_hertz || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let out_len = (samples.len() as u64 * to_hertz as u64 / from_hertz as u64) as usize;
+    let ratio = from_hertz as f64 / to_hertz as f64;
+    let mut output = Vec::with_capacity(out_len);
+
+    if to_hertz > from_hertz {
+        for i in 0..out_len {
+            let pos = i as f64 * ratio;
+            let idx = pos.floor() as usize;
+            let frac = pos - idx as f64;
+            let current = samples[idx] as f64;
+            let next = *samples.get(idx + 1).unwrap_or(&samples[idx]) as f64;
+            output.push((current + (next - current) * frac).round() as i16);
+        }
+    } else {
+        for i in 0..out_len {
+            let start = (i as f64 * ratio).floor() as usize;
+            let end = (((i + 1) as f64 * ratio).floor() as usize).clamp(start + 1, samples.len());
+            let sum: i64 = samples[start..end].iter().map(|s| *s as i64).sum();
+            output.push((sum / (end - start) as i64) as i16);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Resamples mono LINEAR16 (little-endian) bytes from one sample rate into another.
+pub fn resample_bytes(audio: &[u8], from_hertz: u32, to_hertz: u32) -> Result<Vec<u8>> {
+    let samples = super::linear16_from_bytes(audio);
+    Ok(super::linear16_to_bytes(&resample(
+        &samples, from_hertz, to_hertz,
+    )?))
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_resample
+    #[test]
+    fn test_resample() {
+        let samples: Vec<i16> = (0..480).map(|i| (i * 10) as i16).collect();
+
+        let up = resample(&samples, 8000, 48000).unwrap();
+        assert_eq!(up.len(), 2880);
+        assert_eq!(up[0], 0);
+        assert_eq!(up[3], 5);
+        assert_eq!(up[6], 10);
+
+        let down = resample(&up, 48000, 8000).unwrap();
+        assert_eq!(down.len(), 480);
+
+        let down = resample(&samples, 24000, 16000).unwrap();
+        assert_eq!(down.len(), 320);
+
+        assert_eq!(resample(&samples, 16000, 16000).unwrap(), samples);
+        assert!(resample(&samples, 44100, 16000).is_err());
+    }
+}
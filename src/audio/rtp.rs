@@ -0,0 +1,226 @@
+//! RTP packetization of G.711 audio. Audio is split into 20 ms frames
+//! (160 bytes for 8kHz G.711) and wrapped into RTP packets (RFC 3550)
+//! with static payload types PCMU (0) or PCMA (8).
+use super::g711::{linear_to_alaw, linear_to_ulaw};
+use crate::errors::{Error, Result};
+
+/// Frame duration used for RTP packetization.
+pub const FRAME_DURATION_MS: u32 = 20;
+
+/// Size of RTP header without CSRC identifiers and extensions.
+pub const RTP_HEADER_SIZE: usize = 12;
+
+/// Codec of RTP payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtpCodec {
+    /// G.711 μ-law
+    Pcmu,
+    /// G.711 A-law
+    Pcma,
+}
+
+impl RtpCodec {
+    /// Static RTP payload type of the codec.
+    pub fn payload_type(&self) -> u8 {
+        match self {
+            RtpCodec::Pcmu => 0,
+            RtpCodec::Pcma => 8,
+        }
+    }
+
+    /// Sample rate of the codec.
+    pub fn sample_rate(&self) -> u32 {
+        8000
+    }
+
+    /// Encoded silence of the codec, used for padding of the last frame.
+    pub fn silence(&self) -> u8 {
+        match self {
+            RtpCodec::Pcmu => linear_to_ulaw(0),
+            RtpCodec::Pcma => linear_to_alaw(0),
+        }
+    }
+
+    /// Number of bytes (samples) in single 20 ms frame.
+    pub fn frame_size(&self) -> usize {
+        (self.sample_rate() * FRAME_DURATION_MS / 1000) as usize
+    }
+}
+
+/// Returns number of bytes in 20 ms frame of LINEAR16 mono audio with given sample rate.
+pub fn linear16_frame_size(sample_rate_hertz: u32) -> usize {
+    (sample_rate_hertz * FRAME_DURATION_MS / 1000) as usize * 2
+}
+
+/// Splits audio into 20 ms frames of provided size. The last
+/// frame is shorter if audio length is not multiple of frame size.
+pub fn frames(audio: &[u8], frame_size: usize) -> impl Iterator<Item = &[u8]> {
+    audio.chunks(frame_size)
+}
+
+/// Fixed part of parsed RTP packet header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtpHeader {
+    pub marker: bool,
+    pub payload_type: u8,
+    pub sequence_number: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+}
+
+/// Parses RTP packet and returns its header and payload
+/// (CSRC identifiers, header extension and padding are skipped).
+pub fn parse_rtp_packet(packet: &[u8]) -> Result<(RtpHeader, &[u8])> {
+    if packet.len() < RTP_HEADER_SIZE || packet[0] >> 6 != 2 {
+        return Err(Error::new(
+            "parse_rtp_packet: invalid RTP packet".to_string(),
+        ));
+    }
+    let padding = packet[0] & 0x20 != 0;
+    let extension = packet[0] & 0x10 != 0;
+    let csrc_count = (packet[0] & 0x0F) as usize;
+
+    let mut start = RTP_HEADER_SIZE + csrc_count * 4;
+    if extension {
+        if packet.len() < start + 4 {
+            return Err(Error::new(
+                "parse_rtp_packet: truncated extension".to_string(),
+            ));
+        }
+        let ext_len = u16::from_be_bytes([packet[start + 2], packet[start + 3]]) as usize;
+        start += 4 + ext_len * 4;
+    }
+    let mut end = packet.len();
+    if padding && end > 0 {
+        end = end.saturating_sub(packet[end - 1] as usize);
+    }
+    if start > end {
+        return Err(Error::new("parse_rtp_packet: truncated packet".to_string()));
+    }
+
+    let header = RtpHeader {
+        marker: packet[1] & 0x80 != 0,
+        payload_type: packet[1] & 0x7F,
+        sequence_number: u16::from_be_bytes([packet[2], packet[3]]),
+        timestamp: u32::from_be_bytes([packet[4], packet[5], packet[6], packet[7]]),
+        ssrc: u32::from_be_bytes([packet[8], packet[9], packet[10], packet[11]]),
+    };
+    Ok((header, &packet[start..end]))
+}
+
+/// Creates RTP packets from continuous G.711 audio stream. Audio
+/// not filling complete 20 ms frame is buffered until next call of packetize
+/// or until flush is called.
+#[derive(Debug, Clone)]
+pub struct RtpPacketizer {
+    codec: RtpCodec,
+    ssrc: u32,
+    sequence_number: u16,
+    timestamp: u32,
+    /// marker bit is set on the first packet of talkspurt
+    marker: bool,
+    buffer: Vec<u8>,
+}
+
+impl RtpPacketizer {
+    /// Creates new packetizer. Initial sequence number and timestamp should be random (RFC 3550).
+    pub fn new(codec: RtpCodec, ssrc: u32, sequence_number: u16, timestamp: u32) -> Self {
+        RtpPacketizer {
+            codec,
+            ssrc,
+            sequence_number,
+            timestamp,
+            marker: true,
+            buffer: vec![],
+        }
+    }
+
+    /// Marks start of new talkspurt, e.g. when new text-to-speech response is played.
+    pub fn start_talkspurt(&mut self) {
+        self.marker = true;
+    }
+
+    /// Advances timestamp by given number of samples without
+    /// sending any packet (e.g. silence suppression).
+    pub fn skip_samples(&mut self, samples: u32) {
+        self.timestamp = self.timestamp.wrapping_add(samples);
+    }
+
+    /// Packetizes encoded (μ-law or A-law as per codec) audio
+    /// into RTP packets of 20 ms.
+    pub fn packetize(&mut self, audio: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(audio);
+        let frame_size = self.codec.frame_size();
+        let complete = self.buffer.len() / frame_size * frame_size;
+        let frames: Vec<u8> = self.buffer.drain(..complete).collect();
+        frames
+            .chunks(frame_size)
+            .map(|frame| self.packet(frame))
+            .collect()
+    }
+
+    /// Packetizes remaining buffered audio padding it with silence into full 20 ms frame.
+    pub fn flush(&mut self) -> Option<Vec<u8>> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let mut frame = std::mem::take(&mut self.buffer);
+        frame.resize(self.codec.frame_size(), self.codec.silence());
+        Some(self.packet(&frame))
+    }
+
+    fn packet(&mut self, payload: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(RTP_HEADER_SIZE + payload.len());
+        packet.push(0x80); // version 2, no padding, no extension, no CSRC
+        let marker = if self.marker { 0x80 } else { 0x00 };
+        packet.push(marker | self.codec.payload_type());
+        packet.extend_from_slice(&self.sequence_number.to_be_bytes());
+        packet.extend_from_slice(&self.timestamp.to_be_bytes());
+        packet.extend_from_slice(&self.ssrc.to_be_bytes());
+        packet.extend_from_slice(payload);
+
+        self.marker = false;
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+        self.timestamp = self.timestamp.wrapping_add(payload.len() as u32);
+        packet
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_rtp_packetizer
+    #[test]
+    fn test_rtp_packetizer() {
+        let mut packetizer = RtpPacketizer::new(RtpCodec::Pcmu, 0x1234, u16::MAX, 1000);
+        let packets = packetizer.packetize(&[0x11; 400]);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].len(), RTP_HEADER_SIZE + 160);
+
+        let (header, payload) = parse_rtp_packet(&packets[0]).unwrap();
+        assert_eq!(
+            header,
+            RtpHeader {
+                marker: true,
+                payload_type: 0,
+                sequence_number: u16::MAX,
+                timestamp: 1000,
+                ssrc: 0x1234,
+            }
+        );
+        assert_eq!(payload, &[0x11; 160][..]);
+
+        let (header, _) = parse_rtp_packet(&packets[1]).unwrap();
+        assert!(!header.marker);
+        assert_eq!(header.sequence_number, 0);
+        assert_eq!(header.timestamp, 1160);
+
+        let last = packetizer.flush().unwrap();
+        let (header, payload) = parse_rtp_packet(&last).unwrap();
+        assert_eq!(header.timestamp, 1320);
+        assert_eq!(&payload[..80], &[0x11; 80][..]);
+        assert_eq!(&payload[80..], &[0xFF; 80][..]);
+        assert!(packetizer.flush().is_none());
+    }
+}
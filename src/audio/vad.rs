@@ -0,0 +1,486 @@
+//! Local voice activity detection (VAD) and endpointing. VAD stage placed
+//! in front of recognizer/dialogflow audio sink suppresses long silences
+//! (which are otherwise billed as any other streamed audio), emits speech start/end
+//! events and can close the audio sink after configurable trailing silence
+//! (emulating single_utterance for APIs which do not support it).
+//! Streaming APIs abort the stream when no audio is received for several seconds,
+//! during long silences single silence frame is therefore forwarded every keep_alive_ms.
+//!
+//! Audio is expected to be mono LINEAR16 (little-endian). Speech detection itself
+//! is pluggable via VoiceActivityDetector trait, EnergyDetector is provided out of the box.
+use super::linear16_from_bytes;
+use crate::errors::{Error, Result};
+use log::*;
+use tokio::sync::mpsc;
+
+/// Decides whether single audio frame contains speech.
+pub trait VoiceActivityDetector: Send {
+    /// Returns true if frame (mono 16 bit samples) contains speech.
+    fn is_speech(&mut self, frame: &[i16]) -> bool;
+}
+
+/// Simple energy based detector. Frame is considered to be speech when its
+/// RMS level is above fixed threshold or (if adaptive) sufficiently above
+/// estimated level of background noise.
+#[derive(Debug, Clone)]
+pub struct EnergyDetector {
+    /// minimal RMS level (dBFS) of speech frame, e.g. -45.0
+    threshold_dbfs: f32,
+
+    /// if set, frame must also be this many dB above estimated noise floor
+    noise_margin_db: Option<f32>,
+
+    /// estimated noise floor (dBFS)
+    noise_floor_dbfs: f32,
+}
+
+impl EnergyDetector {
+    /// Creates detector with fixed threshold.
+    pub fn new(threshold_dbfs: f32) -> Self {
+        EnergyDetector {
+            threshold_dbfs,
+            noise_margin_db: None,
+            noise_floor_dbfs: threshold_dbfs,
+        }
+    }
+
+    /// Creates detector which tracks background noise level and requires
+    /// speech frames to be at least noise_margin_db above it.
+    pub fn adaptive(threshold_dbfs: f32, noise_margin_db: f32) -> Self {
+        EnergyDetector {
+            threshold_dbfs,
+            noise_margin_db: Some(noise_margin_db),
+            noise_floor_dbfs: threshold_dbfs - noise_margin_db,
+        }
+    }
+
+    /// Returns RMS level of the frame in dBFS. Digital silence returns -100 dBFS.
+    pub fn frame_dbfs(frame: &[i16]) -> f32 {
+        if frame.is_empty() {
+            return -100.0;
+        }
+        let sum: f64 = frame.iter().map(|s| (*s as f64) * (*s as f64)).sum();
+        let rms = (sum / frame.len() as f64).sqrt();
+        if rms < 1.0 {
+            return -100.0;
+        }
+        (20.0 * (rms / 32768.0).log10()) as f32
+    }
+}
+
+impl Default for EnergyDetector {
+    fn default() -> Self {
+        EnergyDetector::new(-45.0)
+    }
+}
+
+impl VoiceActivityDetector for EnergyDetector {
+    fn is_speech(&mut self, frame: &[i16]) -> bool {
+        let level = EnergyDetector::frame_dbfs(frame);
+        match self.noise_margin_db {
+            None => level >= self.threshold_dbfs,
+            Some(margin) => {
+                let is_speech =
+                    level >= self.threshold_dbfs && level >= self.noise_floor_dbfs + margin;
+                if !is_speech {
+                    // noise floor follows quieter frames fast and louder frames slowly
+                    let alpha = if level < self.noise_floor_dbfs {
+                        0.5
+                    } else {
+                        0.05
+                    };
+                    self.noise_floor_dbfs += alpha * (level - self.noise_floor_dbfs);
+                }
+                is_speech
+            }
+        }
+    }
+}
+
+/// Events emitted by VAD. Offsets are measured from the beginning of the audio stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VadEvent {
+    /// Speech detected. Offset points to the first speech frame.
+    SpeechStart { offset_ms: u64 },
+
+    /// Speech ended. Offset points to the end of the last speech frame.
+    SpeechEnd { offset_ms: u64 },
+
+    /// Trailing silence exceeded close_after_silence_ms, audio sink should be closed.
+    EndOfUtterance { offset_ms: u64 },
+}
+
+/// Supported durations of analyzed frames (ms).
+pub const SUPPORTED_FRAME_MS: [u32; 3] = [10, 20, 30];
+
+/// VAD configuration.
+#[derive(Debug, Clone)]
+pub struct VadConfig {
+    /// Sample rate of the audio.
+    pub sample_rate_hertz: u32,
+
+    /// Duration of analyzed frames (10, 20 or 30 ms).
+    pub frame_ms: u32,
+
+    /// Speech must last at least this long to emit SpeechStart.
+    pub speech_start_ms: u32,
+
+    /// Silence must last at least this long to emit SpeechEnd. Audio
+    /// is still forwarded during this period so that trailing phonemes are not cut.
+    pub speech_end_ms: u32,
+
+    /// Audio preceding speech start which is forwarded together with the speech.
+    pub pre_roll_ms: u32,
+
+    /// If set, EndOfUtterance is emitted (and sink closed) when silence
+    /// following the speech lasts this long.
+    pub close_after_silence_ms: Option<u32>,
+
+    /// If set, single silence frame is forwarded after this long period of suppressed
+    /// audio so that streaming API does not abort the stream with audio timeout error
+    /// (defaults to 1000 ms). None disables keep-alive frames.
+    pub keep_alive_ms: Option<u32>,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        VadConfig {
+            sample_rate_hertz: 16000,
+            frame_ms: 20,
+            speech_start_ms: 60,
+            speech_end_ms: 600,
+            pre_roll_ms: 300,
+            close_after_silence_ms: None,
+            keep_alive_ms: Some(1000),
+        }
+    }
+}
+
+impl VadConfig {
+    /// Checks that frame duration is supported and frame contains at least one sample.
+    pub fn validate(&self) -> Result<()> {
+        if !SUPPORTED_FRAME_MS.contains(&self.frame_ms) {
+            return Err(Error::new(format!(
+                "VadConfig: unsupported frame_ms {}, supported values are {:?}",
+                self.frame_ms, SUPPORTED_FRAME_MS
+            )));
+        }
+        if self.sample_rate_hertz as u64 * (self.frame_ms as u64) < 1000 {
+            return Err(Error::new(format!(
+                "VadConfig: sample_rate_hertz {} too low",
+                self.sample_rate_hertz
+            )));
+        }
+        if self.keep_alive_ms == Some(0) {
+            return Err(Error::new(
+                "VadConfig: keep_alive_ms must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Result of processing audio chunk by Vad.
+#[derive(Debug, Default)]
+pub struct VadOutput {
+    /// Audio to be forwarded into the audio sink (silence removed).
+    pub audio: Vec<u8>,
+
+    /// Events detected in processed audio.
+    pub events: Vec<VadEvent>,
+
+    /// True if audio sink should be closed (EndOfUtterance detected).
+    pub close: bool,
+}
+
+/// Voice activity detection state machine. Audio of arbitrary chunk size is
+/// split into frames, frames are classified by detector and forwarded
+/// only while speech (including pre-roll and hangover) is in progress.
+pub struct Vad {
+    config: VadConfig,
+    detector: Box<dyn VoiceActivityDetector>,
+    /// incomplete frame carried over to next process call
+    pending: Vec<u8>,
+    /// recent non-forwarded frames kept as pre-roll
+    pre_roll: Vec<Vec<u8>>,
+    in_speech: bool,
+    /// number of consecutive speech/silence frames
+    speech_frames: u32,
+    silence_frames: u32,
+    /// number of consecutive frames which were not forwarded
+    suppressed_frames: u32,
+    /// true once speech was detected at least once
+    heard_speech: bool,
+    closed: bool,
+    processed_frames: u64,
+}
+
+impl Vad {
+    /// Creates VAD with provided detector. Returns error if config is not valid.
+    pub fn new(config: VadConfig, detector: impl VoiceActivityDetector + 'static) -> Result<Self> {
+        config.validate()?;
+        Ok(Vad {
+            config,
+            detector: Box::new(detector),
+            pending: vec![],
+            pre_roll: vec![],
+            in_speech: false,
+            speech_frames: 0,
+            silence_frames: 0,
+            suppressed_frames: 0,
+            heard_speech: false,
+            closed: false,
+            processed_frames: 0,
+        })
+    }
+
+    /// Creates VAD with default energy detector. Returns error if config is not valid.
+    pub fn with_energy_detector(config: VadConfig) -> Result<Self> {
+        Vad::new(config, EnergyDetector::default())
+    }
+
+    fn frame_bytes(&self) -> usize {
+        (self.config.sample_rate_hertz * self.config.frame_ms / 1000) as usize * 2
+    }
+
+    fn frames_for(&self, ms: u32) -> u32 {
+        ms.div_ceil(self.config.frame_ms)
+    }
+
+    fn offset_ms(&self, frames: u64) -> u64 {
+        frames * self.config.frame_ms as u64
+    }
+
+    /// Returns true if speech is currently in progress.
+    pub fn in_speech(&self) -> bool {
+        self.in_speech
+    }
+
+    /// Processes chunk of LINEAR16 audio.
+    pub fn process(&mut self, audio: &[u8]) -> VadOutput {
+        let mut output = VadOutput::default();
+        if self.closed {
+            output.close = true;
+            return output;
+        }
+
+        self.pending.extend_from_slice(audio);
+        let frame_bytes = self.frame_bytes();
+        let complete = self.pending.len() / frame_bytes * frame_bytes;
+        let frames: Vec<u8> = self.pending.drain(..complete).collect();
+
+        for frame in frames.chunks(frame_bytes) {
+            self.process_frame(frame, &mut output);
+            if output.close {
+                break;
+            }
+        }
+        output
+    }
+
+    fn process_frame(&mut self, frame: &[u8], output: &mut VadOutput) {
+        let is_speech = self.detector.is_speech(&linear16_from_bytes(frame));
+        self.processed_frames += 1;
+
+        if is_speech {
+            self.speech_frames += 1;
+            self.silence_frames = 0;
+        } else {
+            self.silence_frames += 1;
+            self.speech_frames = 0;
+        }
+
+        if self.in_speech {
+            output.audio.extend_from_slice(frame);
+            self.suppressed_frames = 0;
+            if self.silence_frames >= self.frames_for(self.config.speech_end_ms) {
+                self.in_speech = false;
+                let offset_ms = self.offset_ms(self.processed_frames - self.silence_frames as u64);
+                output.events.push(VadEvent::SpeechEnd { offset_ms });
+            }
+        } else {
+            self.pre_roll.push(frame.to_vec());
+            let start_frames = self.frames_for(self.config.speech_start_ms).max(1);
+            if self.speech_frames >= start_frames {
+                self.in_speech = true;
+                self.heard_speech = true;
+                let offset_ms = self.offset_ms(self.processed_frames - self.speech_frames as u64);
+                output.events.push(VadEvent::SpeechStart { offset_ms });
+                for frame in self.pre_roll.drain(..) {
+                    output.audio.extend_from_slice(&frame);
+                }
+                self.suppressed_frames = 0;
+            } else {
+                // pre-roll plus speech frames preceding speech start
+                let max_pre_roll =
+                    (self.frames_for(self.config.pre_roll_ms) + start_frames - 1) as usize;
+                if self.pre_roll.len() > max_pre_roll {
+                    let excess = self.pre_roll.len() - max_pre_roll;
+                    self.pre_roll.drain(..excess);
+                }
+                self.suppressed_frames += 1;
+                if let Some(keep_alive_ms) = self.config.keep_alive_ms {
+                    if self.suppressed_frames >= self.frames_for(keep_alive_ms) {
+                        // keep-alive frame, pre-roll is cleared so that audio is not forwarded twice
+                        output.audio.extend_from_slice(frame);
+                        self.pre_roll.clear();
+                        self.suppressed_frames = 0;
+                    }
+                }
+            }
+        }
+
+        if let Some(close_after_ms) = self.config.close_after_silence_ms {
+            if self.heard_speech && self.silence_frames >= self.frames_for(close_after_ms) {
+                self.closed = true;
+                output.close = true;
+                output.events.push(VadEvent::EndOfUtterance {
+                    offset_ms: self.offset_ms(self.processed_frames),
+                });
+            }
+        }
+    }
+}
+
+/// Spawns VAD stage in front of provided audio sink (e.g. sink returned by
+/// Recognizer::take_audio_sink). Returned sender accepts raw LINEAR16 audio, voiced
+/// audio (and keep-alive frames) is converted into streaming requests by to_request
+/// and forwarded into the sink. VAD events are sent to events sender (if provided). When
+/// EndOfUtterance is detected the sink is dropped which half-closes the stream.
+pub fn spawn_vad_sink<T, F>(
+    mut vad: Vad,
+    audio_sink: mpsc::Sender<T>,
+    to_request: F,
+    events: Option<mpsc::Sender<VadEvent>>,
+) -> mpsc::Sender<Vec<u8>>
+where
+    T: Send + 'static,
+    F: Fn(Vec<u8>) -> T + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::channel::<Vec<u8>>(1000);
+
+    tokio::spawn(async move {
+        while let Some(audio) = receiver.recv().await {
+            let output = vad.process(&audio);
+
+            if let Some(events) = &events {
+                for event in output.events {
+                    let _ = events.send(event).await;
+                }
+            }
+
+            if !output.audio.is_empty() && audio_sink.send(to_request(output.audio)).await.is_err()
+            {
+                trace!("spawn_vad_sink: audio sink closed");
+                break;
+            }
+
+            if output.close {
+                trace!("spawn_vad_sink: end of utterance, closing audio sink");
+                break;
+            }
+        }
+    });
+
+    sender
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::audio::linear16_to_bytes;
+
+    #[allow(dead_code)]
+    fn frames(amplitude: i16, count: usize) -> Vec<u8> {
+        // 20 ms frames of 8kHz audio, square wave of given amplitude
+        let samples: Vec<i16> = (0..160 * count)
+            .map(|i| if i % 2 == 0 { amplitude } else { -amplitude })
+            .collect();
+        linear16_to_bytes(&samples)
+    }
+
+    // cargo test -- --show-output test_vad_events
+    #[test]
+    fn test_vad_events() {
+        let config = VadConfig {
+            sample_rate_hertz: 8000,
+            frame_ms: 20,
+            speech_start_ms: 40,
+            speech_end_ms: 100,
+            pre_roll_ms: 40,
+            close_after_silence_ms: Some(200),
+            keep_alive_ms: None,
+        };
+        let mut vad = Vad::with_energy_detector(config).unwrap();
+
+        let mut audio = frames(0, 10);
+        audio.extend(frames(5000, 10));
+        audio.extend(frames(0, 20));
+
+        // process in chunks not aligned to frames
+        let mut forwarded = 0;
+        let mut events = vec![];
+        let mut closed = false;
+        for chunk in audio.chunks(100) {
+            let output = vad.process(chunk);
+            forwarded += output.audio.len();
+            events.extend(output.events);
+            if output.close {
+                closed = true;
+                break;
+            }
+        }
+
+        assert_eq!(
+            events,
+            vec![
+                VadEvent::SpeechStart { offset_ms: 200 },
+                VadEvent::SpeechEnd { offset_ms: 400 },
+                VadEvent::EndOfUtterance { offset_ms: 600 },
+            ]
+        );
+        assert!(closed);
+        // 2 frames pre-roll, 10 speech frames, 5 hangover frames
+        assert_eq!(forwarded, 17 * 320);
+    }
+
+    // cargo test -- --show-output test_vad_keep_alive
+    #[test]
+    fn test_vad_keep_alive() {
+        let config = VadConfig {
+            sample_rate_hertz: 8000,
+            frame_ms: 20,
+            keep_alive_ms: Some(200),
+            ..Default::default()
+        };
+        let mut vad = Vad::with_energy_detector(config.clone()).unwrap();
+
+        // 2 seconds of silence, single frame forwarded every 200 ms
+        let output = vad.process(&frames(0, 100));
+        assert_eq!(output.audio.len(), 10 * 320);
+        assert!(output.events.is_empty());
+
+        let invalid_configs = vec![
+            VadConfig {
+                frame_ms: 0,
+                ..config.clone()
+            },
+            VadConfig {
+                frame_ms: 25,
+                ..config.clone()
+            },
+            VadConfig {
+                sample_rate_hertz: 0,
+                ..config.clone()
+            },
+            VadConfig {
+                keep_alive_ms: Some(0),
+                ..config
+            },
+        ];
+        for config in invalid_configs {
+            assert!(Vad::with_energy_detector(config).is_err());
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! WAV (RIFF) header parsing. Text-to-speech API returns LINEAR16
+//! audio content including WAV header which must be removed
+//! before the audio is sent to telephony media gateway.
+use crate::errors::{Error, Result};
+
+/// Format of WAV audio as specified in fmt chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WavFormat {
+    /// 1 = PCM (LINEAR16), 6 = A-law, 7 = μ-law
+    pub audio_format: u16,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([bytes[pos], bytes[pos + 1]])
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+}
+
+/// Returns true if provided bytes start with RIFF/WAVE header.
+pub fn is_wav(bytes: &[u8]) -> bool {
+    bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE"
+}
+
+/// Parses WAV file and returns its format and audio data (content of data chunk).
+pub fn parse_wav(bytes: &[u8]) -> Result<(WavFormat, &[u8])> {
+    if !is_wav(bytes) {
+        return Err(Error::new(
+            "parse_wav: missing RIFF/WAVE header".to_string(),
+        ));
+    }
+
+    let mut format: Option<WavFormat> = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let chunk_id = &bytes[pos..pos + 4];
+        let chunk_size = read_u32(bytes, pos + 4) as usize;
+        let chunk_start = pos + 8;
+        // streamed WAV files can have unknown (0 or 0xFFFFFFFF) data chunk size
+        let chunk_end = chunk_start.saturating_add(chunk_size).min(bytes.len());
+
+        match chunk_id {
+            b"fmt " => {
+                if chunk_end - chunk_start < 16 {
+                    return Err(Error::new("parse_wav: fmt chunk too short".to_string()));
+                }
+                format = Some(WavFormat {
+                    audio_format: read_u16(bytes, chunk_start),
+                    channels: read_u16(bytes, chunk_start + 2),
+                    sample_rate: read_u32(bytes, chunk_start + 4),
+                    bits_per_sample: read_u16(bytes, chunk_start + 14),
+                });
+            }
+            b"data" => {
+                let format = format.ok_or_else(|| {
+                    Error::new("parse_wav: data chunk precedes fmt chunk".to_string())
+                })?;
+                let chunk_end = if chunk_size == 0 {
+                    bytes.len()
+                } else {
+                    chunk_end
+                };
+                return Ok((format, &bytes[chunk_start..chunk_end]));
+            }
+            _ => {}
+        }
+        // chunks are word aligned
+        pos = chunk_end + (chunk_size & 1);
+    }
+
+    Err(Error::new("parse_wav: data chunk not found".to_string()))
+}
+
+/// Strips WAV header from audio content (e.g. SynthesizeSpeechResponse.audio_content
+/// with LINEAR16 encoding) and returns raw audio data. If audio content
+/// is not a valid WAV file it is returned unchanged.
+pub fn strip_wav_header(audio_content: &[u8]) -> &[u8] {
+    match parse_wav(audio_content) {
+        Ok((_, data)) => data,
+        Err(_) => audio_content,
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn wav_bytes(data: &[u8]) -> Vec<u8> {
+        let mut wav = vec![];
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32 + 12).to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&24000u32.to_le_bytes());
+        wav.extend_from_slice(&48000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        // odd sized chunk to check word alignment
+        wav.extend_from_slice(b"LIST");
+        wav.extend_from_slice(&3u32.to_le_bytes());
+        wav.extend_from_slice(&[1, 2, 3, 0]);
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(data);
+        wav
+    }
+
+    // cargo test -- --show-output test_parse_wav
+    #[test]
+    fn test_parse_wav() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let wav = wav_bytes(&data);
+        let (format, audio) = parse_wav(&wav).unwrap();
+        assert_eq!(
+            format,
+            WavFormat {
+                audio_format: 1,
+                channels: 1,
+                sample_rate: 24000,
+                bits_per_sample: 16,
+            }
+        );
+        assert_eq!(audio, &data);
+        assert_eq!(strip_wav_header(&wav), &data);
+        assert_eq!(strip_wav_header(&data), &data);
+    }
+}
@@ -0,0 +1,932 @@
+//! gcapi - command line interface for Google cognitive APIs.
+//! Requires feature cli, e.g.: cargo run --features cli --bin gcapi -- --help
+//!
+//! Credentials are taken (in this order) from --credentials flag, GCAPI_CREDENTIALS
+//! or GOOGLE_APPLICATION_CREDENTIALS environment variables or from config file.
+//! Config file (JSON) is specified by --config flag or GCAPI_CONFIG environment variable,
+//! default is ~/.config/gcapi/config.json. Example config file:
+//!
+//! {"credentials": "/path/to/cred.json", "projectId": "my-project", "languageCode": "en-US"}
+//!
+//! API endpoint (e.g. regional endpoint https://europe-west1-dialogflow.googleapis.com)
+//! is taken from --endpoint flag, GCAPI_ENDPOINT environment variable or endpoint
+//! attribute of config file. If not specified default endpoint of the API is used.
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use google_cognitive_apis::api::grpc::google::cloud::dialogflow::v2beta1::{
+    query_input::Input, AudioEncoding as DialogflowAudioEncoding, DetectIntentRequest,
+    DetectIntentResponse, InputAudioConfig, QueryInput, TextInput,
+};
+use google_cognitive_apis::api::grpc::google::cloud::speechtotext::v1::{
+    recognition_audio::AudioSource, recognition_config::AudioEncoding as RecognitionAudioEncoding,
+    LongRunningRecognizeRequest, LongRunningRecognizeResponse, RecognitionAudio, RecognitionConfig,
+    RecognizeRequest, SpeechRecognitionResult, StreamingRecognitionConfig,
+};
+use google_cognitive_apis::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, AudioConfig, AudioEncoding as SynthesisAudioEncoding,
+    ListVoicesRequest, SsmlVoiceGender, SynthesisInput, SynthesizeSpeechRequest,
+    VoiceSelectionParams,
+};
+use google_cognitive_apis::api::grpc::google::longrunning::{operation, Operation};
+use google_cognitive_apis::api::protobuf::struct_to_json;
+use google_cognitive_apis::audio::wav::{is_wav, parse_wav};
+use google_cognitive_apis::audio::{linear16_to_alaw_8k, linear16_to_ulaw_8k};
+use google_cognitive_apis::dialogflow::sessions_client::SessionsClient;
+use google_cognitive_apis::errors::{Error, Result};
+use google_cognitive_apis::longrunning::{decode_operation_result, OperationsClient};
+use google_cognitive_apis::speechtotext::batch::{
+    audio_duration, BatchConfig, BatchManifest, BatchTranscriber,
+};
+use google_cognitive_apis::speechtotext::recognizer::Recognizer;
+use google_cognitive_apis::texttospeech::synthesizer::Synthesizer;
+use google_cognitive_apis::transport::ClientConfig;
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Command line interface for Google speech-to-text, text-to-speech and dialogflow APIs.
+#[derive(Debug, Parser)]
+#[command(name = "gcapi", version)]
+struct Cli {
+    /// Path to GCP service account JSON credentials.
+    #[arg(long, global = true)]
+    credentials: Option<PathBuf>,
+
+    /// Path to JSON config file.
+    #[arg(long, global = true, env = "GCAPI_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// API endpoint URL or domain (e.g. europe-west1-dialogflow.googleapis.com)
+    /// overriding default endpoint of the API.
+    #[arg(long, global = true, env = "GCAPI_ENDPOINT")]
+    endpoint: Option<String>,
+
+    /// Print output as JSON instead of human readable text.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Transcribe audio file, audio piped into stdin or batch of files.
+    Transcribe(TranscribeArgs),
+    /// Synthesize text or SSML into audio file.
+    Synthesize(SynthesizeArgs),
+    /// List voices available for synthesis.
+    Voices(VoicesArgs),
+    /// Detect intent of text or audio using dialogflow ES agent.
+    DetectIntent(DetectIntentArgs),
+    /// Get, wait for or cancel long running operation.
+    Operation(OperationArgs),
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum InputEncoding {
+    Linear16,
+    Flac,
+    Mulaw,
+    OggOpus,
+}
+
+#[derive(Debug, Args)]
+struct TranscribeArgs {
+    /// Audio file to transcribe, "-" reads audio from stdin.
+    input: Option<String>,
+
+    /// Stream audio using streaming recognition as it is read from the input
+    /// (e.g. audio piped into stdin from another process).
+    #[arg(long)]
+    streaming: bool,
+
+    /// Print interim results when streaming.
+    #[arg(long)]
+    interim: bool,
+
+    /// Batch manifest (file or directory) to transcribe. Results are written into --output.
+    #[arg(long, conflicts_with = "input")]
+    batch: Option<PathBuf>,
+
+    /// JSON lines output file for batch transcription.
+    #[arg(long, requires = "batch")]
+    output: Option<PathBuf>,
+
+    /// State file used to resume interrupted batch transcription.
+    #[arg(long, requires = "batch")]
+    state: Option<PathBuf>,
+
+    /// Number of concurrently transcribed batch entries.
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Encoding of raw audio (taken from WAV header for WAV files).
+    #[arg(long, value_enum, default_value_t = InputEncoding::Linear16)]
+    encoding: InputEncoding,
+
+    /// Sample rate of raw audio (taken from WAV header for WAV files).
+    #[arg(long, default_value_t = 16000)]
+    sample_rate: i32,
+
+    /// Number of channels of raw audio (taken from WAV header for WAV files).
+    #[arg(long, default_value_t = 1)]
+    channels: i32,
+
+    /// Language code, defaults to config file value or en-US.
+    #[arg(long)]
+    language: Option<String>,
+
+    #[arg(long)]
+    punctuation: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OutputEncoding {
+    Linear16,
+    Mp3,
+    OggOpus,
+    /// 8kHz G.711 μ-law (raw, without header)
+    Mulaw,
+    /// 8kHz G.711 A-law (raw, without header)
+    Alaw,
+}
+
+#[derive(Debug, Args)]
+struct SynthesizeArgs {
+    /// Text to synthesize.
+    #[arg(long, conflicts_with = "ssml", required_unless_present = "ssml")]
+    text: Option<String>,
+
+    /// SSML to synthesize.
+    #[arg(long)]
+    ssml: Option<String>,
+
+    /// Output audio file.
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// Voice name, e.g. en-US-Wavenet-D.
+    #[arg(long)]
+    voice: Option<String>,
+
+    #[arg(long)]
+    language: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = OutputEncoding::Linear16)]
+    encoding: OutputEncoding,
+
+    #[arg(long, default_value_t = 16000)]
+    sample_rate: i32,
+
+    #[arg(long, default_value_t = 1.0)]
+    speaking_rate: f64,
+}
+
+#[derive(Debug, Args)]
+struct VoicesArgs {
+    /// Return only voices supporting given language.
+    #[arg(long)]
+    language: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct DetectIntentArgs {
+    /// Text query.
+    #[arg(long, conflicts_with = "audio", required_unless_present = "audio")]
+    text: Option<String>,
+
+    /// Audio file with the query (LINEAR16 WAV or raw audio).
+    #[arg(long)]
+    audio: Option<PathBuf>,
+
+    /// GCP project of the agent, defaults to config file value.
+    #[arg(long, env = "GCAPI_PROJECT_ID")]
+    project: Option<String>,
+
+    /// Session id, new session is created if not specified.
+    #[arg(long)]
+    session: Option<String>,
+
+    #[arg(long)]
+    language: Option<String>,
+
+    #[arg(long, default_value_t = 16000)]
+    sample_rate: i32,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OperationAction {
+    Get,
+    Wait,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Service {
+    Speech,
+    Dialogflow,
+    Texttospeech,
+}
+
+#[derive(Debug, Args)]
+struct OperationArgs {
+    #[arg(value_enum)]
+    action: OperationAction,
+
+    /// Operation name.
+    name: String,
+
+    /// Service which created the operation (--endpoint overrides its default endpoint).
+    #[arg(long, value_enum, default_value_t = Service::Speech)]
+    service: Service,
+
+    /// Status check interval in ms used by wait.
+    #[arg(long, default_value_t = 1000)]
+    interval: u64,
+}
+
+/// Content of config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Config {
+    credentials: Option<PathBuf>,
+    project_id: Option<String>,
+    language_code: Option<String>,
+    endpoint: Option<String>,
+}
+
+impl Config {
+    fn load(path: Option<&PathBuf>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.clone(),
+            None => match env::var("HOME") {
+                Ok(home) => PathBuf::from(home).join(".config/gcapi/config.json"),
+                Err(_) => return Ok(Config::default()),
+            },
+        };
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn language(&self, language: &Option<String>) -> String {
+        language
+            .clone()
+            .or_else(|| self.language_code.clone())
+            .unwrap_or_else(|| "en-US".to_string())
+    }
+}
+
+/// Returns endpoint URL from flag (or environment) or config file.
+/// Endpoint specified as domain name is converted into https URL.
+fn endpoint_url(cli: &Cli, config: &Config) -> Option<String> {
+    cli.endpoint
+        .clone()
+        .or_else(|| config.endpoint.clone())
+        .map(|endpoint| {
+            if endpoint.contains("://") {
+                endpoint
+            } else {
+                format!("https://{}", endpoint)
+            }
+        })
+}
+
+/// Reads credentials from flag, environment or config file.
+fn read_credentials(cli: &Cli, config: &Config) -> Result<String> {
+    let path = cli
+        .credentials
+        .clone()
+        .or_else(|| env::var("GCAPI_CREDENTIALS").ok().map(PathBuf::from))
+        .or_else(|| {
+            env::var("GOOGLE_APPLICATION_CREDENTIALS")
+                .ok()
+                .map(PathBuf::from)
+        })
+        .or_else(|| config.credentials.clone())
+        .ok_or_else(|| {
+            Error::new(
+                "credentials not specified, use --credentials, GCAPI_CREDENTIALS or config file"
+                    .to_string(),
+            )
+        })?;
+    Ok(fs::read_to_string(path)?)
+}
+
+fn print_output(json_output: bool, value: JsonValue, text: String) {
+    if json_output {
+        println!("{}", value);
+    } else {
+        println!("{}", text);
+    }
+}
+
+fn results_to_json(results: &[SpeechRecognitionResult]) -> JsonValue {
+    JsonValue::Array(
+        results
+            .iter()
+            .filter_map(|result| {
+                result.alternatives.first().map(|alternative| {
+                    json!({
+                        "transcript": alternative.transcript,
+                        "confidence": alternative.confidence,
+                        "channelTag": result.channel_tag,
+                    })
+                })
+            })
+            .collect(),
+    )
+}
+
+fn results_to_text(results: &[SpeechRecognitionResult]) -> String {
+    results
+        .iter()
+        .filter_map(|result| result.alternatives.first())
+        .map(|alternative| alternative.transcript.trim().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Opens input file, "-" opens stdin.
+fn open_input(input: &str) -> Result<Box<dyn Read + Send>> {
+    if input == "-" {
+        Ok(Box::new(std::io::stdin()))
+    } else {
+        Ok(Box::new(fs::File::open(input)?))
+    }
+}
+
+/// Reads up to size bytes, returns less bytes only at the end of input.
+fn read_chunk(reader: &mut dyn Read, size: usize) -> Result<Vec<u8>> {
+    let mut chunk = vec![];
+    reader.take(size as u64).read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
+
+fn read_input(input: &str) -> Result<Vec<u8>> {
+    if input == "-" {
+        let mut audio = vec![];
+        std::io::stdin().read_to_end(&mut audio)?;
+        Ok(audio)
+    } else {
+        Ok(fs::read(input)?)
+    }
+}
+
+/// Applies format of WAV audio (encoding, sample rate and number of channels) to recognition
+/// config overriding values of the flags. Returns audio data without WAV header.
+/// Audio which is not WAV is returned unchanged.
+fn apply_wav_header<'a>(audio: &'a [u8], config: &mut RecognitionConfig) -> Result<&'a [u8]> {
+    if !is_wav(audio) {
+        return Ok(audio);
+    }
+    let (format, data) = parse_wav(audio)?;
+    config.encoding = match (format.audio_format, format.bits_per_sample) {
+        (1, 16) => RecognitionAudioEncoding::Linear16,
+        (7, 8) => RecognitionAudioEncoding::Mulaw,
+        (audio_format, bits_per_sample) => {
+            return Err(Error::new(format!(
+                "unsupported WAV format {} with {} bits per sample, only LINEAR16 and MULAW WAV files are supported",
+                audio_format, bits_per_sample
+            )))
+        }
+    } as i32;
+    config.sample_rate_hertz = format.sample_rate as i32;
+    config.audio_channel_count = format.channels as i32;
+    Ok(data)
+}
+
+async fn transcribe(cli: &Cli, config: &Config, args: &TranscribeArgs) -> Result<()> {
+    let credentials = read_credentials(cli, config)?;
+    let mut recognition_config = RecognitionConfig {
+        encoding: match args.encoding {
+            InputEncoding::Linear16 => RecognitionAudioEncoding::Linear16,
+            InputEncoding::Flac => RecognitionAudioEncoding::Flac,
+            InputEncoding::Mulaw => RecognitionAudioEncoding::Mulaw,
+            InputEncoding::OggOpus => RecognitionAudioEncoding::OggOpus,
+        } as i32,
+        sample_rate_hertz: args.sample_rate,
+        audio_channel_count: args.channels,
+        language_code: config.language(&args.language),
+        max_alternatives: 1,
+        enable_automatic_punctuation: args.punctuation,
+        ..Default::default()
+    };
+
+    if let Some(batch) = &args.batch {
+        let manifest = if batch.is_dir() {
+            BatchManifest::from_dir(batch, &["wav", "flac", "raw", "ulaw", "opus"])?
+        } else {
+            BatchManifest::from_file(batch)?
+        };
+        let output = args
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("transcripts.jsonl"));
+        let mut batch_config = BatchConfig::new(recognition_config, output);
+        batch_config.state_file = args.state.clone();
+        batch_config.concurrency = args.concurrency;
+        let summary = BatchTranscriber::new(credentials, batch_config)
+            .run(manifest, None)
+            .await?;
+        print_output(
+            cli.json,
+            json!({
+                "total": summary.total,
+                "skipped": summary.skipped,
+                "succeeded": summary.succeeded,
+                "failed": summary.failed,
+                "retried": summary.retried,
+            }),
+            format!("{:?}", summary),
+        );
+        return Ok(());
+    }
+
+    let input = args.input.clone().unwrap_or_else(|| "-".to_string());
+
+    if args.streaming {
+        let reader = open_input(&input)?;
+        return transcribe_streaming(cli, credentials, recognition_config, reader, args.interim)
+            .await;
+    }
+
+    let audio = read_input(&input)?;
+    let audio = apply_wav_header(&audio, &mut recognition_config)?.to_vec();
+    let sync = matches!(
+        audio_duration(&audio, &recognition_config),
+        Some(duration) if duration <= Duration::from_secs(55)
+    );
+    let recognition_audio = RecognitionAudio {
+        audio_source: Some(AudioSource::Content(audio)),
+    };
+
+    let results = if sync {
+        let mut recognizer = Recognizer::create_synchronous_recognizer(credentials).await?;
+        recognizer
+            .recognize(
+                RecognizeRequest {
+                    config: Some(recognition_config),
+                    audio: Some(recognition_audio),
+                },
+                None,
+            )
+            .await?
+            .results
+    } else {
+        let mut recognizer = Recognizer::create_asynchronous_recognizer(credentials).await?;
+        let operation = recognizer
+            .long_running_recognize(
+                LongRunningRecognizeRequest {
+                    config: Some(recognition_config),
+                    audio: Some(recognition_audio),
+                },
+                None,
+            )
+            .await?;
+        recognizer
+            .long_running_wait(operation.into_inner(), None, None)
+            .await?
+            .map(|response| response.results)
+            .unwrap_or_default()
+    };
+
+    print_output(
+        cli.json,
+        json!({ "results": results_to_json(&results) }),
+        results_to_text(&results),
+    );
+    Ok(())
+}
+
+async fn transcribe_streaming(
+    cli: &Cli,
+    credentials: String,
+    recognition_config: RecognitionConfig,
+    reader: Box<dyn Read + Send>,
+    interim: bool,
+) -> Result<()> {
+    let mut reader = reader;
+    let mut recognition_config = recognition_config;
+    // first chunk is read upfront so that format of WAV input is known before streaming starts
+    let (first_chunk, mut reader) = tokio::task::spawn_blocking(move || {
+        read_chunk(&mut reader, 4096).map(|chunk| (chunk, reader))
+    })
+    .await??;
+    let first_chunk = apply_wav_header(&first_chunk, &mut recognition_config)?.to_vec();
+
+    let streaming_config = StreamingRecognitionConfig {
+        config: Some(recognition_config),
+        single_utterance: false,
+        interim_results: interim,
+    };
+    let mut recognizer =
+        Recognizer::create_streaming_recognizer(credentials, streaming_config, None).await?;
+    let audio_sink = recognizer
+        .take_audio_sink()
+        .ok_or_else(|| Error::new("audio sink not available".to_string()))?;
+    let mut result_receiver = recognizer.get_streaming_result_receiver(None);
+
+    // input is read on blocking thread, audio is forwarded as it arrives
+    tokio::task::spawn_blocking(move || {
+        if !first_chunk.is_empty()
+            && audio_sink
+                .blocking_send(Recognizer::streaming_request_from_bytes(first_chunk))
+                .is_err()
+        {
+            return;
+        }
+        let mut buffer = vec![0u8; 4096];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let request = Recognizer::streaming_request_from_bytes(buffer[..n].to_vec());
+                    if audio_sink.blocking_send(request).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let recognition = tokio::spawn(async move { recognizer.streaming_recognize(None).await });
+
+    while let Some(response) = result_receiver.recv().await {
+        for result in response.results {
+            if let Some(alternative) = result.alternatives.first() {
+                print_output(
+                    cli.json,
+                    json!({
+                        "transcript": alternative.transcript,
+                        "confidence": alternative.confidence,
+                        "isFinal": result.is_final,
+                    }),
+                    if result.is_final {
+                        alternative.transcript.trim().to_string()
+                    } else {
+                        format!("... {}", alternative.transcript.trim())
+                    },
+                );
+            }
+        }
+    }
+
+    recognition.await??;
+    Ok(())
+}
+
+async fn synthesize(cli: &Cli, config: &Config, args: &SynthesizeArgs) -> Result<()> {
+    let credentials = read_credentials(cli, config)?;
+    let mut synthesizer = Synthesizer::create(credentials).await?;
+
+    let (audio_encoding, sample_rate) = match args.encoding {
+        OutputEncoding::Linear16 => (SynthesisAudioEncoding::Linear16, args.sample_rate),
+        OutputEncoding::Mp3 => (SynthesisAudioEncoding::Mp3, args.sample_rate),
+        OutputEncoding::OggOpus => (SynthesisAudioEncoding::OggOpus, args.sample_rate),
+        // G.711 is created locally from 8kHz LINEAR16
+        OutputEncoding::Mulaw | OutputEncoding::Alaw => (SynthesisAudioEncoding::Linear16, 8000),
+    };
+
+    let input_source = match (&args.text, &args.ssml) {
+        (_, Some(ssml)) => InputSource::Ssml(ssml.clone()),
+        (Some(text), None) => InputSource::Text(text.clone()),
+        (None, None) => return Err(Error::new("--text or --ssml is required".to_string())),
+    };
+
+    let response = synthesizer
+        .synthesize_speech(
+            SynthesizeSpeechRequest {
+                input: Some(SynthesisInput {
+                    input_source: Some(input_source),
+                }),
+                voice: Some(VoiceSelectionParams {
+                    language_code: config.language(&args.language),
+                    name: args.voice.clone().unwrap_or_default(),
+                    ssml_gender: SsmlVoiceGender::Unspecified as i32,
+                }),
+                audio_config: Some(AudioConfig {
+                    audio_encoding: audio_encoding as i32,
+                    speaking_rate: args.speaking_rate,
+                    pitch: 0f64,
+                    volume_gain_db: 0f64,
+                    sample_rate_hertz: sample_rate,
+                    effects_profile_id: vec![],
+                }),
+            },
+            None,
+        )
+        .await?;
+
+    let audio = match args.encoding {
+        OutputEncoding::Mulaw => linear16_to_ulaw_8k(&response.audio_content, 8000)?,
+        OutputEncoding::Alaw => linear16_to_alaw_8k(&response.audio_content, 8000)?,
+        _ => response.audio_content,
+    };
+    fs::write(&args.output, &audio)?;
+
+    print_output(
+        cli.json,
+        json!({ "output": args.output, "bytes": audio.len() }),
+        format!("{} bytes written to {}", audio.len(), args.output.display()),
+    );
+    Ok(())
+}
+
+async fn voices(cli: &Cli, config: &Config, args: &VoicesArgs) -> Result<()> {
+    let credentials = read_credentials(cli, config)?;
+    let mut synthesizer = Synthesizer::create(credentials).await?;
+    let response = synthesizer
+        .list_voices(
+            ListVoicesRequest {
+                language_code: args.language.clone().unwrap_or_default(),
+            },
+            None,
+        )
+        .await?;
+
+    for voice in response.voices {
+        let gender = SsmlVoiceGender::from_i32(voice.ssml_gender)
+            .unwrap_or(SsmlVoiceGender::Unspecified)
+            .as_str_name();
+        print_output(
+            cli.json,
+            json!({
+                "name": voice.name,
+                "languageCodes": voice.language_codes,
+                "ssmlGender": gender,
+                "naturalSampleRateHertz": voice.natural_sample_rate_hertz,
+            }),
+            format!(
+                "{:<32} {:<16} {:<8} {}",
+                voice.name,
+                voice.language_codes.join(","),
+                gender,
+                voice.natural_sample_rate_hertz
+            ),
+        );
+    }
+    Ok(())
+}
+
+async fn detect_intent(cli: &Cli, config: &Config, args: &DetectIntentArgs) -> Result<()> {
+    let credentials = read_credentials(cli, config)?;
+    let project_id = args
+        .project
+        .clone()
+        .or_else(|| config.project_id.clone())
+        .ok_or_else(|| {
+            Error::new("project not specified, use --project or config file".to_string())
+        })?;
+    let session_id = args.session.clone().unwrap_or_else(|| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        format!("gcapi-{}", now.as_millis())
+    });
+    let language_code = config.language(&args.language);
+
+    let (input, input_audio) = match (&args.text, &args.audio) {
+        (Some(text), _) => (
+            Input::Text(TextInput {
+                text: text.clone(),
+                language_code,
+            }),
+            vec![],
+        ),
+        (None, Some(audio)) => {
+            let audio = fs::read(audio)?;
+            // WAV header is stripped, encoding and sample rate are taken from it
+            let mut audio_config = RecognitionConfig {
+                encoding: RecognitionAudioEncoding::Linear16 as i32,
+                sample_rate_hertz: args.sample_rate,
+                ..Default::default()
+            };
+            let input_audio = apply_wav_header(&audio, &mut audio_config)?.to_vec();
+            let audio_encoding = if audio_config.encoding == RecognitionAudioEncoding::Mulaw as i32
+            {
+                DialogflowAudioEncoding::Mulaw
+            } else {
+                DialogflowAudioEncoding::Linear16
+            };
+            (
+                Input::AudioConfig(InputAudioConfig {
+                    audio_encoding: audio_encoding as i32,
+                    sample_rate_hertz: audio_config.sample_rate_hertz,
+                    language_code,
+                    ..Default::default()
+                }),
+                input_audio,
+            )
+        }
+        (None, None) => return Err(Error::new("--text or --audio is required".to_string())),
+    };
+
+    let mut sessions_client = SessionsClient::create(credentials).await?;
+    let response: DetectIntentResponse = sessions_client
+        .detect_intent(
+            DetectIntentRequest {
+                session: SessionsClient::get_session_string(&project_id, &session_id),
+                query_params: None,
+                query_input: Some(QueryInput { input: Some(input) }),
+                output_audio_config: None,
+                output_audio_config_mask: None,
+                input_audio,
+            },
+            None,
+        )
+        .await?;
+
+    let query_result = response.query_result.clone().unwrap_or_default();
+    let intent = query_result
+        .intent
+        .as_ref()
+        .map(|intent| intent.display_name.clone())
+        .unwrap_or_default();
+    let parameters = query_result
+        .parameters
+        .as_ref()
+        .map(struct_to_json)
+        .unwrap_or(JsonValue::Null);
+
+    print_output(
+        cli.json,
+        json!({
+            "session": session_id,
+            "queryText": query_result.query_text,
+            "intent": intent,
+            "confidence": query_result.intent_detection_confidence,
+            "fulfillmentText": query_result.fulfillment_text,
+            "parameters": parameters,
+            "endOfConversation": SessionsClient::is_eoc(&response),
+        }),
+        format!(
+            "session: {}\nquery: {}\nintent: {} ({:.2})\nparameters: {}\nresponse: {}",
+            session_id,
+            query_result.query_text,
+            intent,
+            query_result.intent_detection_confidence,
+            parameters,
+            query_result.fulfillment_text
+        ),
+    );
+    Ok(())
+}
+
+fn operation_to_json(operation: &Operation) -> JsonValue {
+    let result = match &operation.result {
+        Some(operation::Result::Error(status)) => {
+            json!({ "error": { "code": status.code, "message": status.message } })
+        }
+        Some(operation::Result::Response(response)) => {
+            json!({ "responseType": response.type_url })
+        }
+        None => JsonValue::Null,
+    };
+    json!({
+        "name": operation.name,
+        "done": operation.done,
+        "metadataType": operation.metadata.as_ref().map(|metadata| metadata.type_url.clone()),
+        "result": result,
+    })
+}
+
+async fn operation(cli: &Cli, config: &Config, args: &OperationArgs) -> Result<()> {
+    let credentials = read_credentials(cli, config)?;
+    // custom endpoint (if any) is applied by ClientConfig, see run
+    let (domain, url) = match args.service {
+        Service::Speech => ("speech.googleapis.com", "https://speech.googleapis.com"),
+        Service::Dialogflow => (
+            "dialogflow.googleapis.com",
+            "https://dialogflow.googleapis.com",
+        ),
+        Service::Texttospeech => (
+            "texttospeech.googleapis.com",
+            "https://texttospeech.googleapis.com",
+        ),
+    };
+    let mut operations_client = OperationsClient::create(credentials, domain, url).await?;
+
+    let operation = match args.action {
+        OperationAction::Get => operations_client.get_operation(&args.name, None).await?,
+        OperationAction::Wait => {
+            let operation = operations_client.get_operation(&args.name, None).await?;
+            operations_client
+                .wait_operation(operation, Some(args.interval), None)
+                .await?
+        }
+        OperationAction::Cancel => {
+            operations_client.cancel_operation(&args.name, None).await?;
+            operations_client.get_operation(&args.name, None).await?
+        }
+    };
+
+    let mut value = operation_to_json(&operation);
+    let mut text = format!(
+        "name: {}\ndone: {}\nresult: {}",
+        operation.name, operation.done, value["result"]
+    );
+
+    // long running recognition results are decoded and printed as transcript
+    let is_recognition = matches!(
+        &operation.result,
+        Some(operation::Result::Response(response)) if response.type_url.ends_with("LongRunningRecognizeResponse")
+    );
+    if is_recognition {
+        if let Some(response) = decode_operation_result::<LongRunningRecognizeResponse>(operation)?
+        {
+            value["results"] = results_to_json(&response.results);
+            text = format!("{}\n{}", text, results_to_text(&response.results));
+        }
+    }
+
+    print_output(cli.json, value, text);
+    Ok(())
+}
+
+async fn run_command(cli: &Cli, config: &Config) -> Result<()> {
+    match &cli.command {
+        Command::Transcribe(args) => transcribe(cli, config, args).await,
+        Command::Synthesize(args) => synthesize(cli, config, args).await,
+        Command::Voices(args) => voices(cli, config, args).await,
+        Command::DetectIntent(args) => detect_intent(cli, config, args).await,
+        Command::Operation(args) => operation(cli, config, args).await,
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let config = Config::load(cli.config.as_ref())?;
+    // clients created by the command connect the endpoint of the client config
+    let mut client_config = ClientConfig::new();
+    if let Some(endpoint) = endpoint_url(&cli, &config) {
+        client_config = client_config.endpoint(endpoint);
+    }
+    client_config.scope(run_command(&cli, &config)).await
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+    let json_output = cli.json;
+    if let Err(err) = run(cli).await {
+        if json_output {
+            eprintln!(
+                "{}",
+                json!({
+                    "error": { "message": err.message, "code": err.code, "grpcCode": err.grpc_code }
+                })
+            );
+        } else {
+            eprintln!("error: {}", err.message);
+        }
+        std::process::exit(1);
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test --features cli -- --show-output test_apply_wav_header
+    #[test]
+    fn test_apply_wav_header() {
+        let mut wav = vec![];
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&40u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&32000u32.to_le_bytes());
+        wav.extend_from_slice(&4u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&4u32.to_le_bytes());
+        wav.extend_from_slice(&[1, 2, 3, 4]);
+
+        let mut config = RecognitionConfig {
+            encoding: RecognitionAudioEncoding::Flac as i32,
+            sample_rate_hertz: 16000,
+            audio_channel_count: 1,
+            ..Default::default()
+        };
+        let data = apply_wav_header(&wav, &mut config).unwrap();
+        assert_eq!(data, &[1, 2, 3, 4]);
+        assert_eq!(config.encoding, RecognitionAudioEncoding::Linear16 as i32);
+        assert_eq!(config.sample_rate_hertz, 8000);
+        assert_eq!(config.audio_channel_count, 2);
+
+        // raw audio is left untouched
+        let mut raw_config = RecognitionConfig::default();
+        assert_eq!(apply_wav_header(&[1, 2], &mut raw_config).unwrap(), &[1, 2]);
+        assert_eq!(raw_config, RecognitionConfig::default());
+    }
+}
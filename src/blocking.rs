@@ -0,0 +1,67 @@
+//! Contains blocking (synchronous) clients for applications not running tokio runtime.
+//! Every client owns internal multi-threaded runtime which drives underlying asynchronous
+//! client. Blocking clients must not be used from within asynchronous context
+//! (i.e. from tokio runtime), this would panic.
+//!
+//! Clients are configured by ClientConfig (see transport module) via function with_config:
+//!
+//! ```ignore
+//! let synthesizer = blocking::with_config(ClientConfig::new().rate_limiter(limiter), || {
+//!     blocking::synthesizer::Synthesizer::create(credentials)
+//! })?;
+//! ```
+use crate::errors::Result;
+use crate::transport::ClientConfig;
+use std::cell::RefCell;
+use std::future::Future;
+use tokio::runtime::{Builder, Runtime};
+
+/// blocking v1 STT API recognizer
+pub mod recognizer;
+
+/// blocking text-to-speech synthesizer
+pub mod synthesizer;
+
+/// blocking dialogflow sessions client (detect intent)
+pub mod sessions_client;
+
+/// Number of worker threads of the internal runtime of the client.
+const WORKER_THREADS: usize = 2;
+
+thread_local! {
+    static CURRENT_CLIENT_CONFIG: RefCell<Option<ClientConfig>> = const { RefCell::new(None) };
+}
+
+/// Restores previous config when with_config returns (or panics).
+struct ConfigGuard(Option<ClientConfig>);
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT_CLIENT_CONFIG.with(|config| *config.borrow_mut() = previous);
+    }
+}
+
+/// Runs function (typically blocking client constructor) with given client config.
+/// Blocking counterpart of ClientConfig::scope.
+pub fn with_config<T>(config: ClientConfig, create: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_CLIENT_CONFIG.with(|current| current.borrow_mut().replace(config));
+    let _guard = ConfigGuard(previous);
+    create()
+}
+
+/// Creates internal runtime of the blocking client.
+pub(crate) fn new_runtime() -> Result<Runtime> {
+    Ok(Builder::new_multi_thread()
+        .worker_threads(WORKER_THREADS)
+        .enable_all()
+        .build()?)
+}
+
+/// Runs asynchronous client constructor within client config set by with_config (if any).
+pub(crate) fn block_on_create<F: Future>(runtime: &Runtime, create: F) -> F::Output {
+    match CURRENT_CLIENT_CONFIG.with(|config| config.borrow().clone()) {
+        Some(config) => runtime.block_on(config.scope(create)),
+        None => runtime.block_on(create),
+    }
+}
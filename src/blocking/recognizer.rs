@@ -0,0 +1,312 @@
+//! Blocking speech-to-text recognizer - v1 STT API. Streaming recognition
+//! is exposed as iterator of streaming recognition results.
+use crate::api::grpc::google::cloud::speechtotext::v1::{
+    LongRunningRecognizeRequest, LongRunningRecognizeResponse, RecognizeRequest, RecognizeResponse,
+    StreamingRecognitionConfig, StreamingRecognizeRequest, StreamingRecognizeResponse,
+};
+use crate::api::grpc::google::longrunning::Operation;
+use crate::blocking::{block_on_create, new_runtime};
+use crate::call_options::CallOptions;
+use crate::errors::{Error, Result};
+use crate::metering::UsageMeter;
+use crate::speechtotext::recognizer::Recognizer as AsyncRecognizer;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio_stream::StreamExt;
+
+/// Blocking Google Speech API recognizer, see speechtotext::recognizer::Recognizer.
+#[derive(Debug)]
+pub struct Recognizer {
+    /// internal runtime driving asynchronous recognizer, shared with streaming recognition
+    runtime: Arc<Runtime>,
+
+    /// underlying asynchronous recognizer
+    recognizer: AsyncRecognizer,
+}
+
+/// Running streaming recognition. Audio is sent via send_audio (or fed from iterator,
+/// see Recognizer::streaming_recognize_iter), results are retrieved by iterating over
+/// this struct. Iteration ends once the audio is closed (close_audio) and all results are received.
+#[derive(Debug)]
+pub struct StreamingRecognition {
+    /// internal runtime of the recognizer, must outlive streaming task
+    runtime: Arc<Runtime>,
+
+    /// audio sink, None once closed
+    audio_sender: Option<mpsc::Sender<StreamingRecognizeRequest>>,
+
+    /// streaming recognition results
+    result_receiver: mpsc::UnboundedReceiver<Result<StreamingRecognizeResponse>>,
+
+    /// true once all results were received
+    finished: bool,
+}
+
+impl StreamingRecognition {
+    /// Sends audio bytes, blocks if the audio sink is full.
+    pub fn send_audio(&self, audio_bytes: Vec<u8>) -> Result<()> {
+        match &self.audio_sender {
+            Some(audio_sender) => Ok(audio_sender
+                .blocking_send(AsyncRecognizer::streaming_request_from_bytes(audio_bytes))?),
+            None => Err(Error::new(
+                "StreamingRecognition.send_audio: audio already closed".to_string(),
+            )),
+        }
+    }
+
+    /// Closes the audio, API returns remaining results and closes the stream.
+    pub fn close_audio(&mut self) {
+        self.audio_sender = None;
+    }
+
+    /// Returns next result if it was already received, does not block.
+    /// Returns None if no result is available (yet), see is_finished.
+    pub fn try_next(&mut self) -> Option<Result<StreamingRecognizeResponse>> {
+        match self.result_receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+
+    /// Returns true once the stream is closed and all results were received.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+impl Iterator for StreamingRecognition {
+    type Item = Result<StreamingRecognizeResponse>;
+
+    /// Blocks until next result is received, returns None once the stream is closed.
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.result_receiver.blocking_recv();
+        self.finished = result.is_none();
+        result
+    }
+}
+
+impl Recognizer {
+    fn new(runtime: Runtime, recognizer: AsyncRecognizer) -> Self {
+        Recognizer {
+            runtime: Arc::new(runtime),
+            recognizer,
+        }
+    }
+
+    /// Creates new speech recognizer for streaming recognition.
+    /// Capacity of audio sink defaults to 1000 if not provided.
+    pub fn create_streaming_recognizer(
+        google_credentials: impl AsRef<str>,
+        config: StreamingRecognitionConfig,
+        buffer_size: Option<usize>,
+    ) -> Result<Self> {
+        let runtime = new_runtime()?;
+        let recognizer = block_on_create(
+            &runtime,
+            AsyncRecognizer::create_streaming_recognizer(google_credentials, config, buffer_size),
+        )?;
+        Ok(Recognizer::new(runtime, recognizer))
+    }
+
+    /// Creates new speech recognizer for long running recognition.
+    pub fn create_asynchronous_recognizer(google_credentials: impl AsRef<str>) -> Result<Self> {
+        let runtime = new_runtime()?;
+        let recognizer = block_on_create(
+            &runtime,
+            AsyncRecognizer::create_asynchronous_recognizer(google_credentials),
+        )?;
+        Ok(Recognizer::new(runtime, recognizer))
+    }
+
+    /// Creates new speech recognizer for synchronous recognition.
+    pub fn create_synchronous_recognizer(google_credentials: impl AsRef<str>) -> Result<Self> {
+        let runtime = new_runtime()?;
+        let recognizer = block_on_create(
+            &runtime,
+            AsyncRecognizer::create_synchronous_recognizer(google_credentials),
+        )?;
+        Ok(Recognizer::new(runtime, recognizer))
+    }
+
+    /// Sets usage meter recording billable units consumed by this client.
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.recognizer.set_usage_meter(usage_meter);
+    }
+
+    /// Performs synchronous speech recognition.
+    pub fn recognize(
+        &mut self,
+        request: RecognizeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<RecognizeResponse> {
+        self.runtime
+            .block_on(self.recognizer.recognize(request, options))
+    }
+
+    /// Initiates asynchronous recognition, returns long running operation.
+    pub fn long_running_recognize(
+        &mut self,
+        request: LongRunningRecognizeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let response = self
+            .runtime
+            .block_on(self.recognizer.long_running_recognize(request, options))?;
+        Ok(response.into_inner())
+    }
+
+    /// Waits for completion of long running operation returned by long_running_recognize.
+    /// Operation status is checked every check_interval_ms ms (defaults to 1 sec).
+    pub fn long_running_wait(
+        &mut self,
+        operation: Operation,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<LongRunningRecognizeResponse>> {
+        self.runtime.block_on(self.recognizer.long_running_wait(
+            operation,
+            check_interval_ms,
+            options,
+        ))
+    }
+
+    /// Consumes recognizer and initiates streaming recognition (recognizer must be created
+    /// via create_streaming_recognizer). Streaming runs on the internal runtime, i.e. audio
+    /// can be sent before the results are read.
+    pub fn streaming_recognize(self, options: Option<CallOptions>) -> Result<StreamingRecognition> {
+        let runtime = self.runtime;
+        let (audio_sender, stream) = self.recognizer.into_streaming_session(options)?;
+        let (result_sender, result_receiver) = mpsc::unbounded_channel();
+        runtime.spawn(async move {
+            tokio::pin!(stream);
+            while let Some(result) = stream.next().await {
+                if result_sender.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(StreamingRecognition {
+            runtime,
+            audio_sender: Some(audio_sender),
+            result_receiver,
+            finished: false,
+        })
+    }
+
+    /// Consumes recognizer and initiates streaming recognition of audio chunks returned by the iterator.
+    /// Iterator is consumed on blocking thread of the internal runtime, audio is closed once
+    /// the iterator is exhausted. Returns iterator of streaming recognition results.
+    pub fn streaming_recognize_iter<I>(
+        self,
+        audio: I,
+        options: Option<CallOptions>,
+    ) -> Result<StreamingRecognition>
+    where
+        I: IntoIterator<Item = Vec<u8>>,
+        I::IntoIter: Send + 'static,
+    {
+        let mut streaming = self.streaming_recognize(options)?;
+        if let Some(audio_sender) = streaming.audio_sender.take() {
+            let audio = audio.into_iter();
+            streaming.runtime.spawn_blocking(move || {
+                for audio_bytes in audio {
+                    let request = AsyncRecognizer::streaming_request_from_bytes(audio_bytes);
+                    if audio_sender.blocking_send(request).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Ok(streaming)
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::api::grpc::google::cloud::speechtotext::v1::{
+        RecognitionConfig, SpeechRecognitionAlternative, StreamingRecognitionResult,
+    };
+    #[allow(unused_imports)]
+    use crate::blocking::with_config;
+    #[allow(unused_imports)]
+    use crate::recording::{RecordedCall, RecordedResponse, Recording, Replayer};
+    #[allow(unused_imports)]
+    use crate::transport::ClientConfig;
+    #[allow(unused_imports)]
+    use prost::Message;
+    #[allow(unused_imports)]
+    use std::collections::BTreeMap;
+
+    // cargo test -- --show-output test_blocking_streaming_recognize
+    #[test]
+    fn test_blocking_streaming_recognize() {
+        let responses = ["hello", "hello rust"]
+            .iter()
+            .enumerate()
+            .map(|(index, transcript)| {
+                let response = StreamingRecognizeResponse {
+                    results: vec![StreamingRecognitionResult {
+                        alternatives: vec![SpeechRecognitionAlternative {
+                            transcript: transcript.to_string(),
+                            ..Default::default()
+                        }],
+                        is_final: index == 1,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }
+                .encode_to_vec();
+                let mut frame = vec![0u8];
+                frame.extend((response.len() as u32).to_be_bytes());
+                frame.extend(response);
+                RecordedResponse {
+                    offset_ms: 0,
+                    request_bytes: 0,
+                    data: base64::encode(&frame),
+                }
+            })
+            .collect();
+        let recording = Recording {
+            calls: vec![RecordedCall {
+                path: "/google.cloud.speech.v1.Speech/StreamingRecognize".to_string(),
+                requests: vec![],
+                responses,
+                status: 0,
+                trailers: BTreeMap::new(),
+                finished_ms: 0,
+                request_bytes: 0,
+            }],
+        };
+
+        let config = StreamingRecognitionConfig {
+            config: Some(RecognitionConfig::default()),
+            single_utterance: false,
+            interim_results: true,
+        };
+        // requests (audio) are not recorded
+        let replayer = Replayer::new(recording).strict(false);
+        let recognizer = with_config(ClientConfig::new().replay(replayer), || {
+            Recognizer::create_streaming_recognizer("", config, None)
+        })
+        .unwrap();
+
+        let transcripts: Vec<String> = recognizer
+            .streaming_recognize_iter(vec![vec![0u8; 320], vec![0u8; 320]], None)
+            .unwrap()
+            .map(|response| {
+                response.unwrap().results[0].alternatives[0]
+                    .transcript
+                    .clone()
+            })
+            .collect();
+        assert_eq!(transcripts, vec!["hello", "hello rust"]);
+    }
+}
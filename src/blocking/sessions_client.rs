@@ -0,0 +1,59 @@
+//! Blocking Google Dialogflow sessions client (detect intent).
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    DetectIntentRequest, DetectIntentResponse,
+};
+use crate::blocking::{block_on_create, new_runtime};
+use crate::call_options::CallOptions;
+use crate::dialogflow::sessions_client::SessionsClient as AsyncSessionsClient;
+use crate::errors::Result;
+use crate::metering::UsageMeter;
+use tokio::runtime::Runtime;
+
+/// Blocking Google Dialogflow sessions client, see dialogflow::sessions_client::SessionsClient.
+#[derive(Debug)]
+pub struct SessionsClient {
+    /// internal runtime driving asynchronous sessions client
+    runtime: Runtime,
+
+    /// underlying asynchronous sessions client
+    sessions_client: AsyncSessionsClient,
+}
+
+impl SessionsClient {
+    /// Creates new sessions client using GCP project JSON credentials
+    pub fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        let runtime = new_runtime()?;
+        let sessions_client =
+            block_on_create(&runtime, AsyncSessionsClient::create(google_credentials))?;
+        Ok(SessionsClient {
+            runtime,
+            sessions_client,
+        })
+    }
+
+    /// Sets usage meter recording billable units consumed by this client.
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.sessions_client.set_usage_meter(usage_meter);
+    }
+
+    /// Calls detect_intent API of underlying GRPC SessionClient.
+    pub fn detect_intent(
+        &mut self,
+        request: DetectIntentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<DetectIntentResponse> {
+        self.runtime
+            .block_on(self.sessions_client.detect_intent(request, options))
+    }
+
+    /// Convenience function to check if DetectIntentResponse
+    /// represents end of conversation.
+    pub fn is_eoc(response: &DetectIntentResponse) -> bool {
+        AsyncSessionsClient::is_eoc(response)
+    }
+
+    /// Returns session string for given project and session id.
+    pub fn get_session_string(project_id: &str, session_id: &str) -> String {
+        AsyncSessionsClient::get_session_string(project_id, session_id)
+    }
+}
@@ -0,0 +1,57 @@
+//! Blocking text-to-speech synthesizer.
+use crate::api::grpc::google::cloud::texttospeech::v1::{
+    ListVoicesRequest, ListVoicesResponse, SynthesizeSpeechRequest, SynthesizeSpeechResponse,
+};
+use crate::blocking::{block_on_create, new_runtime};
+use crate::call_options::CallOptions;
+use crate::errors::Result;
+use crate::metering::UsageMeter;
+use crate::texttospeech::synthesizer::Synthesizer as AsyncSynthesizer;
+use tokio::runtime::Runtime;
+
+/// Blocking Google Text-to-Speech synthesizer, see texttospeech::synthesizer::Synthesizer.
+#[derive(Debug)]
+pub struct Synthesizer {
+    /// internal runtime driving asynchronous synthesizer
+    runtime: Runtime,
+
+    /// underlying asynchronous synthesizer
+    synthesizer: AsyncSynthesizer,
+}
+
+impl Synthesizer {
+    /// Creates new text-to-speech synthesizer using GCP project JSON credentials
+    pub fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        let runtime = new_runtime()?;
+        let synthesizer = block_on_create(&runtime, AsyncSynthesizer::create(google_credentials))?;
+        Ok(Synthesizer {
+            runtime,
+            synthesizer,
+        })
+    }
+
+    /// Sets usage meter recording billable units consumed by this client.
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.synthesizer.set_usage_meter(usage_meter);
+    }
+
+    /// Synthesizes speech synchronously.
+    pub fn synthesize_speech(
+        &mut self,
+        request: SynthesizeSpeechRequest,
+        options: Option<CallOptions>,
+    ) -> Result<SynthesizeSpeechResponse> {
+        self.runtime
+            .block_on(self.synthesizer.synthesize_speech(request, options))
+    }
+
+    /// Returns a list of Voice supported for synthesis.
+    pub fn list_voices(
+        &mut self,
+        request: ListVoicesRequest,
+        options: Option<CallOptions>,
+    ) -> Result<ListVoicesResponse> {
+        self.runtime
+            .block_on(self.synthesizer.list_voices(request, options))
+    }
+}
@@ -0,0 +1,252 @@
+//! Contains per-call options (deadline, request metadata, quota project).
+//! Every high-level method of the clients in this crate accepts options as its last argument:
+//!
+//! ```ignore
+//! let options = CallOptions::new()
+//!     .timeout(Duration::from_secs(5))
+//!     .quota_project("my-billing-project");
+//! let response = synthesizer.synthesize_speech(request, Some(options)).await?;
+//! ```
+//!
+//! When options are not specified (None), options of the current scope (tokio task local) are used
+//! as a fallback. Scope is not inherited by spawned tasks, explicit options should be preferred.
+//! Nested scopes are not merged, the innermost options are used.
+use crate::errors::{Error, Result};
+use crate::telemetry::TraceContext;
+use std::future::Future;
+use std::time::Duration;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::Request;
+
+tokio::task_local! {
+    static CURRENT_CALL_OPTIONS: CallOptions;
+}
+
+/// Header used to bill and quota the request to specific project.
+pub const QUOTA_PROJECT_HEADER: &str = "x-goog-user-project";
+
+/// Header used for routing of the request to the resource, e.g. name=projects/p/locations/l.
+pub const REQUEST_PARAMS_HEADER: &str = "x-goog-request-params";
+
+/// Options applied to GRPC calls made within the scope of the options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallOptions {
+    /// Deadline of the call. Sent to the API as grpc-timeout header and also
+    /// enforced by the client. For streaming calls deadline applies to the whole stream.
+    pub timeout: Option<Duration>,
+
+    /// Project used for billing and quota (x-goog-user-project header).
+    pub quota_project: Option<String>,
+
+    /// Routing parameters (x-goog-request-params header).
+    pub request_params: Vec<(String, String)>,
+
+    /// Arbitrary additional ASCII metadata (e.g. custom correlation IDs).
+    pub metadata: Vec<(String, String)>,
+
+    /// W3C trace context propagated into GRPC metadata (traceparent, tracestate headers).
+    /// Takes precedence over global trace context provider, see telemetry module.
+    pub trace_context: Option<TraceContext>,
+
+    /// Labels of usage records (e.g. tenant), see metering module. Not sent to the API.
+    pub usage_labels: Vec<(String, String)>,
+}
+
+impl CallOptions {
+    pub fn new() -> Self {
+        CallOptions::default()
+    }
+
+    /// Sets deadline of the call.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets project used for billing and quota.
+    pub fn quota_project(mut self, project_id: impl Into<String>) -> Self {
+        self.quota_project = Some(project_id.into());
+        self
+    }
+
+    /// Adds routing parameter, e.g. ("name", "projects/p/locations/global/recognizers/r").
+    pub fn request_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request_params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Adds arbitrary metadata entry. Key must be valid lowercase ASCII metadata key.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets W3C trace context propagated to the API.
+    pub fn trace_context(mut self, trace_context: TraceContext) -> Self {
+        self.trace_context = Some(trace_context);
+        self
+    }
+
+    /// Adds label of usage records of the calls (e.g. ("tenant", "acme")).
+    pub fn usage_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.usage_labels.push((key.into(), value.into()));
+        self
+    }
+
+    /// Runs the future with these options. All GRPC calls made by the clients
+    /// of this crate while polling the future without explicit options will use these options.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CALL_OPTIONS.scope(self, future).await
+    }
+
+    /// Returns options of the current scope (if any).
+    pub fn current() -> Option<CallOptions> {
+        CURRENT_CALL_OPTIONS
+            .try_with(|options| options.clone())
+            .ok()
+    }
+
+    /// Applies options to GRPC request. Trace context is not applied here,
+    /// it is propagated by the transport (see transport::GrpcChannel).
+    pub fn apply<T>(&self, request: &mut Request<T>) -> Result<()> {
+        if let Some(timeout) = self.timeout {
+            request.set_timeout(timeout);
+        }
+
+        if let Some(quota_project) = &self.quota_project {
+            insert_metadata(request, QUOTA_PROJECT_HEADER, quota_project)?;
+        }
+
+        if !self.request_params.is_empty() {
+            let request_params = self
+                .request_params
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, url_encode(value)))
+                .collect::<Vec<String>>()
+                .join("&");
+            insert_metadata(request, REQUEST_PARAMS_HEADER, &request_params)?;
+        }
+
+        for (key, value) in &self.metadata {
+            insert_metadata(request, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates GRPC request with given options, or with options of the current scope
+/// if not specified. Options are applied to the request and stored in its extensions
+/// (transport reads trace context from there).
+pub(crate) fn new_request<T>(message: T, options: Option<CallOptions>) -> Result<Request<T>> {
+    let mut request = Request::new(message);
+    if let Some(options) = options.or_else(CallOptions::current) {
+        options.apply(&mut request)?;
+        request.extensions_mut().insert(options);
+    }
+    Ok(request)
+}
+
+fn insert_metadata<T>(request: &mut Request<T>, key: &str, value: &str) -> Result<()> {
+    let metadata_key = key
+        .parse::<AsciiMetadataKey>()
+        .map_err(|err| Error::new(format!("invalid metadata key {}: {:?}", key, err)))?;
+    let metadata_value = value
+        .parse::<AsciiMetadataValue>()
+        .map_err(|err| Error::new(format!("invalid metadata value of {}: {:?}", key, err)))?;
+    request.metadata_mut().insert(metadata_key, metadata_value);
+    Ok(())
+}
+
+/// Percent-encodes all characters except unreserved ones (RFC 3986).
+fn url_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_call_options_apply
+    #[test]
+    fn test_call_options_apply() {
+        let options = CallOptions::new()
+            .timeout(Duration::from_millis(1500))
+            .quota_project("billing-project")
+            .request_param("name", "projects/p/locations/global")
+            .metadata("x-correlation-id", "abc-123");
+
+        let mut request = Request::new(());
+        options.apply(&mut request).unwrap();
+        let metadata = request.metadata();
+        assert_eq!(metadata.get("grpc-timeout").unwrap(), "1500000u");
+        assert_eq!(
+            metadata.get(QUOTA_PROJECT_HEADER).unwrap(),
+            "billing-project"
+        );
+        assert_eq!(
+            metadata.get(REQUEST_PARAMS_HEADER).unwrap(),
+            "name=projects%2Fp%2Flocations%2Fglobal"
+        );
+        assert_eq!(metadata.get("x-correlation-id").unwrap(), "abc-123");
+
+        let invalid = CallOptions::new().metadata("Invalid Key", "value");
+        assert!(invalid.apply(&mut Request::new(())).is_err());
+    }
+
+    // cargo test -- --show-output test_call_options_scope
+    #[tokio::test]
+    async fn test_call_options_scope() {
+        assert!(CallOptions::current().is_none());
+        let options = CallOptions::new().quota_project("outer");
+        let current = options
+            .clone()
+            .scope(async {
+                let inner = CallOptions::new()
+                    .quota_project("inner")
+                    .scope(async { CallOptions::current() })
+                    .await;
+                assert_eq!(inner.unwrap().quota_project.unwrap(), "inner");
+                CallOptions::current()
+            })
+            .await;
+        assert_eq!(current, Some(options));
+    }
+
+    // cargo test -- --show-output test_new_request
+    #[tokio::test]
+    async fn test_new_request() {
+        let explicit = CallOptions::new().quota_project("explicit");
+        let request = CallOptions::new()
+            .quota_project("scoped")
+            .scope(async { new_request((), Some(explicit.clone())).unwrap() })
+            .await;
+        assert_eq!(
+            request.metadata().get(QUOTA_PROJECT_HEADER).unwrap(),
+            "explicit"
+        );
+        assert_eq!(request.extensions().get::<CallOptions>(), Some(&explicit));
+
+        // options of the current scope are used as a fallback
+        let request = CallOptions::new()
+            .quota_project("scoped")
+            .scope(async { new_request((), None).unwrap() })
+            .await;
+        assert_eq!(
+            request.metadata().get(QUOTA_PROJECT_HEADER).unwrap(),
+            "scoped"
+        );
+
+        let request = new_request((), None).unwrap();
+        assert!(request.metadata().is_empty());
+        assert!(request.extensions().get::<CallOptions>().is_none());
+    }
+}
@@ -0,0 +1,338 @@
+//! Contains circuit breaker guarding GRPC endpoint of the clients. Breaker trips (opens)
+//! when error rate of recent calls exceeds configured threshold. While open, calls are routed
+//! to fallback endpoint (if configured, e.g. global endpoint instead of regional one)
+//! or rejected immediately with UNAVAILABLE status instead of waiting for timeouts.
+//! After open duration single probe call is let through (half-open state), its result
+//! either closes the breaker or opens it again.
+//!
+//! Breaker and fallback endpoint are set via ClientConfig (see transport module). Breaker is cheaply
+//! cloneable, clone kept by the application can be used for health checks:
+//!
+//! ```ignore
+//! let breaker = CircuitBreaker::new().error_rate_threshold(0.5).open_duration(Duration::from_secs(30));
+//! let recognizer = ClientConfig::new()
+//!     .endpoint("https://eu-speech.googleapis.com")
+//!     .fallback_endpoint("https://speech.googleapis.com")
+//!     .circuit_breaker(breaker.clone())
+//!     .scope(Recognizer::create_synchronous_recognizer(credentials))
+//!     .await?;
+//! let healthy = breaker.state() == CircuitState::Closed;
+//! ```
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tonic::Code;
+
+/// State of the circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Endpoint is healthy, calls are let through.
+    Closed,
+
+    /// Endpoint is failing, calls are routed to fallback endpoint or rejected.
+    Open,
+
+    /// Open duration elapsed, probe call is let through to verify endpoint health.
+    HalfOpen,
+}
+
+/// Returns true for GRPC codes indicating unhealthy endpoint (as opposed to invalid request).
+fn is_failure(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unavailable | Code::DeadlineExceeded | Code::Internal | Code::Unknown
+    )
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+    trips: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BreakerSettings {
+    error_rate_threshold: f64,
+    min_calls: usize,
+    window_size: usize,
+    open_duration: Duration,
+}
+
+/// Circuit breaker. All clones share the same state. Breaker should guard single endpoint,
+/// i.e. it should not be shared by clients of different APIs.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    settings: BreakerSettings,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("settings", &self.settings)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker::new()
+    }
+}
+
+/// Routing decision of the circuit breaker for single call.
+#[derive(Debug)]
+pub(crate) enum CircuitDecision {
+    /// Call goes to primary endpoint, its result must be reported.
+    Primary(CircuitCall),
+
+    /// Breaker is open, call goes to fallback endpoint or is rejected.
+    Open,
+}
+
+/// Call let through by the breaker. Dropping the call without result (e.g. cancelled stream)
+/// does not count as failure, only releases the probe slot.
+#[derive(Debug)]
+pub(crate) struct CircuitCall {
+    breaker: CircuitBreaker,
+    probe: bool,
+}
+
+impl CircuitCall {
+    /// Reports GRPC status of the finished call.
+    pub(crate) fn finish(mut self, code: Code) {
+        self.breaker.record(!is_failure(code), self.probe);
+        self.probe = false;
+    }
+}
+
+impl Drop for CircuitCall {
+    fn drop(&mut self) {
+        if self.probe {
+            if let Ok(mut state) = self.breaker.state.lock() {
+                state.probe_in_flight = false;
+            }
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates breaker opening when at least 50% of last 20 calls failed (at least 10 calls
+    /// must be recorded), open state lasts 30 seconds.
+    pub fn new() -> Self {
+        CircuitBreaker {
+            settings: BreakerSettings {
+                error_rate_threshold: 0.5,
+                min_calls: 10,
+                window_size: 20,
+                open_duration: Duration::from_secs(30),
+            },
+            state: Arc::new(Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: None,
+                probe_in_flight: false,
+                trips: 0,
+            })),
+        }
+    }
+
+    /// Sets error rate (0.0 - 1.0) of recent calls opening the breaker.
+    pub fn error_rate_threshold(mut self, error_rate_threshold: f64) -> Self {
+        self.settings.error_rate_threshold = error_rate_threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets number of recent calls used to calculate error rate and minimal
+    /// number of calls which must be recorded before the breaker can open.
+    pub fn window(mut self, window_size: usize, min_calls: usize) -> Self {
+        self.settings.window_size = window_size.max(1);
+        self.settings.min_calls = min_calls.clamp(1, self.settings.window_size);
+        self
+    }
+
+    /// Sets duration of open state after which probe call is let through.
+    pub fn open_duration(mut self, open_duration: Duration) -> Self {
+        self.settings.open_duration = open_duration;
+        self
+    }
+
+    /// Returns current state of the breaker.
+    pub fn state(&self) -> CircuitState {
+        match self.state.lock() {
+            Ok(state) => match (state.state, state.opened_at) {
+                (CircuitState::Open, Some(opened_at))
+                    if opened_at.elapsed() >= self.settings.open_duration =>
+                {
+                    CircuitState::HalfOpen
+                }
+                (state, _) => state,
+            },
+            Err(_) => CircuitState::Closed,
+        }
+    }
+
+    /// Returns error rate of recent calls (0.0 - 1.0).
+    pub fn error_rate(&self) -> f64 {
+        match self.state.lock() {
+            Ok(state) if !state.outcomes.is_empty() => {
+                let failures = state.outcomes.iter().filter(|success| !**success).count();
+                failures as f64 / state.outcomes.len() as f64
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Returns how many times the breaker opened.
+    pub fn trips(&self) -> u64 {
+        self.state.lock().map(|state| state.trips).unwrap_or(0)
+    }
+
+    /// Opens the breaker regardless of error rate (e.g. when primary endpoint cannot be connected).
+    pub fn trip(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            Self::open(&mut state);
+        }
+    }
+
+    /// Closes the breaker and clears recorded calls.
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.state = CircuitState::Closed;
+            state.outcomes.clear();
+            state.opened_at = None;
+        }
+    }
+
+    fn open(state: &mut BreakerState) {
+        if state.state != CircuitState::Open {
+            state.trips += 1;
+        }
+        state.state = CircuitState::Open;
+        state.opened_at = Some(Instant::now());
+        state.outcomes.clear();
+    }
+
+    /// Decides whether the call can go to primary endpoint.
+    pub(crate) fn decide(&self) -> CircuitDecision {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => {
+                return CircuitDecision::Primary(CircuitCall {
+                    breaker: self.clone(),
+                    probe: false,
+                })
+            }
+        };
+        let probe = match state.state {
+            CircuitState::Closed => false,
+            _ => {
+                let elapsed = state
+                    .opened_at
+                    .map(|opened_at| opened_at.elapsed() >= self.settings.open_duration)
+                    .unwrap_or(true);
+                if !elapsed || state.probe_in_flight {
+                    return CircuitDecision::Open;
+                }
+                state.state = CircuitState::HalfOpen;
+                state.probe_in_flight = true;
+                true
+            }
+        };
+        CircuitDecision::Primary(CircuitCall {
+            breaker: self.clone(),
+            probe,
+        })
+    }
+
+    fn record(&self, success: bool, probe: bool) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+        if probe {
+            state.probe_in_flight = false;
+            if success {
+                log::debug!("CircuitBreaker: probe call succeeded, closing breaker");
+                state.state = CircuitState::Closed;
+                state.opened_at = None;
+                state.outcomes.clear();
+            } else {
+                log::warn!("CircuitBreaker: probe call failed, opening breaker again");
+                Self::open(&mut state);
+            }
+            return;
+        }
+        if state.state != CircuitState::Closed {
+            // late result of call started before the breaker opened
+            return;
+        }
+        state.outcomes.push_back(success);
+        while state.outcomes.len() > self.settings.window_size {
+            state.outcomes.pop_front();
+        }
+        let failures = state.outcomes.iter().filter(|success| !**success).count();
+        let calls = state.outcomes.len();
+        if calls >= self.settings.min_calls
+            && failures as f64 / calls as f64 >= self.settings.error_rate_threshold
+        {
+            log::warn!(
+                "CircuitBreaker: {} of {} recent calls failed, opening breaker",
+                failures,
+                calls
+            );
+            Self::open(&mut state);
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_circuit_breaker
+    #[test]
+    fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new()
+            .error_rate_threshold(0.5)
+            .window(4, 4)
+            .open_duration(Duration::from_millis(0));
+
+        let call = |code: Code| match breaker.decide() {
+            CircuitDecision::Primary(call) => call.finish(code),
+            CircuitDecision::Open => panic!("breaker unexpectedly open"),
+        };
+
+        // invalid requests do not count as failures
+        call(Code::InvalidArgument);
+        call(Code::Unavailable);
+        call(Code::Ok);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        call(Code::DeadlineExceeded);
+        assert_eq!(breaker.trips(), 1);
+
+        // open duration elapsed, single probe call is let through
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        let probe = match breaker.decide() {
+            CircuitDecision::Primary(probe) => probe,
+            CircuitDecision::Open => panic!("probe call expected"),
+        };
+        assert!(matches!(breaker.decide(), CircuitDecision::Open));
+
+        // dropped probe releases the slot, successful probe closes the breaker
+        drop(probe);
+        call(Code::Ok);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.error_rate(), 0.0);
+
+        let breaker = CircuitBreaker::new().open_duration(Duration::from_secs(60));
+        breaker.trip();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(breaker.decide(), CircuitDecision::Open));
+    }
+}
@@ -1,18 +1,26 @@
 //! Contains common utility & convenience functions.
 //! All functions here are intended for internal use only.
-use crate::errors::Result;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::errors::{Error, Result};
+use crate::transport::{ClientConfig, GrpcChannel};
 use crate::CERTIFICATES;
 use gouth::Builder;
 use std::sync::Arc;
 use tonic::{
     metadata::{Ascii, MetadataValue},
     service::Interceptor,
-    transport::{Certificate, Channel, ClientTlsConfig},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint},
     Status,
 };
 
 #[derive(Clone)]
 pub struct TokenInterceptor(Arc<String>);
+impl std::fmt::Debug for TokenInterceptor {
+    // token value is intentionally not printed
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TokenInterceptor")
+    }
+}
 impl TokenInterceptor {
     fn new(token_header_val: Arc<String>) -> TokenInterceptor {
         TokenInterceptor(token_header_val)
@@ -29,7 +37,7 @@ impl Interceptor for TokenInterceptor {
         let mut req = request;
         #[allow(deprecated)]
         let meta_result = MetadataValue::<Ascii>::from_str(&self.0);
-        return match meta_result {
+        match meta_result {
             Ok(meta) => {
                 req.metadata_mut().insert("authorization", meta);
 
@@ -45,40 +53,111 @@ impl Interceptor for TokenInterceptor {
                 "new_interceptor: Error when getting MetadataValue from token {:?}",
                 some_error
             ))),
-        };
+        }
     }
 }
 
-/// Creates new GRPC channel to *.googleapis.com API
-/// Domain name and channel URL (like texttospeech.googleapis.com & https://texttospeech.googleapis.com)
-/// is provided as input. Optionally timeout in seconds can be specified.
-pub(crate) async fn new_grpc_channel(
-    domain_name: &'static str,
-    channel_url: &'static str,
+/// Creates TLS endpoint for given domain name and channel URL.
+fn new_endpoint(
+    domain_name: String,
+    channel_url: String,
     timeout_secs: Option<u64>,
-) -> Result<Channel> {
+) -> Result<Endpoint> {
     let tls_config = ClientTlsConfig::new()
         .ca_certificate(Certificate::from_pem(CERTIFICATES))
         .domain_name(domain_name);
 
+    let endpoint = Channel::from_shared(channel_url.clone())
+        .map_err(|err| Error::new(format!("invalid channel url {}: {}", channel_url, err)))?
+        .tls_config(tls_config)?;
+
     if let Some(timeout) = timeout_secs {
-        Ok(Channel::from_static(channel_url)
-            .tls_config(tls_config.clone())?
-            .timeout(std::time::Duration::from_secs(timeout))
-            .connect()
-            .await?)
+        Ok(endpoint.timeout(std::time::Duration::from_secs(timeout)))
     } else {
-        Ok(Channel::from_static(channel_url)
-            .tls_config(tls_config.clone())?
-            .connect()
-            .await?)
+        Ok(endpoint)
+    }
+}
+
+/// Returns domain name of endpoint URL, e.g. speech.googleapis.com for https://speech.googleapis.com.
+fn endpoint_domain(channel_url: &str) -> Result<String> {
+    channel_url
+        .parse::<tonic::codegen::http::Uri>()
+        .ok()
+        .and_then(|uri| uri.host().map(|host| host.to_string()))
+        .ok_or_else(|| Error::new(format!("invalid endpoint url {}", channel_url)))
+}
+
+/// Creates new GRPC channel to *.googleapis.com API
+/// Domain name and channel URL (like texttospeech.googleapis.com & https://texttospeech.googleapis.com)
+/// is provided as input. Optionally timeout in seconds can be specified.
+/// Channel is wrapped into instrumented transport (see transport module)
+/// configured by given ClientConfig.
+pub(crate) async fn new_grpc_channel_with_config(
+    domain_name: &str,
+    channel_url: &str,
+    timeout_secs: Option<u64>,
+    config: &ClientConfig,
+) -> Result<GrpcChannel> {
+    let mut config = config.clone();
+    if config.is_replay() {
+        return Ok(GrpcChannel::offline(config));
+    }
+
+    let endpoint = match &config.endpoint {
+        Some(url) => new_endpoint(endpoint_domain(url)?, url.clone(), timeout_secs)?,
+        None => new_endpoint(
+            domain_name.to_string(),
+            channel_url.to_string(),
+            timeout_secs,
+        )?,
+    };
+
+    // fallback endpoint is connected on first use only
+    let fallback = match &config.fallback_endpoint {
+        Some(url) => {
+            Some(new_endpoint(endpoint_domain(url)?, url.clone(), timeout_secs)?.connect_lazy())
+        }
+        None => None,
+    };
+    if fallback.is_some() && config.circuit_breaker.is_none() {
+        config.circuit_breaker = Some(CircuitBreaker::new());
+    }
+
+    let channel = match endpoint.connect().await {
+        Ok(channel) => channel,
+        Err(err) => match (&fallback, &config.circuit_breaker) {
+            (Some(_), Some(circuit_breaker)) => {
+                log::warn!(
+                    "new_grpc_channel: cannot connect {}, failing over to fallback endpoint: {}",
+                    endpoint.uri(),
+                    err
+                );
+                circuit_breaker.trip();
+                endpoint.connect_lazy()
+            }
+            _ => return Err(err.into()),
+        },
+    };
+
+    let grpc_channel = GrpcChannel::new(channel).with_config(config);
+    match fallback {
+        Some(fallback) => Ok(grpc_channel.with_fallback(fallback)),
+        None => Ok(grpc_channel),
     }
 }
 
 /// Returns google token (String value) from
-/// Google Cloud Platform project JSON credentials (provided as String).
+/// Google Cloud Platform project JSON credentials (provided as String)
+/// for the client configured by given ClientConfig.
 #[allow(clippy::rc_buffer)]
-pub(crate) fn get_token(google_credentials: impl AsRef<str>) -> Result<Arc<String>> {
+pub(crate) fn get_token_with_config(
+    google_credentials: impl AsRef<str>,
+    config: &ClientConfig,
+) -> Result<Arc<String>> {
+    // replayed calls do not leave the process, credentials are not needed
+    if config.is_replay() {
+        return Ok(Arc::new("Bearer replay".to_string()));
+    }
     let token = Builder::new().json(google_credentials).build()?;
     let token_header_val: Arc<String> = token.header_value()?;
     Ok(token_header_val)
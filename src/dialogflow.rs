@@ -1,14 +1,48 @@
-//! Contains dialogflow session client.
+//! Contains dialogflow sessions client and agent management clients.
 
-use crate::api::grpc::google::cloud::dialogflow::v2beta1::DetectIntentResponse;
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    DetectIntentResponse, QueryResult, StreamingDetectIntentResponse,
+};
+use crate::api::protobuf::struct_to_type;
+use crate::errors::Result;
+use serde::de::DeserializeOwned;
 
+pub mod agent_sync;
+pub mod agents_client;
+pub mod contexts_client;
+pub mod entity_types_client;
+pub mod intents_client;
+pub mod session_entity_types_client;
+#[cfg(any(feature = "default", feature = "google-cloud-dialogflow-cx-v3",))]
+pub mod session_entity_types_client_cx;
+#[cfg(any(feature = "default", feature = "google-cloud-dialogflow-v2",))]
+pub mod session_entity_types_client_v2;
 pub mod sessions_client;
 pub mod sessions_client_streaming;
 
+pub(crate) const GRPC_API_DOMAIN: &str = "dialogflow.googleapis.com";
+pub(crate) const GRPC_API_URL: &str = "https://dialogflow.googleapis.com";
+
+/// Location using global (not location specific) endpoint.
+pub const GLOBAL_LOCATION: &str = "global";
+
+/// Returns domain name and channel URL of the dialogflow API endpoint for given location,
+/// e.g. europe-west1-dialogflow.googleapis.com & https://europe-west1-dialogflow.googleapis.com.
+/// Global location (or empty location) uses dialogflow.googleapis.com.
+pub fn get_api_endpoint(location: &str) -> (String, String) {
+    let domain = if location.is_empty() || location == GLOBAL_LOCATION {
+        GRPC_API_DOMAIN.to_string()
+    } else {
+        format!("{}-{}", location, GRPC_API_DOMAIN)
+    };
+    let url = format!("https://{}", domain);
+    (domain, url)
+}
+
 /// Convenience function to check if DetectIntentResponse
 /// represents end of conversation. If so, returns true, otherwise false.
 pub fn is_eoc(response: &DetectIntentResponse) -> bool {
-    return if let Some(query_result) = &response.query_result {
+    if let Some(query_result) = &response.query_result {
         let mut eoc = false;
         if let Some(diagnostic_info) = &query_result.diagnostic_info {
             if let Some(end_conversation) = diagnostic_info.fields.get("end_conversation") {
@@ -21,7 +55,7 @@ pub fn is_eoc(response: &DetectIntentResponse) -> bool {
         eoc
     } else {
         false
-    };
+    }
 }
 
 /// Convenience function to return properly formatted session string
@@ -29,3 +63,71 @@ pub fn is_eoc(response: &DetectIntentResponse) -> bool {
 pub fn get_session_string(project_id: &str, session_id: &str) -> String {
     format!("projects/{}/agent/sessions/{}", project_id, session_id)
 }
+
+/// Deserializes query result parameters into arbitrary user defined type.
+/// Returns None if query result or its parameters are not present.
+fn query_result_parameters<T: DeserializeOwned>(
+    query_result: &Option<QueryResult>,
+) -> Result<Option<T>> {
+    if let Some(query_result) = query_result {
+        if let Some(parameters) = &query_result.parameters {
+            return Ok(Some(struct_to_type(parameters)?));
+        }
+    }
+    Ok(None)
+}
+
+impl DetectIntentResponse {
+    /// Returns query result parameters deserialized into arbitrary user defined type.
+    /// Returns None if response does not contain any parameters.
+    pub fn parameters<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        query_result_parameters(&self.query_result)
+    }
+}
+
+impl StreamingDetectIntentResponse {
+    /// Returns query result parameters deserialized into arbitrary user defined type.
+    /// Returns None if response does not contain any parameters
+    /// (e.g. intermediate responses with recognition results only).
+    pub fn parameters<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        query_result_parameters(&self.query_result)
+    }
+}
+
+/// Convenience function to return properly formatted agent string
+/// used as parent of intents and entity types.
+pub fn get_agent_string(project_id: &str) -> String {
+    format!("projects/{}/agent", project_id)
+}
+
+/// Convenience function to return properly formatted context string
+/// for contexts client calls.
+pub fn get_context_string(project_id: &str, session_id: &str, context_id: &str) -> String {
+    format!(
+        "{}/contexts/{}",
+        get_session_string(project_id, session_id),
+        context_id
+    )
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_dialogflow_api_endpoint
+    #[test]
+    fn test_dialogflow_api_endpoint() {
+        assert_eq!(
+            get_api_endpoint("europe-west1"),
+            (
+                "europe-west1-dialogflow.googleapis.com".to_string(),
+                "https://europe-west1-dialogflow.googleapis.com".to_string()
+            )
+        );
+        assert_eq!(
+            get_api_endpoint(GLOBAL_LOCATION),
+            (GRPC_API_DOMAIN.to_string(), GRPC_API_URL.to_string())
+        );
+        assert_eq!(get_api_endpoint("").1, GRPC_API_URL);
+    }
+}
@@ -0,0 +1,883 @@
+//! This module contains helpers for maintaining dialogflow agent as code.
+//! Agent (its intents and entity types) is described in local JSON or YAML file
+//! (see AgentDescription). Description is then compared with live agent
+//! (see diff_agent) and differences can be applied to the live agent (see sync_agent).
+//!
+//! Sample YAML description:
+//! ```yaml
+//! intents:
+//!   - displayName: order.pizza
+//!     action: order_pizza
+//!     trainingPhrases:
+//!       - I would like to order pizza
+//!       - one pizza please
+//!       - parts:
+//!           - text: "I would like "
+//!           - text: mushrooms
+//!             entityType: "@topping"
+//!             alias: topping
+//!     responses:
+//!       - Which pizza would you like?
+//! entityTypes:
+//!   - displayName: topping
+//!     entities:
+//!       - value: mushrooms
+//!         synonyms: [mushrooms, champignons]
+//! ```
+//!
+//! Only attributes present in the description are synchronized (see sync_agent). Training phrases
+//! described as plain text keep the annotations of the live agent, platform specific responses
+//! (telephony, Slack...) and rich messages of the live agent are left untouched.
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    entity_type::{AutoExpansionMode, Entity, Kind as EntityTypeKind},
+    intent::{
+        message::{Message as IntentMessageKind, Platform, Text as IntentMessageText},
+        training_phrase::{Part as TrainingPhrasePart, Type as TrainingPhraseType},
+        Message as IntentMessage, TrainingPhrase,
+    },
+    EntityType, Intent, IntentView,
+};
+use crate::call_options::CallOptions;
+use crate::dialogflow::entity_types_client::EntityTypesClient;
+use crate::dialogflow::intents_client::IntentsClient;
+use crate::errors::{Error, Result};
+use prost_types::FieldMask;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Local description of dialogflow agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentDescription {
+    #[serde(default)]
+    pub intents: Vec<IntentDescription>,
+
+    #[serde(rename = "entityTypes", default)]
+    pub entity_types: Vec<EntityTypeDescription>,
+}
+
+/// Local description of single intent. Intents are matched
+/// with live agent intents by display name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntentDescription {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<String>>,
+
+    #[serde(rename = "trainingPhrases", default)]
+    pub training_phrases: Vec<TrainingPhraseDescription>,
+
+    /// text responses of the intent (platform independent text message)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub responses: Option<Vec<String>>,
+}
+
+/// Local description of single training phrase. Phrase is either plain text
+/// (annotations of the live phrase with the same text are preserved) or list
+/// of parts with explicit entity annotations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TrainingPhraseDescription {
+    Text(String),
+    Parts {
+        parts: Vec<TrainingPhrasePartDescription>,
+    },
+}
+
+/// Local description of single part of annotated training phrase.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingPhrasePartDescription {
+    pub text: String,
+
+    /// annotated entity type, e.g. @sys.date or @topping
+    #[serde(
+        rename = "entityType",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub entity_type: Option<String>,
+
+    /// parameter name of the annotated part
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    #[serde(rename = "userDefined", default)]
+    pub user_defined: bool,
+}
+
+impl From<&str> for TrainingPhraseDescription {
+    fn from(text: &str) -> Self {
+        TrainingPhraseDescription::Text(text.to_string())
+    }
+}
+
+impl TrainingPhraseDescription {
+    /// Returns full text of the phrase.
+    pub fn text(&self) -> String {
+        match self {
+            TrainingPhraseDescription::Text(text) => text.clone(),
+            TrainingPhraseDescription::Parts { parts } => {
+                parts.iter().map(|part| part.text.as_str()).collect()
+            }
+        }
+    }
+
+    fn parts(&self) -> Vec<TrainingPhrasePart> {
+        match self {
+            TrainingPhraseDescription::Text(text) => vec![TrainingPhrasePart {
+                text: text.clone(),
+                entity_type: "".to_string(),
+                alias: "".to_string(),
+                user_defined: false,
+            }],
+            TrainingPhraseDescription::Parts { parts } => parts
+                .iter()
+                .map(|part| TrainingPhrasePart {
+                    text: part.text.clone(),
+                    entity_type: part.entity_type.clone().unwrap_or_default(),
+                    alias: part.alias.clone().unwrap_or_default(),
+                    user_defined: part.user_defined,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns true if live training phrase corresponds to this description.
+    /// Annotations are compared only for phrases described with parts.
+    fn matches(&self, phrase: &TrainingPhrase) -> bool {
+        match self {
+            TrainingPhraseDescription::Text(text) => *text == training_phrase_text(phrase),
+            TrainingPhraseDescription::Parts { .. } => phrase.parts == self.parts(),
+        }
+    }
+
+    fn to_training_phrase(&self) -> TrainingPhrase {
+        TrainingPhrase {
+            name: "".to_string(),
+            r#type: TrainingPhraseType::Example as i32,
+            parts: self.parts(),
+            times_added_count: 0,
+        }
+    }
+}
+
+fn training_phrase_text(phrase: &TrainingPhrase) -> String {
+    phrase.parts.iter().map(|part| part.text.as_str()).collect()
+}
+
+/// Returns true for platform independent text messages, the only messages synchronized
+/// from the description.
+fn is_default_text_message(message: &IntentMessage) -> bool {
+    message.platform == Platform::Unspecified as i32
+        && matches!(message.message, Some(IntentMessageKind::Text(_)))
+}
+
+/// Fields of the intent covered by the description, attributes
+/// missing in the description are not updated.
+fn intent_update_mask(intent_desc: &IntentDescription) -> FieldMask {
+    let mut paths = vec![];
+    if intent_desc.action.is_some() {
+        paths.push("action".to_string());
+    }
+    if intent_desc.events.is_some() {
+        paths.push("events".to_string());
+    }
+    paths.push("training_phrases".to_string());
+    if intent_desc.responses.is_some() {
+        paths.push("messages".to_string());
+    }
+    FieldMask { paths }
+}
+
+/// Fields of the entity type covered by the description.
+fn entity_type_update_mask() -> FieldMask {
+    FieldMask {
+        paths: vec![
+            "kind".to_string(),
+            "auto_expansion_mode".to_string(),
+            "enable_fuzzy_extraction".to_string(),
+            "entities".to_string(),
+        ],
+    }
+}
+
+/// Local description of single entity type. Entity types are matched
+/// with live agent entity types by display name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityTypeDescription {
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+
+    /// list entity types have no synonyms, if not specified map entity type is used
+    #[serde(default)]
+    pub list: bool,
+
+    #[serde(rename = "autoExpansion", default)]
+    pub auto_expansion: bool,
+
+    #[serde(rename = "fuzzyExtraction", default)]
+    pub fuzzy_extraction: bool,
+
+    #[serde(default)]
+    pub entities: Vec<EntityDescription>,
+}
+
+/// Local description of single entity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityDescription {
+    pub value: String,
+
+    /// if not specified value is used as the only synonym
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+}
+
+impl AgentDescription {
+    /// Parses agent description from JSON string.
+    pub fn from_json_str(json_str: &str) -> Result<Self> {
+        let jd = &mut serde_json::Deserializer::from_str(json_str);
+        let result: std::result::Result<AgentDescription, _> = serde_path_to_error::deserialize(jd);
+        match result {
+            Ok(description) => Ok(description),
+            Err(err) => Err(Error::new(format!(
+                "Error when deserializing agent description at path: {}. Full error: {}",
+                err.path(),
+                err
+            ))),
+        }
+    }
+
+    /// Parses agent description from YAML string.
+    pub fn from_yaml_str(yaml_str: &str) -> Result<Self> {
+        serde_yaml::from_str(yaml_str).map_err(|err| {
+            Error::new(format!(
+                "Error when deserializing agent description: {}",
+                err
+            ))
+        })
+    }
+
+    /// Loads agent description from local file. Files with
+    /// .yaml or .yml extension are parsed as YAML, all other files as JSON.
+    pub fn from_file(file_path: impl AsRef<Path>) -> Result<Self> {
+        let file_path = file_path.as_ref();
+        let content = fs::read_to_string(file_path)?;
+        match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => AgentDescription::from_yaml_str(&content),
+            _ => AgentDescription::from_json_str(&content),
+        }
+    }
+}
+
+impl IntentDescription {
+    /// Converts intent description into dialogflow intent (without name).
+    pub fn to_intent(&self) -> Intent {
+        let mut intent = Intent {
+            display_name: self.display_name.clone(),
+            ..Default::default()
+        };
+        self.apply_to(&mut intent);
+        intent
+    }
+
+    /// Overwrites described attributes of existing intent. Other intent attributes
+    /// (contexts, parameters, followups...) are left untouched. Live training phrases
+    /// matching the description are kept as they are (including their annotations),
+    /// only platform independent text messages are replaced.
+    fn apply_to(&self, intent: &mut Intent) {
+        if let Some(action) = &self.action {
+            intent.action = action.clone();
+        }
+        if let Some(events) = &self.events {
+            intent.events = events.clone();
+        }
+        intent.training_phrases = self
+            .training_phrases
+            .iter()
+            .map(|phrase_desc| {
+                intent
+                    .training_phrases
+                    .iter()
+                    .find(|phrase| phrase_desc.matches(phrase))
+                    .cloned()
+                    .unwrap_or_else(|| phrase_desc.to_training_phrase())
+            })
+            .collect();
+
+        let responses = match &self.responses {
+            Some(responses) => responses,
+            None => return,
+        };
+        let position = intent
+            .messages
+            .iter()
+            .position(is_default_text_message)
+            .unwrap_or(0);
+        intent
+            .messages
+            .retain(|message| !is_default_text_message(message));
+        if !responses.is_empty() {
+            intent.messages.insert(
+                position.min(intent.messages.len()),
+                IntentMessage {
+                    platform: Platform::Unspecified as i32,
+                    message: Some(IntentMessageKind::Text(IntentMessageText {
+                        text: responses.clone(),
+                    })),
+                },
+            );
+        }
+    }
+
+    /// Returns true if live intent corresponds to this description.
+    fn matches(&self, intent: &Intent) -> bool {
+        let training_phrases_match = intent.training_phrases.len() == self.training_phrases.len()
+            && self.training_phrases.iter().all(|phrase_desc| {
+                intent
+                    .training_phrases
+                    .iter()
+                    .any(|phrase| phrase_desc.matches(phrase))
+            });
+
+        let responses: Vec<String> = intent
+            .messages
+            .iter()
+            .filter(|message| is_default_text_message(message))
+            .filter_map(|message| match &message.message {
+                Some(IntentMessageKind::Text(text)) => Some(text.text.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+
+        let events_match = self.events.as_ref().is_none_or(|expected_events| {
+            let mut events = intent.events.clone();
+            events.sort();
+            let mut expected_events = expected_events.clone();
+            expected_events.sort();
+            events == expected_events
+        });
+
+        self.action
+            .as_ref()
+            .is_none_or(|action| *action == intent.action)
+            && events_match
+            && training_phrases_match
+            && self
+                .responses
+                .as_ref()
+                .is_none_or(|expected_responses| responses == *expected_responses)
+    }
+}
+
+impl EntityTypeDescription {
+    /// Converts entity type description into dialogflow entity type (without name).
+    pub fn to_entity_type(&self) -> EntityType {
+        let mut entity_type = EntityType {
+            display_name: self.display_name.clone(),
+            ..Default::default()
+        };
+        self.apply_to(&mut entity_type);
+        entity_type
+    }
+
+    fn apply_to(&self, entity_type: &mut EntityType) {
+        entity_type.kind = self.kind() as i32;
+        entity_type.auto_expansion_mode = if self.auto_expansion {
+            AutoExpansionMode::Default as i32
+        } else {
+            AutoExpansionMode::Unspecified as i32
+        };
+        entity_type.enable_fuzzy_extraction = self.fuzzy_extraction;
+        entity_type.entities = self.entities();
+    }
+
+    fn kind(&self) -> EntityTypeKind {
+        if self.list {
+            EntityTypeKind::List
+        } else {
+            EntityTypeKind::Map
+        }
+    }
+
+    fn entities(&self) -> Vec<Entity> {
+        self.entities
+            .iter()
+            .map(|entity| {
+                let synonyms = if self.list || entity.synonyms.is_empty() {
+                    vec![entity.value.clone()]
+                } else {
+                    entity.synonyms.clone()
+                };
+                Entity {
+                    value: entity.value.clone(),
+                    synonyms,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if live entity type corresponds to this description.
+    fn matches(&self, entity_type: &EntityType) -> bool {
+        let normalize = |entities: &[Entity]| {
+            let mut entities: Vec<(String, Vec<String>)> = entities
+                .iter()
+                .map(|entity| {
+                    let mut synonyms = entity.synonyms.clone();
+                    synonyms.sort();
+                    (entity.value.clone(), synonyms)
+                })
+                .collect();
+            entities.sort();
+            entities
+        };
+        let expected = self.to_entity_type();
+        entity_type.kind == expected.kind
+            && entity_type.auto_expansion_mode == expected.auto_expansion_mode
+            && entity_type.enable_fuzzy_extraction == expected.enable_fuzzy_extraction
+            && normalize(&entity_type.entities) == normalize(&expected.entities)
+    }
+}
+
+/// Differences between local agent description and live agent.
+#[derive(Debug, Clone, Default)]
+pub struct AgentDiff {
+    /// intents present in description only
+    pub intents_to_create: Vec<Intent>,
+
+    /// live intents which differ from description (already updated according to description)
+    pub intents_to_update: Vec<Intent>,
+
+    /// live intents not present in description
+    pub intents_to_delete: Vec<Intent>,
+
+    /// entity types present in description only
+    pub entity_types_to_create: Vec<EntityType>,
+
+    /// live entity types which differ from description (already updated according to description)
+    pub entity_types_to_update: Vec<EntityType>,
+
+    /// live entity types not present in description
+    pub entity_types_to_delete: Vec<EntityType>,
+}
+
+impl AgentDiff {
+    /// Returns true if live agent corresponds to the description.
+    /// Live intents/entity types missing in the description are considered only
+    /// if include_deletes is true.
+    pub fn is_empty(&self, include_deletes: bool) -> bool {
+        self.intents_to_create.is_empty()
+            && self.intents_to_update.is_empty()
+            && self.entity_types_to_create.is_empty()
+            && self.entity_types_to_update.is_empty()
+            && (!include_deletes
+                || (self.intents_to_delete.is_empty() && self.entity_types_to_delete.is_empty()))
+    }
+}
+
+/// Compares local agent description with live agent intents
+/// (retrieved with IntentView::Full) and entity types.
+pub fn diff_agent(
+    description: &AgentDescription,
+    live_intents: &[Intent],
+    live_entity_types: &[EntityType],
+) -> AgentDiff {
+    let mut diff = AgentDiff::default();
+
+    let live_intents_map: HashMap<&str, &Intent> = live_intents
+        .iter()
+        .map(|intent| (intent.display_name.as_str(), intent))
+        .collect();
+    for intent_desc in description.intents.iter() {
+        match live_intents_map.get(intent_desc.display_name.as_str()) {
+            None => diff.intents_to_create.push(intent_desc.to_intent()),
+            Some(live_intent) => {
+                if !intent_desc.matches(live_intent) {
+                    let mut intent = (*live_intent).clone();
+                    intent_desc.apply_to(&mut intent);
+                    diff.intents_to_update.push(intent);
+                }
+            }
+        }
+    }
+    diff.intents_to_delete = live_intents
+        .iter()
+        .filter(|intent| {
+            !description
+                .intents
+                .iter()
+                .any(|desc| desc.display_name == intent.display_name)
+        })
+        .cloned()
+        .collect();
+
+    let live_entity_types_map: HashMap<&str, &EntityType> = live_entity_types
+        .iter()
+        .map(|entity_type| (entity_type.display_name.as_str(), entity_type))
+        .collect();
+    for entity_type_desc in description.entity_types.iter() {
+        match live_entity_types_map.get(entity_type_desc.display_name.as_str()) {
+            None => diff
+                .entity_types_to_create
+                .push(entity_type_desc.to_entity_type()),
+            Some(live_entity_type) => {
+                if !entity_type_desc.matches(live_entity_type) {
+                    let mut entity_type = (*live_entity_type).clone();
+                    entity_type_desc.apply_to(&mut entity_type);
+                    diff.entity_types_to_update.push(entity_type);
+                }
+            }
+        }
+    }
+    diff.entity_types_to_delete = live_entity_types
+        .iter()
+        .filter(|entity_type| {
+            !description
+                .entity_types
+                .iter()
+                .any(|desc| desc.display_name == entity_type.display_name)
+        })
+        .cloned()
+        .collect();
+
+    diff
+}
+
+/// Reconciles live agent with local agent description. Entity types are
+/// synchronized first since intents might reference them. Updates use update mask
+/// limited to the attributes covered by the description. Live intents and entity types
+/// not present in the description are deleted only if delete_missing is true.
+/// Returns applied differences. To only inspect differences without applying them
+/// use diff_agent.
+#[allow(clippy::too_many_arguments)]
+pub async fn sync_agent(
+    intents_client: &mut IntentsClient,
+    entity_types_client: &mut EntityTypesClient,
+    // agent string, i.e. projects/<Project ID>/agent
+    parent: &str,
+    language_code: Option<String>,
+    description: &AgentDescription,
+    delete_missing: bool,
+    check_interval_ms: Option<u64>,
+    options: Option<CallOptions>,
+) -> Result<AgentDiff> {
+    let live_intents = intents_client
+        .list_all_intents(
+            parent,
+            language_code.clone(),
+            IntentView::Full,
+            options.clone(),
+        )
+        .await?;
+    let live_entity_types = entity_types_client
+        .list_all_entity_types(parent, language_code.clone(), options.clone())
+        .await?;
+
+    let diff = diff_agent(description, &live_intents, &live_entity_types);
+
+    // created objects are written as a whole, updates touch only the described fields
+    for (entity_types, update_mask) in [
+        (&diff.entity_types_to_create, None),
+        (
+            &diff.entity_types_to_update,
+            Some(entity_type_update_mask()),
+        ),
+    ] {
+        if !entity_types.is_empty() {
+            entity_types_client
+                .batch_update_entity_types_wait(
+                    parent,
+                    entity_types.clone(),
+                    language_code.clone(),
+                    update_mask,
+                    check_interval_ms,
+                    options.clone(),
+                )
+                .await?;
+        }
+    }
+
+    // intents are updated in batches sharing the same update mask
+    let mut intent_batches: Vec<(Option<FieldMask>, Vec<Intent>)> =
+        vec![(None, diff.intents_to_create.clone())];
+    for intent in diff.intents_to_update.iter() {
+        let update_mask = description
+            .intents
+            .iter()
+            .find(|desc| desc.display_name == intent.display_name)
+            .map(intent_update_mask);
+        match intent_batches
+            .iter_mut()
+            .find(|(mask, _)| mask.is_some() && *mask == update_mask)
+        {
+            Some((_, intents)) => intents.push(intent.clone()),
+            None => intent_batches.push((update_mask, vec![intent.clone()])),
+        }
+    }
+    for (update_mask, intents) in intent_batches {
+        if !intents.is_empty() {
+            intents_client
+                .batch_update_intents_wait(
+                    parent,
+                    intents,
+                    language_code.clone(),
+                    update_mask,
+                    check_interval_ms,
+                    options.clone(),
+                )
+                .await?;
+        }
+    }
+
+    if delete_missing {
+        if !diff.intents_to_delete.is_empty() {
+            intents_client
+                .batch_delete_intents_wait(
+                    parent,
+                    diff.intents_to_delete.clone(),
+                    check_interval_ms,
+                    options.clone(),
+                )
+                .await?;
+        }
+        if !diff.entity_types_to_delete.is_empty() {
+            let names = diff
+                .entity_types_to_delete
+                .iter()
+                .map(|entity_type| entity_type.name.clone())
+                .collect();
+            entity_types_client
+                .batch_delete_entity_types_wait(parent, names, check_interval_ms, options)
+                .await?;
+        }
+    }
+
+    Ok(diff)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn sample_description() -> AgentDescription {
+        let yaml_str = r#"
+intents:
+  - displayName: order.pizza
+    action: order_pizza
+    trainingPhrases:
+      - I would like to order pizza
+      - one pizza please
+    responses:
+      - Which pizza would you like?
+  - displayName: goodbye
+    trainingPhrases:
+      - bye
+entityTypes:
+  - displayName: topping
+    entities:
+      - value: mushrooms
+        synonyms: [mushrooms, champignons]
+      - value: ham
+"#;
+        AgentDescription::from_yaml_str(yaml_str).unwrap()
+    }
+
+    // cargo test -- --show-output test_agent_description_json
+    #[test]
+    fn test_agent_description_json() {
+        let json_str = r#"
+            {
+                "intents": [
+                    {
+                        "displayName": "goodbye",
+                        "trainingPhrases": ["bye"]
+                    }
+                ]
+            }
+            "#;
+        let description = AgentDescription::from_json_str(json_str).unwrap();
+        assert_eq!(description.intents.len(), 1);
+        assert!(description.entity_types.is_empty());
+
+        let err = AgentDescription::from_json_str(r#"{"intents": [{"events": []}]}"#).unwrap_err();
+        assert!(err.message.contains("intents[0]"));
+    }
+
+    // cargo test -- --show-output test_diff_agent_empty_live_agent
+    #[test]
+    fn test_diff_agent_empty_live_agent() {
+        let description = sample_description();
+        let diff = diff_agent(&description, &[], &[]);
+        assert_eq!(diff.intents_to_create.len(), 2);
+        assert_eq!(diff.entity_types_to_create.len(), 1);
+        assert!(diff.intents_to_update.is_empty());
+        assert!(!diff.is_empty(false));
+
+        let entity_type = &diff.entity_types_to_create[0];
+        assert_eq!(entity_type.kind, EntityTypeKind::Map as i32);
+        assert_eq!(entity_type.entities[1].synonyms, vec!["ham".to_string()]);
+    }
+
+    // cargo test -- --show-output test_diff_agent_in_sync
+    #[test]
+    fn test_diff_agent_in_sync() {
+        let description = sample_description();
+        let mut live_intents: Vec<Intent> = description
+            .intents
+            .iter()
+            .map(|desc| desc.to_intent())
+            .collect();
+        live_intents[0].name = "projects/foo/agent/intents/1".to_string();
+        // training phrases order does not matter
+        live_intents[0].training_phrases.reverse();
+        let fallback = Intent {
+            name: "projects/foo/agent/intents/2".to_string(),
+            display_name: "Default Fallback Intent".to_string(),
+            is_fallback: true,
+            ..Default::default()
+        };
+        live_intents.push(fallback);
+        let live_entity_types: Vec<EntityType> = description
+            .entity_types
+            .iter()
+            .map(|desc| desc.to_entity_type())
+            .collect();
+
+        let diff = diff_agent(&description, &live_intents, &live_entity_types);
+        assert!(diff.is_empty(false));
+        assert!(!diff.is_empty(true));
+        assert_eq!(
+            diff.intents_to_delete[0].display_name,
+            "Default Fallback Intent"
+        );
+    }
+
+    // cargo test -- --show-output test_diff_agent_update
+    #[test]
+    fn test_diff_agent_update() {
+        let description = sample_description();
+        let mut live_intent = description.intents[0].to_intent();
+        live_intent.name = "projects/foo/agent/intents/1".to_string();
+        live_intent.training_phrases.pop();
+        live_intent.input_context_names = vec!["ctx".to_string()];
+
+        let diff = diff_agent(&description, &[live_intent], &[]);
+        assert_eq!(diff.intents_to_update.len(), 1);
+        let updated = &diff.intents_to_update[0];
+        // name and attributes not covered by description are preserved
+        assert_eq!(updated.name, "projects/foo/agent/intents/1");
+        assert_eq!(updated.input_context_names, vec!["ctx".to_string()]);
+        assert_eq!(updated.training_phrases.len(), 2);
+    }
+
+    // cargo test -- --show-output test_diff_agent_preserves_annotations
+    #[test]
+    fn test_diff_agent_preserves_annotations() {
+        let description = sample_description();
+        let mut live_intent = description.intents[0].to_intent();
+        live_intent.name = "projects/foo/agent/intents/1".to_string();
+        // plain text phrase annotated in the live agent
+        live_intent.training_phrases[1] = TrainingPhrase {
+            name: "phrase-1".to_string(),
+            r#type: TrainingPhraseType::Example as i32,
+            parts: vec![
+                TrainingPhrasePart {
+                    text: "one".to_string(),
+                    entity_type: "@sys.number".to_string(),
+                    alias: "count".to_string(),
+                    user_defined: true,
+                },
+                TrainingPhrasePart {
+                    text: " pizza please".to_string(),
+                    ..Default::default()
+                },
+            ],
+            times_added_count: 0,
+        };
+        let telephony_message = IntentMessage {
+            platform: Platform::Telephony as i32,
+            message: Some(IntentMessageKind::Text(IntentMessageText {
+                text: vec!["Which pizza would you like? Say it after the beep.".to_string()],
+            })),
+        };
+        live_intent.messages.push(telephony_message.clone());
+
+        // annotations and platform specific messages are not a difference
+        let diff = diff_agent(&description, &[live_intent.clone()], &[]);
+        assert!(diff.intents_to_update.is_empty());
+
+        // response change preserves annotations and platform specific messages
+        let mut description = description;
+        description.intents[0].responses = Some(vec!["What pizza?".to_string()]);
+        let diff = diff_agent(&description, &[live_intent.clone()], &[]);
+        let updated = &diff.intents_to_update[0];
+        assert_eq!(updated.training_phrases[1], live_intent.training_phrases[1]);
+        assert_eq!(updated.messages.len(), 2);
+        assert_eq!(updated.messages[1], telephony_message);
+        assert!(is_default_text_message(&updated.messages[0]));
+
+        // annotation drift of explicitly annotated phrase is detected
+        let yaml_str = r#"
+parts:
+  - text: one
+    entityType: "@sys.number"
+    alias: amount
+    userDefined: true
+  - text: " pizza please"
+"#;
+        description.intents[0].responses = Some(vec!["Which pizza would you like?".to_string()]);
+        description.intents[0].training_phrases[1] = serde_yaml::from_str(yaml_str).unwrap();
+        let diff = diff_agent(&description, &[live_intent], &[]);
+        let updated = &diff.intents_to_update[0];
+        assert_eq!(updated.training_phrases[1].parts[0].alias, "amount");
+        assert_eq!(
+            intent_update_mask(&description.intents[0]).paths,
+            vec!["action", "training_phrases", "messages"]
+        );
+    }
+
+    // cargo test -- --show-output test_diff_agent_missing_attributes
+    #[test]
+    fn test_diff_agent_missing_attributes() {
+        // goodbye intent describes training phrases only
+        let mut description = sample_description();
+        let mut live_intent = description.intents[1].to_intent();
+        live_intent.action = "end_call".to_string();
+        live_intent.events = vec!["END".to_string()];
+        live_intent.messages = vec![IntentMessage {
+            platform: Platform::Unspecified as i32,
+            message: Some(IntentMessageKind::Text(IntentMessageText {
+                text: vec!["Goodbye!".to_string()],
+            })),
+        }];
+
+        // attributes missing in the description are not a difference
+        let diff = diff_agent(&description, &[live_intent.clone()], &[]);
+        assert!(diff.intents_to_update.is_empty());
+
+        // update keeps live attributes missing in the description
+        description.intents[1].training_phrases =
+            vec![TrainingPhraseDescription::Text("see you".to_string())];
+        let diff = diff_agent(&description, &[live_intent.clone()], &[]);
+        let updated = &diff.intents_to_update[0];
+        assert_eq!(updated.action, "end_call");
+        assert_eq!(updated.events, live_intent.events);
+        assert_eq!(updated.messages, live_intent.messages);
+        assert_eq!(
+            intent_update_mask(&description.intents[1]).paths,
+            vec!["training_phrases"]
+        );
+
+        // empty responses present in the description remove the live ones
+        description.intents[1].responses = Some(vec![]);
+        let diff = diff_agent(&description, &[live_intent], &[]);
+        assert!(diff.intents_to_update[0].messages.is_empty());
+    }
+}
@@ -0,0 +1,285 @@
+//! This module contains Google Dialogflow Agents client
+//! that can be used for agent management: export, import,
+//! restore and training of the agent. These operations are long running
+//! operations. Client provides methods to wait for their completion.
+//! This client is easily cloneable.
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    agents_client::AgentsClient as GrpcAgentsClient, export_agent_response, import_agent_request,
+    restore_agent_request, Agent, ExportAgentRequest, ExportAgentResponse, GetAgentRequest,
+    GetValidationResultRequest, ImportAgentRequest, RestoreAgentRequest, TrainAgentRequest,
+    ValidationResult,
+};
+use crate::api::grpc::google::longrunning::Operation;
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::{GRPC_API_DOMAIN, GRPC_API_URL};
+use crate::errors::{Error, Result};
+use crate::longrunning::OperationsClient;
+use crate::transport::{ClientConfig, GrpcChannel};
+use prost::Message;
+use std::path::Path;
+use tokio::fs;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+/// Google Dialogflow agents client.
+/// Used for agent management APIs. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct AgentsClient {
+    /// internal GRPC dialogflow agents client
+    agents_client: GrpcAgentsClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// long running operations client used to wait for completion of agent operations
+    operations_client: OperationsClient,
+}
+
+impl AgentsClient {
+    /// Creates new agents client using GCP project JSON credentials.
+    pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        AgentsClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(AgentsClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new agents client from existing GRPC channel and token interceptor.
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        AgentsClient {
+            agents_client: GrpcAgentsClient::with_interceptor(
+                channel.clone(),
+                token_interceptor.clone(),
+            ),
+            operations_client: OperationsClient::new(channel, token_interceptor),
+        }
+    }
+
+    /// Retrieves the agent associated with the specified project
+    /// (parent is specified in format projects/<Project ID>).
+    pub async fn get_agent(
+        &mut self,
+        parent: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Agent> {
+        let request = GetAgentRequest {
+            parent: parent.into(),
+        };
+        let response: TonicResponse<Agent> = self
+            .agents_client
+            .get_agent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Initiates training of the agent. Returns long running operation,
+    /// use long_running_wait to wait until training is done.
+    pub async fn train_agent(
+        &mut self,
+        parent: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let request = TrainAgentRequest {
+            parent: parent.into(),
+        };
+        let response: TonicResponse<Operation> = self
+            .agents_client
+            .train_agent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Initiates export of the agent. Returns long running operation
+    /// with ExportAgentResponse as a result. Use long_running_wait to wait until export is done.
+    pub async fn export_agent(
+        &mut self,
+        request: ExportAgentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let response: TonicResponse<Operation> = self
+            .agents_client
+            .export_agent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Initiates import of the agent. Replaces the current agent version with a new one.
+    /// Returns long running operation, use long_running_wait to wait until import is done.
+    pub async fn import_agent(
+        &mut self,
+        request: ImportAgentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let response: TonicResponse<Operation> = self
+            .agents_client
+            .import_agent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Initiates restore of the agent. Replaces the current agent version with a new one
+    /// and deletes all intents and entity types of older version.
+    /// Returns long running operation, use long_running_wait to wait until restore is done.
+    pub async fn restore_agent(
+        &mut self,
+        request: RestoreAgentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let response: TonicResponse<Operation> = self
+            .agents_client
+            .restore_agent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns validation result of the agent. Validation result is updated
+    /// when training is completed. If language_code is not specified,
+    /// agent's default language is used.
+    pub async fn get_validation_result(
+        &mut self,
+        parent: impl Into<String>,
+        language_code: Option<String>,
+        options: Option<CallOptions>,
+    ) -> Result<ValidationResult> {
+        let request = GetValidationResultRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+        };
+        let response: TonicResponse<ValidationResult> = self
+            .agents_client
+            .get_validation_result(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Waits for completion of long running operation returned
+    /// by train_agent, export_agent, import_agent or restore_agent.
+    /// Long running operation result is casted into struct R,
+    /// e.g. ExportAgentResponse for export_agent or () for operations without response.
+    /// Operation status is checked every check_interval_ms ms. If check_interval_ms
+    /// is not specified default interval check is 1 sec.
+    pub async fn long_running_wait<R: Message + Default>(
+        &mut self,
+        operation: Operation,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<R>> {
+        self.operations_client
+            .long_running_wait(operation, check_interval_ms, options)
+            .await
+    }
+
+    /// Trains the agent and waits until training is done.
+    pub async fn train_agent_wait(
+        &mut self,
+        parent: impl Into<String>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let operation = self.train_agent(parent, options.clone()).await?;
+        self.long_running_wait::<()>(operation, check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Exports the agent as zip file and stores it into provided local file.
+    pub async fn export_agent_to_file(
+        &mut self,
+        parent: impl Into<String>,
+        file_path: impl AsRef<Path>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = ExportAgentRequest {
+            parent: parent.into(),
+            // when agent_uri is empty agent zip is returned inline in the response
+            agent_uri: "".to_string(),
+        };
+        let operation = self.export_agent(request, options.clone()).await?;
+        let response: Option<ExportAgentResponse> = self
+            .long_running_wait(operation, check_interval_ms, options)
+            .await?;
+
+        match response.and_then(|response| response.agent) {
+            Some(export_agent_response::Agent::AgentContent(agent_content)) => {
+                fs::write(file_path, agent_content).await?;
+                Ok(())
+            }
+            Some(export_agent_response::Agent::AgentUri(agent_uri)) => Err(Error::new(format!(
+                "AgentsClient.export_agent_to_file: agent exported to {} instead of inline content",
+                agent_uri
+            ))),
+            None => Err(Error::new(
+                "AgentsClient.export_agent_to_file: export agent operation returned no agent"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Imports the agent from local zip file and waits until import is done.
+    /// Intents and entity types with the same name are replaced, other
+    /// intents and entity types of the agent are left untouched.
+    pub async fn import_agent_from_file(
+        &mut self,
+        parent: impl Into<String>,
+        file_path: impl AsRef<Path>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = ImportAgentRequest {
+            parent: parent.into(),
+            agent: Some(import_agent_request::Agent::AgentContent(
+                fs::read(file_path).await?,
+            )),
+        };
+        let operation = self.import_agent(request, options.clone()).await?;
+        self.long_running_wait::<()>(operation, check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Restores the agent from local zip file and waits until restore is done.
+    /// All intents and entity types of the agent are replaced with the ones from zip file.
+    pub async fn restore_agent_from_file(
+        &mut self,
+        parent: impl Into<String>,
+        file_path: impl AsRef<Path>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = RestoreAgentRequest {
+            parent: parent.into(),
+            agent: Some(restore_agent_request::Agent::AgentContent(
+                fs::read(file_path).await?,
+            )),
+        };
+        let operation = self.restore_agent(request, options.clone()).await?;
+        self.long_running_wait::<()>(operation, check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Convenience function to return properly formatted agent parent string.
+    pub fn get_agent_parent_string(project_id: &str) -> String {
+        format!("projects/{}", project_id)
+    }
+}
@@ -0,0 +1,263 @@
+//! This module contains Google Dialogflow Contexts client
+//! that can be used to list, create, update and delete
+//! contexts of given dialogflow session. This is simple
+//! request/response client, no streaming is involved. As a result
+//! this client is easily cloneable.
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    contexts_client::ContextsClient as GrpcContextsClient, Context, CreateContextRequest,
+    DeleteAllContextsRequest, DeleteContextRequest, GetContextRequest, ListContextsRequest,
+    ListContextsResponse, UpdateContextRequest,
+};
+use crate::api::protobuf::{struct_to_type, type_to_struct};
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::{GRPC_API_DOMAIN, GRPC_API_URL};
+use crate::errors::Result;
+use crate::transport::{ClientConfig, GrpcChannel};
+use prost_types::FieldMask;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+/// Google Dialogflow contexts client.
+/// Used for management of session contexts. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct ContextsClient {
+    /// internal GRPC dialogflow contexts client
+    contexts_client: GrpcContextsClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+}
+
+impl ContextsClient {
+    /// Creates new contexts client using GCP project JSON credentials.
+    /// If sessions client is already available consider using
+    /// SessionsClient::contexts_client instead which reuses existing GRPC channel.
+    pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        ContextsClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(ContextsClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new contexts client from existing GRPC channel and token interceptor.
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        ContextsClient {
+            contexts_client: GrpcContextsClient::with_interceptor(channel, token_interceptor),
+        }
+    }
+
+    /// Returns single page of contexts of given session. Session is
+    /// specified in format projects/<Project ID>/agent/sessions/<Session ID>
+    /// (see get_session_string). If page_size is not specified
+    /// default page size of the API (100) is used.
+    pub async fn list_contexts(
+        &mut self,
+        session: impl Into<String>,
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        options: Option<CallOptions>,
+    ) -> Result<ListContextsResponse> {
+        let request = ListContextsRequest {
+            parent: session.into(),
+            page_size: page_size.unwrap_or(0),
+            page_token: page_token.unwrap_or_default(),
+        };
+        let response: TonicResponse<ListContextsResponse> = self
+            .contexts_client
+            .list_contexts(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns all contexts of given session. Iterates over all
+    /// result pages returned by list_contexts.
+    pub async fn list_all_contexts(
+        &mut self,
+        session: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<Context>> {
+        let session = session.into();
+        let mut contexts = vec![];
+        let mut page_token = None;
+        loop {
+            let response = self
+                .list_contexts(session.clone(), None, page_token, options.clone())
+                .await?;
+            contexts.extend(response.contexts);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            page_token = Some(response.next_page_token);
+        }
+        Ok(contexts)
+    }
+
+    /// Retrieves the specified context. Context name is specified in format
+    /// projects/<Project ID>/agent/sessions/<Session ID>/contexts/<Context ID>
+    /// (see get_context_string).
+    pub async fn get_context(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Context> {
+        let request = GetContextRequest { name: name.into() };
+        let response: TonicResponse<Context> = self
+            .contexts_client
+            .get_context(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Creates a context in given session. If the specified context already exists,
+    /// overrides the context.
+    pub async fn create_context(
+        &mut self,
+        session: impl Into<String>,
+        context: Context,
+        options: Option<CallOptions>,
+    ) -> Result<Context> {
+        let request = CreateContextRequest {
+            parent: session.into(),
+            context: Some(context),
+        };
+        let response: TonicResponse<Context> = self
+            .contexts_client
+            .create_context(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Updates the specified context. If update_mask is not provided
+    /// all fields of the context are updated.
+    pub async fn update_context(
+        &mut self,
+        context: Context,
+        update_mask: Option<FieldMask>,
+        options: Option<CallOptions>,
+    ) -> Result<Context> {
+        let request = UpdateContextRequest {
+            context: Some(context),
+            update_mask,
+        };
+        let response: TonicResponse<Context> = self
+            .contexts_client
+            .update_context(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Changes lifespan (number of conversational turns after which
+    /// context expires) of the specified context. Context parameters are left untouched.
+    pub async fn set_context_lifespan(
+        &mut self,
+        name: impl Into<String>,
+        lifespan_count: i32,
+        options: Option<CallOptions>,
+    ) -> Result<Context> {
+        let context = Context {
+            name: name.into(),
+            lifespan_count,
+            parameters: None,
+        };
+        let update_mask = FieldMask {
+            paths: vec!["lifespan_count".to_string()],
+        };
+        self.update_context(context, Some(update_mask), options)
+            .await
+    }
+
+    /// Deletes the specified context.
+    pub async fn delete_context(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeleteContextRequest { name: name.into() };
+        self.contexts_client
+            .delete_context(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes all active contexts in the specified session.
+    pub async fn delete_all_contexts(
+        &mut self,
+        session: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeleteAllContextsRequest {
+            parent: session.into(),
+        };
+        self.contexts_client
+            .delete_all_contexts(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Convenience function to return properly formatted context string.
+    pub fn get_context_string(project_id: &str, session_id: &str, context_id: &str) -> String {
+        super::get_context_string(project_id, session_id, context_id)
+    }
+
+    /// Convenience function to return properly formatted session string.
+    pub fn get_session_string(project_id: &str, session_id: &str) -> String {
+        super::get_session_string(project_id, session_id)
+    }
+}
+
+impl Context {
+    /// Creates new context with given name, lifespan and parameters
+    /// serialized from arbitrary user defined type.
+    pub fn with_parameters<T: Serialize>(
+        name: impl Into<String>,
+        lifespan_count: i32,
+        parameters: &T,
+    ) -> Result<Self> {
+        Ok(Context {
+            name: name.into(),
+            lifespan_count,
+            parameters: Some(type_to_struct(parameters)?),
+        })
+    }
+
+    /// Returns context parameters deserialized into arbitrary user defined type.
+    /// Returns None if context does not contain any parameters.
+    pub fn parameters<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        match &self.parameters {
+            Some(parameters) => Ok(Some(struct_to_type(parameters)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets context parameters serialized from arbitrary user defined type.
+    pub fn set_parameters<T: Serialize>(&mut self, parameters: &T) -> Result<()> {
+        self.parameters = Some(type_to_struct(parameters)?);
+        Ok(())
+    }
+
+    /// Returns context id, i.e. last segment of context name.
+    pub fn context_id(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or_default()
+    }
+}
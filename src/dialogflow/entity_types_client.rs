@@ -0,0 +1,376 @@
+//! This module contains Google Dialogflow Entity types client
+//! that can be used for management of agent entity types and their entities.
+//! Batch operations are long running operations, client provides methods
+//! to wait for their completion. This client is easily cloneable.
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    batch_update_entity_types_request::EntityTypeBatch as EntityTypeBatchSource,
+    entity_type::Entity, entity_types_client::EntityTypesClient as GrpcEntityTypesClient,
+    BatchCreateEntitiesRequest, BatchDeleteEntitiesRequest, BatchDeleteEntityTypesRequest,
+    BatchUpdateEntitiesRequest, BatchUpdateEntityTypesRequest, BatchUpdateEntityTypesResponse,
+    CreateEntityTypeRequest, DeleteEntityTypeRequest, EntityType, EntityTypeBatch,
+    GetEntityTypeRequest, ListEntityTypesRequest, ListEntityTypesResponse, UpdateEntityTypeRequest,
+};
+use crate::api::grpc::google::longrunning::Operation;
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::{GRPC_API_DOMAIN, GRPC_API_URL};
+use crate::errors::Result;
+use crate::longrunning::OperationsClient;
+use crate::transport::{ClientConfig, GrpcChannel};
+use async_stream::try_stream;
+use futures_core::stream::Stream;
+use prost_types::FieldMask;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+/// Google Dialogflow entity types client.
+/// Used for management of agent entity types. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct EntityTypesClient {
+    /// internal GRPC dialogflow entity types client
+    entity_types_client: GrpcEntityTypesClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// long running operations client used to wait for completion of batch operations
+    operations_client: OperationsClient,
+}
+
+impl EntityTypesClient {
+    /// Creates new entity types client using GCP project JSON credentials.
+    pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        EntityTypesClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(EntityTypesClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new entity types client from existing GRPC channel and token interceptor.
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        EntityTypesClient {
+            entity_types_client: GrpcEntityTypesClient::with_interceptor(
+                channel.clone(),
+                token_interceptor.clone(),
+            ),
+            operations_client: OperationsClient::new(channel, token_interceptor),
+        }
+    }
+
+    /// Returns single page of agent entity types.
+    pub async fn list_entity_types(
+        &mut self,
+        request: ListEntityTypesRequest,
+        options: Option<CallOptions>,
+    ) -> Result<ListEntityTypesResponse> {
+        let response: TonicResponse<ListEntityTypesResponse> = self
+            .entity_types_client
+            .list_entity_types(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns asynchronous stream of all agent entity types. Result pages
+    /// are retrieved lazily as the stream is consumed. Parent is specified
+    /// in format projects/<Project ID>/agent. If language_code is not specified,
+    /// agent's default language is used.
+    #[allow(unreachable_code)]
+    pub fn list_entity_types_stream(
+        &mut self,
+        parent: impl Into<String>,
+        language_code: Option<String>,
+        options: Option<CallOptions>,
+    ) -> impl Stream<Item = Result<EntityType>> + '_ {
+        let mut request = ListEntityTypesRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+            page_size: 0,
+            page_token: "".to_string(),
+        };
+        try_stream! {
+            loop {
+                let response = self
+                    .list_entity_types(request.clone(), options.clone())
+                    .await?;
+                for entity_type in response.entity_types {
+                    yield entity_type;
+                }
+                if response.next_page_token.is_empty() {
+                    break;
+                }
+                request.page_token = response.next_page_token;
+            }
+        }
+    }
+
+    /// Returns all agent entity types. Iterates over all
+    /// result pages returned by list_entity_types.
+    pub async fn list_all_entity_types(
+        &mut self,
+        parent: impl Into<String>,
+        language_code: Option<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<EntityType>> {
+        let mut request = ListEntityTypesRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+            page_size: 0,
+            page_token: "".to_string(),
+        };
+        let mut entity_types = vec![];
+        loop {
+            let response = self
+                .list_entity_types(request.clone(), options.clone())
+                .await?;
+            entity_types.extend(response.entity_types);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            request.page_token = response.next_page_token;
+        }
+        Ok(entity_types)
+    }
+
+    /// Retrieves the specified entity type.
+    pub async fn get_entity_type(
+        &mut self,
+        request: GetEntityTypeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<EntityType> {
+        let response: TonicResponse<EntityType> = self
+            .entity_types_client
+            .get_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Creates an entity type in the specified agent.
+    pub async fn create_entity_type(
+        &mut self,
+        request: CreateEntityTypeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<EntityType> {
+        let response: TonicResponse<EntityType> = self
+            .entity_types_client
+            .create_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Updates the specified entity type.
+    pub async fn update_entity_type(
+        &mut self,
+        request: UpdateEntityTypeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<EntityType> {
+        let response: TonicResponse<EntityType> = self
+            .entity_types_client
+            .update_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes the specified entity type.
+    pub async fn delete_entity_type(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeleteEntityTypeRequest { name: name.into() };
+        self.entity_types_client
+            .delete_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Updates/creates multiple entity types in the specified agent. Entity types with name
+    /// are updated, entity types without name are created. Returns long running operation,
+    /// use batch_update_entity_types_wait to wait for its completion.
+    pub async fn batch_update_entity_types(
+        &mut self,
+        parent: impl Into<String>,
+        entity_types: Vec<EntityType>,
+        language_code: Option<String>,
+        update_mask: Option<FieldMask>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let request = BatchUpdateEntityTypesRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+            update_mask,
+            entity_type_batch: Some(EntityTypeBatchSource::EntityTypeBatchInline(
+                EntityTypeBatch { entity_types },
+            )),
+        };
+        let response: TonicResponse<Operation> = self
+            .entity_types_client
+            .batch_update_entity_types(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Updates/creates multiple entity types in the specified agent
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_update_entity_types_wait(
+        &mut self,
+        parent: impl Into<String>,
+        entity_types: Vec<EntityType>,
+        language_code: Option<String>,
+        update_mask: Option<FieldMask>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<BatchUpdateEntityTypesResponse>> {
+        let operation = self
+            .batch_update_entity_types(
+                parent,
+                entity_types,
+                language_code,
+                update_mask,
+                options.clone(),
+            )
+            .await?;
+        self.operations_client
+            .long_running_wait(operation, check_interval_ms, options)
+            .await
+    }
+
+    /// Deletes multiple entity types (specified by names) in the specified agent.
+    /// Returns long running operation, use batch_delete_entity_types_wait
+    /// to wait for its completion.
+    pub async fn batch_delete_entity_types(
+        &mut self,
+        parent: impl Into<String>,
+        entity_type_names: Vec<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let request = BatchDeleteEntityTypesRequest {
+            parent: parent.into(),
+            entity_type_names,
+        };
+        let response: TonicResponse<Operation> = self
+            .entity_types_client
+            .batch_delete_entity_types(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes multiple entity types in the specified agent
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_delete_entity_types_wait(
+        &mut self,
+        parent: impl Into<String>,
+        entity_type_names: Vec<String>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let operation = self
+            .batch_delete_entity_types(parent, entity_type_names, options.clone())
+            .await?;
+        self.operations_client
+            .long_running_wait::<()>(operation, check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Creates multiple new entities in the specified entity type
+    /// (specified in format projects/<Project ID>/agent/entityTypes/<Entity Type ID>)
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_create_entities_wait(
+        &mut self,
+        entity_type_name: impl Into<String>,
+        entities: Vec<Entity>,
+        language_code: Option<String>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = BatchCreateEntitiesRequest {
+            parent: entity_type_name.into(),
+            entities,
+            language_code: language_code.unwrap_or_default(),
+        };
+        let response: TonicResponse<Operation> = self
+            .entity_types_client
+            .batch_create_entities(new_request(request, options.clone())?)
+            .await?;
+        self.operations_client
+            .long_running_wait::<()>(response.into_inner(), check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Updates or creates multiple entities in the specified entity type
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_update_entities_wait(
+        &mut self,
+        entity_type_name: impl Into<String>,
+        entities: Vec<Entity>,
+        language_code: Option<String>,
+        update_mask: Option<FieldMask>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = BatchUpdateEntitiesRequest {
+            parent: entity_type_name.into(),
+            entities,
+            language_code: language_code.unwrap_or_default(),
+            update_mask,
+        };
+        let response: TonicResponse<Operation> = self
+            .entity_types_client
+            .batch_update_entities(new_request(request, options.clone())?)
+            .await?;
+        self.operations_client
+            .long_running_wait::<()>(response.into_inner(), check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes entities (specified by their values) in the specified entity type
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_delete_entities_wait(
+        &mut self,
+        entity_type_name: impl Into<String>,
+        entity_values: Vec<String>,
+        language_code: Option<String>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = BatchDeleteEntitiesRequest {
+            parent: entity_type_name.into(),
+            entity_values,
+            language_code: language_code.unwrap_or_default(),
+        };
+        let response: TonicResponse<Operation> = self
+            .entity_types_client
+            .batch_delete_entities(new_request(request, options.clone())?)
+            .await?;
+        self.operations_client
+            .long_running_wait::<()>(response.into_inner(), check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Convenience function to return properly formatted agent string
+    /// used as parent of entity types.
+    pub fn get_agent_string(project_id: &str) -> String {
+        super::get_agent_string(project_id)
+    }
+}
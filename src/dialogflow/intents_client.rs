@@ -0,0 +1,294 @@
+//! This module contains Google Dialogflow Intents client
+//! that can be used for management of agent intents. Batch operations
+//! are long running operations, client provides methods to wait for their completion.
+//! This client is easily cloneable.
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    batch_update_intents_request::IntentBatch as IntentBatchSource,
+    intents_client::IntentsClient as GrpcIntentsClient, BatchDeleteIntentsRequest,
+    BatchUpdateIntentsRequest, BatchUpdateIntentsResponse, CreateIntentRequest,
+    DeleteIntentRequest, GetIntentRequest, Intent, IntentBatch, IntentView, ListIntentsRequest,
+    ListIntentsResponse, UpdateIntentRequest,
+};
+use crate::api::grpc::google::longrunning::Operation;
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::{GRPC_API_DOMAIN, GRPC_API_URL};
+use crate::errors::Result;
+use crate::longrunning::OperationsClient;
+use crate::transport::{ClientConfig, GrpcChannel};
+use async_stream::try_stream;
+use futures_core::stream::Stream;
+use prost_types::FieldMask;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+/// Google Dialogflow intents client.
+/// Used for management of agent intents. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct IntentsClient {
+    /// internal GRPC dialogflow intents client
+    intents_client: GrpcIntentsClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// long running operations client used to wait for completion of batch operations
+    operations_client: OperationsClient,
+}
+
+impl IntentsClient {
+    /// Creates new intents client using GCP project JSON credentials.
+    pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        IntentsClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(IntentsClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new intents client from existing GRPC channel and token interceptor.
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        IntentsClient {
+            intents_client: GrpcIntentsClient::with_interceptor(
+                channel.clone(),
+                token_interceptor.clone(),
+            ),
+            operations_client: OperationsClient::new(channel, token_interceptor),
+        }
+    }
+
+    /// Returns single page of agent intents.
+    pub async fn list_intents(
+        &mut self,
+        request: ListIntentsRequest,
+        options: Option<CallOptions>,
+    ) -> Result<ListIntentsResponse> {
+        let response: TonicResponse<ListIntentsResponse> = self
+            .intents_client
+            .list_intents(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns asynchronous stream of all agent intents. Result pages
+    /// are retrieved lazily as the stream is consumed. Parent is specified
+    /// in format projects/<Project ID>/agent. If language_code is not specified,
+    /// agent's default language is used.
+    #[allow(unreachable_code)]
+    pub fn list_intents_stream(
+        &mut self,
+        parent: impl Into<String>,
+        language_code: Option<String>,
+        intent_view: IntentView,
+        options: Option<CallOptions>,
+    ) -> impl Stream<Item = Result<Intent>> + '_ {
+        let mut request = ListIntentsRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+            intent_view: intent_view as i32,
+            page_size: 0,
+            page_token: "".to_string(),
+        };
+        try_stream! {
+            loop {
+                let response = self
+                    .list_intents(request.clone(), options.clone())
+                    .await?;
+                for intent in response.intents {
+                    yield intent;
+                }
+                if response.next_page_token.is_empty() {
+                    break;
+                }
+                request.page_token = response.next_page_token;
+            }
+        }
+    }
+
+    /// Returns all agent intents. Iterates over all
+    /// result pages returned by list_intents.
+    pub async fn list_all_intents(
+        &mut self,
+        parent: impl Into<String>,
+        language_code: Option<String>,
+        intent_view: IntentView,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<Intent>> {
+        let mut request = ListIntentsRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+            intent_view: intent_view as i32,
+            page_size: 0,
+            page_token: "".to_string(),
+        };
+        let mut intents = vec![];
+        loop {
+            let response = self.list_intents(request.clone(), options.clone()).await?;
+            intents.extend(response.intents);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            request.page_token = response.next_page_token;
+        }
+        Ok(intents)
+    }
+
+    /// Retrieves the specified intent.
+    pub async fn get_intent(
+        &mut self,
+        request: GetIntentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Intent> {
+        let response: TonicResponse<Intent> = self
+            .intents_client
+            .get_intent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Creates an intent in the specified agent.
+    pub async fn create_intent(
+        &mut self,
+        request: CreateIntentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Intent> {
+        let response: TonicResponse<Intent> = self
+            .intents_client
+            .create_intent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Updates the specified intent.
+    pub async fn update_intent(
+        &mut self,
+        request: UpdateIntentRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Intent> {
+        let response: TonicResponse<Intent> = self
+            .intents_client
+            .update_intent(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes the specified intent and its direct or indirect followup intents.
+    pub async fn delete_intent(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeleteIntentRequest { name: name.into() };
+        self.intents_client
+            .delete_intent(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Updates/creates multiple intents in the specified agent. Intents with name
+    /// are updated, intents without name are created. Returns long running operation,
+    /// use batch_update_intents_wait to wait for its completion.
+    pub async fn batch_update_intents(
+        &mut self,
+        parent: impl Into<String>,
+        intents: Vec<Intent>,
+        language_code: Option<String>,
+        update_mask: Option<FieldMask>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let request = BatchUpdateIntentsRequest {
+            parent: parent.into(),
+            language_code: language_code.unwrap_or_default(),
+            update_mask,
+            intent_view: IntentView::Full as i32,
+            intent_batch: Some(IntentBatchSource::IntentBatchInline(IntentBatch {
+                intents,
+            })),
+        };
+        let response: TonicResponse<Operation> = self
+            .intents_client
+            .batch_update_intents(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Updates/creates multiple intents in the specified agent
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_update_intents_wait(
+        &mut self,
+        parent: impl Into<String>,
+        intents: Vec<Intent>,
+        language_code: Option<String>,
+        update_mask: Option<FieldMask>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<BatchUpdateIntentsResponse>> {
+        let operation = self
+            .batch_update_intents(parent, intents, language_code, update_mask, options.clone())
+            .await?;
+        self.operations_client
+            .long_running_wait(operation, check_interval_ms, options)
+            .await
+    }
+
+    /// Deletes multiple intents in the specified agent. Only name
+    /// of the intents needs to be filled. Returns long running operation,
+    /// use batch_delete_intents_wait to wait for its completion.
+    pub async fn batch_delete_intents(
+        &mut self,
+        parent: impl Into<String>,
+        intents: Vec<Intent>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let request = BatchDeleteIntentsRequest {
+            parent: parent.into(),
+            intents,
+        };
+        let response: TonicResponse<Operation> = self
+            .intents_client
+            .batch_delete_intents(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes multiple intents in the specified agent
+    /// and waits for completion of respective long running operation.
+    pub async fn batch_delete_intents_wait(
+        &mut self,
+        parent: impl Into<String>,
+        intents: Vec<Intent>,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let operation = self
+            .batch_delete_intents(parent, intents, options.clone())
+            .await?;
+        self.operations_client
+            .long_running_wait::<()>(operation, check_interval_ms, options)
+            .await?;
+        Ok(())
+    }
+
+    /// Convenience function to return properly formatted agent string
+    /// used as parent of intents.
+    pub fn get_agent_string(project_id: &str) -> String {
+        super::get_agent_string(project_id)
+    }
+}
@@ -0,0 +1,352 @@
+//! This module contains Google Dialogflow Session entity types client - v2beta1 API.
+//! Session entity types are used to override or supplement entities of custom
+//! entity types for the duration of particular session (e.g. contacts
+//! of the caller, names of caller's accounts). This is simple request/response
+//! client, no streaming is involved. As a result this client is easily cloneable.
+// For now to keep it as easy to maintain as possible following rule should be followed:
+// the only difference between session_entity_types_client.rs, session_entity_types_client_v2.rs
+// and session_entity_types_client_cx.rs is this comment section and import of
+// crate::api::grpc::google::cloud::dialogflow::... structs (v2beta1 vs v2 vs cx::v3)!
+// all the other code below it must be identical!
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    entity_type::Entity, session_entity_type::EntityOverrideMode,
+    session_entity_types_client::SessionEntityTypesClient as GrpcSessionEntityTypesClient,
+    CreateSessionEntityTypeRequest, DeleteSessionEntityTypeRequest, DetectIntentRequest,
+    GetSessionEntityTypeRequest, ListSessionEntityTypesRequest, ListSessionEntityTypesResponse,
+    QueryParameters, SessionEntityType, UpdateSessionEntityTypeRequest,
+};
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::{get_api_endpoint, GRPC_API_DOMAIN, GRPC_API_URL};
+use crate::errors::Result;
+use crate::transport::{ClientConfig, GrpcChannel};
+use prost_types::FieldMask;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+/// Google Dialogflow session entity types client.
+/// Used for management of per-session entity overrides. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct SessionEntityTypesClient {
+    /// internal GRPC dialogflow session entity types client
+    session_entity_types_client:
+        GrpcSessionEntityTypesClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+}
+
+impl SessionEntityTypesClient {
+    /// Creates new session entity types client using GCP project JSON credentials.
+    pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        SessionEntityTypesClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(SessionEntityTypesClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new session entity types client connected to the endpoint of given location
+    /// (e.g. europe-west1-dialogflow.googleapis.com). Agents in non-global locations
+    /// are accessible only via location specific endpoint.
+    pub async fn create_for_location(
+        google_credentials: impl AsRef<str>,
+        location: &str,
+    ) -> Result<Self> {
+        SessionEntityTypesClient::create_for_location_with_config(
+            google_credentials,
+            location,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create_for_location with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_for_location_with_config(
+        google_credentials: impl AsRef<str>,
+        location: &str,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let (domain, url) = get_api_endpoint(location);
+        let channel = new_grpc_channel_with_config(&domain, &url, None, &client_config).await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(SessionEntityTypesClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new session entity types client from existing GRPC channel and token interceptor.
+    #[allow(dead_code)]
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        SessionEntityTypesClient {
+            session_entity_types_client: GrpcSessionEntityTypesClient::with_interceptor(
+                channel,
+                token_interceptor,
+            ),
+        }
+    }
+
+    /// Returns single page of session entity types of given session.
+    /// If page_size is not specified default page size of the API (100) is used.
+    pub async fn list_session_entity_types(
+        &mut self,
+        session: impl Into<String>,
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        options: Option<CallOptions>,
+    ) -> Result<ListSessionEntityTypesResponse> {
+        let request = ListSessionEntityTypesRequest {
+            parent: session.into(),
+            page_size: page_size.unwrap_or(0),
+            page_token: page_token.unwrap_or_default(),
+        };
+        let response: TonicResponse<ListSessionEntityTypesResponse> = self
+            .session_entity_types_client
+            .list_session_entity_types(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns all session entity types of given session. Iterates over all
+    /// result pages returned by list_session_entity_types.
+    pub async fn list_all_session_entity_types(
+        &mut self,
+        session: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<SessionEntityType>> {
+        let session = session.into();
+        let mut session_entity_types = vec![];
+        let mut page_token = None;
+        loop {
+            let response = self
+                .list_session_entity_types(session.clone(), None, page_token, options.clone())
+                .await?;
+            session_entity_types.extend(response.session_entity_types);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            page_token = Some(response.next_page_token);
+        }
+        Ok(session_entity_types)
+    }
+
+    /// Retrieves the specified session entity type.
+    /// See get_session_entity_type_string for name format.
+    pub async fn get_session_entity_type(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<SessionEntityType> {
+        let request = GetSessionEntityTypeRequest { name: name.into() };
+        let response: TonicResponse<SessionEntityType> = self
+            .session_entity_types_client
+            .get_session_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Creates a session entity type in given session.
+    pub async fn create_session_entity_type(
+        &mut self,
+        session: impl Into<String>,
+        session_entity_type: SessionEntityType,
+        options: Option<CallOptions>,
+    ) -> Result<SessionEntityType> {
+        let request = CreateSessionEntityTypeRequest {
+            parent: session.into(),
+            session_entity_type: Some(session_entity_type),
+        };
+        let response: TonicResponse<SessionEntityType> = self
+            .session_entity_types_client
+            .create_session_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Updates the specified session entity type. If update_mask is not provided
+    /// all fields of the session entity type are updated.
+    pub async fn update_session_entity_type(
+        &mut self,
+        session_entity_type: SessionEntityType,
+        update_mask: Option<FieldMask>,
+        options: Option<CallOptions>,
+    ) -> Result<SessionEntityType> {
+        let request = UpdateSessionEntityTypeRequest {
+            session_entity_type: Some(session_entity_type),
+            update_mask,
+        };
+        let response: TonicResponse<SessionEntityType> = self
+            .session_entity_types_client
+            .update_session_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes the specified session entity type.
+    pub async fn delete_session_entity_type(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeleteSessionEntityTypeRequest { name: name.into() };
+        self.session_entity_types_client
+            .delete_session_entity_type(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Convenience function to return properly formatted session entity type string.
+    /// Session is full session string (see get_session_string), entity_type is
+    /// display name of the entity type (ES) or entity type id (CX).
+    pub fn get_session_entity_type_string(session: &str, entity_type: &str) -> String {
+        format!("{}/entityTypes/{}", session, entity_type)
+    }
+}
+
+impl SessionEntityType {
+    /// Creates session entity type which replaces all entities of the
+    /// respective custom entity type with provided entities for given session.
+    pub fn override_entities(name: impl Into<String>, entities: Vec<Entity>) -> Self {
+        SessionEntityType {
+            name: name.into(),
+            entity_override_mode: EntityOverrideMode::Override as i32,
+            entities,
+        }
+    }
+
+    /// Creates session entity type which adds provided entities to the
+    /// entities of the respective custom entity type for given session.
+    pub fn supplement_entities(name: impl Into<String>, entities: Vec<Entity>) -> Self {
+        SessionEntityType {
+            name: name.into(),
+            entity_override_mode: EntityOverrideMode::Supplement as i32,
+            entities,
+        }
+    }
+
+    /// Adds entity with provided value and synonyms.
+    pub fn add_entity(mut self, value: impl Into<String>, synonyms: Vec<String>) -> Self {
+        self.entities.push(Entity::with_synonyms(value, synonyms));
+        self
+    }
+}
+
+impl Entity {
+    /// Creates new entity with provided value and synonyms. If synonyms
+    /// are empty, value is used as the only synonym.
+    pub fn with_synonyms(value: impl Into<String>, synonyms: Vec<String>) -> Self {
+        let value = value.into();
+        let synonyms = if synonyms.is_empty() {
+            vec![value.clone()]
+        } else {
+            synonyms
+        };
+        Entity { value, synonyms }
+    }
+}
+
+impl QueryParameters {
+    /// Attaches session entity type inline to the query. Such session entity type
+    /// is applied only to the respective query and does not need to be created
+    /// via session entity types client.
+    pub fn add_session_entity_type(&mut self, session_entity_type: SessionEntityType) {
+        self.session_entity_types.push(session_entity_type);
+    }
+}
+
+impl DetectIntentRequest {
+    /// Attaches session entity type inline to the detect intent request.
+    /// Query parameters are created if not present yet.
+    pub fn add_session_entity_type(&mut self, session_entity_type: SessionEntityType) {
+        self.query_params
+            .get_or_insert_with(QueryParameters::default)
+            .add_session_entity_type(session_entity_type);
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::recording::{Recording, Replayer};
+
+    // cargo test -- --show-output test_session_entity_type_builders
+    #[test]
+    fn test_session_entity_type_builders() {
+        let session_entity_type = SessionEntityType::override_entities("contacts", vec![])
+            .add_entity("John", vec!["John".to_string(), "Johnny".to_string()])
+            .add_entity("Jane", vec![]);
+        assert_eq!(session_entity_type.name, "contacts");
+        assert_eq!(
+            session_entity_type.entity_override_mode,
+            EntityOverrideMode::Override as i32
+        );
+        assert_eq!(session_entity_type.entities.len(), 2);
+        assert_eq!(
+            session_entity_type.entities[0].synonyms,
+            vec!["John", "Johnny"]
+        );
+        // value is the only synonym if no synonyms are provided
+        assert_eq!(session_entity_type.entities[1].synonyms, vec!["Jane"]);
+
+        let session_entity_type = SessionEntityType::supplement_entities(
+            "contacts",
+            vec![Entity::with_synonyms("Jack", vec![])],
+        );
+        assert_eq!(
+            session_entity_type.entity_override_mode,
+            EntityOverrideMode::Supplement as i32
+        );
+        assert_eq!(session_entity_type.entities[0].value, "Jack");
+    }
+
+    // cargo test -- --show-output test_inline_session_entity_types
+    #[test]
+    fn test_inline_session_entity_types() {
+        let mut request = DetectIntentRequest::default();
+        assert!(request.query_params.is_none());
+        request.add_session_entity_type(SessionEntityType::override_entities("contacts", vec![]));
+        request.add_session_entity_type(SessionEntityType::supplement_entities("accounts", vec![]));
+        let query_params = request.query_params.unwrap();
+        assert_eq!(query_params.session_entity_types.len(), 2);
+        assert_eq!(query_params.session_entity_types[0].name, "contacts");
+        assert_eq!(query_params.session_entity_types[1].name, "accounts");
+    }
+
+    // cargo test -- --show-output test_create_for_location_with_config
+    #[tokio::test]
+    async fn test_create_for_location_with_config() {
+        // explicit config is applied, replayed client needs no credentials
+        let config = ClientConfig::new().replay(Replayer::new(Recording::default()));
+        let mut client =
+            SessionEntityTypesClient::create_for_location_with_config("", "europe-west2", config)
+                .await
+                .unwrap();
+        let status = client
+            .list_session_entity_types("session", None, None, None)
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", status).contains("no recorded call"));
+    }
+}
@@ -7,11 +7,21 @@ use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
     sessions_client::SessionsClient as GrpcSessionsClient, DetectIntentRequest,
     DetectIntentResponse, StreamingDetectIntentRequest, StreamingDetectIntentResponse,
 };
-use crate::common::{get_token, new_grpc_channel, new_interceptor, TokenInterceptor};
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::agents_client::AgentsClient;
+use crate::dialogflow::contexts_client::ContextsClient;
+use crate::dialogflow::entity_types_client::EntityTypesClient;
+use crate::dialogflow::intents_client::IntentsClient;
+use crate::dialogflow::session_entity_types_client::SessionEntityTypesClient;
+use crate::dialogflow::{GRPC_API_DOMAIN, GRPC_API_URL};
 use crate::errors::Result;
+use crate::metering::{detect_intent_usage, UsageMeter};
+use crate::transport::{ClientConfig, GrpcChannel};
 use tokio::sync::mpsc;
 use tonic::codegen::InterceptedService;
-use tonic::transport::Channel;
 use tonic::Response as TonicResponse;
 
 /// Google Dialogflow sessions client.
@@ -20,7 +30,13 @@ use tonic::Response as TonicResponse;
 #[allow(dead_code)]
 pub struct SessionsClient {
     /// internal GRPC dialogflow sessions client
-    sessions_client: GrpcSessionsClient<InterceptedService<Channel, TokenInterceptor>>,
+    sessions_client: GrpcSessionsClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// underlying GRPC channel, shared with other dialogflow clients
+    channel: GrpcChannel,
+
+    /// token interceptor, shared with other dialogflow clients
+    token_interceptor: TokenInterceptor,
 
     /// channel for sending audio data
     audio_sender: Option<mpsc::Sender<StreamingDetectIntentRequest>>,
@@ -29,31 +45,73 @@ pub struct SessionsClient {
     /// where STT results will be sent. Library client is using respective
     /// receiver to get the results.
     result_sender: Option<mpsc::Sender<StreamingDetectIntentResponse>>,
+
+    /// usage meter of billable units, see metering module
+    usage_meter: Option<UsageMeter>,
 }
 
 impl SessionsClient {
     /// Creates new sessions client using GCP project JSON credentials
     /// This client should be used for synchronous invocation (detect_intent)
     pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
-        let channel = new_grpc_channel(
-            "dialogflow.googleapis.com",
-            "https://dialogflow.googleapis.com",
-            None,
+        SessionsClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
         )
-        .await?;
+        .await
+    }
 
-        let token_header_val = get_token(google_credentials)?;
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+        let token_interceptor = new_interceptor(token_header_val);
 
         let sessions_client =
-            GrpcSessionsClient::with_interceptor(channel, new_interceptor(token_header_val));
+            GrpcSessionsClient::with_interceptor(channel.clone(), token_interceptor.clone());
 
         Ok(SessionsClient {
             sessions_client,
+            channel,
+            token_interceptor,
             audio_sender: None,
             result_sender: None,
+            usage_meter: None,
         })
     }
 
+    /// Creates contexts client sharing GRPC channel and credentials of this sessions client.
+    pub fn contexts_client(&self) -> ContextsClient {
+        ContextsClient::new(self.channel.clone(), self.token_interceptor.clone())
+    }
+
+    /// Creates agents client sharing GRPC channel and credentials of this sessions client.
+    pub fn agents_client(&self) -> AgentsClient {
+        AgentsClient::new(self.channel.clone(), self.token_interceptor.clone())
+    }
+
+    /// Creates intents client sharing GRPC channel and credentials of this sessions client.
+    pub fn intents_client(&self) -> IntentsClient {
+        IntentsClient::new(self.channel.clone(), self.token_interceptor.clone())
+    }
+
+    /// Creates entity types client sharing GRPC channel and credentials of this sessions client.
+    pub fn entity_types_client(&self) -> EntityTypesClient {
+        EntityTypesClient::new(self.channel.clone(), self.token_interceptor.clone())
+    }
+
+    /// Creates session entity types client sharing GRPC channel and credentials of this sessions client.
+    pub fn session_entity_types_client(&self) -> SessionEntityTypesClient {
+        SessionEntityTypesClient::new(self.channel.clone(), self.token_interceptor.clone())
+    }
+
     /// Returns sender than can be used to stream in audio bytes. This method will take
     /// the sender out of the option leaving None in its place. No additional sender
     /// can be retrieved from session client after this call. When sender is dropped respective
@@ -107,15 +165,27 @@ impl SessionsClient {
     pub async fn detect_intent(
         &mut self,
         request: DetectIntentRequest,
+        options: Option<CallOptions>,
     ) -> Result<DetectIntentResponse> {
-        let detect_intent_req = tonic::Request::new(request);
+        let metric = detect_intent_usage(&request);
+        let options = options.or_else(CallOptions::current);
+        let detect_intent_req = new_request(request, options.clone())?;
         let response: TonicResponse<DetectIntentResponse> = self
             .sessions_client
             .detect_intent(detect_intent_req)
             .await?;
+        if let Some(usage_meter) = &self.usage_meter {
+            usage_meter.record_with_labels(metric, 1.0, usage_meter.labels(options.as_ref()));
+        }
         Ok(response.into_inner())
     }
 
+    /// Sets usage meter recording billable units consumed by this client
+    /// (see metering module).
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.usage_meter = Some(usage_meter);
+    }
+
     /// Convenience function to check if DetectIntentResponse
     /// represents end of conversation. If so, returns true, otherwise false.
     pub fn is_eoc(response: &DetectIntentResponse) -> bool {
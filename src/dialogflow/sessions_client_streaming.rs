@@ -7,8 +7,18 @@ use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
     sessions_client::SessionsClient as GrpcSessionsClient, DetectIntentResponse,
     StreamingDetectIntentRequest, StreamingDetectIntentResponse,
 };
-use crate::common::{get_token, new_grpc_channel, new_interceptor, TokenInterceptor};
-use crate::errors::Result;
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::{GRPC_API_DOMAIN, GRPC_API_URL};
+use crate::errors::{Error, Result};
+use crate::metering::{UsageMeter, UsageMetric};
+use crate::streaming::{
+    cancellable_request_stream, forward_responses, CancellationToken, StreamingOutcome,
+};
+use crate::telemetry::StreamingSession;
+use crate::transport::{ClientConfig, GrpcChannel};
 use async_stream::try_stream;
 use futures_core::stream::Stream;
 use log::*;
@@ -18,14 +28,14 @@ use tokio_stream::wrappers::ReceiverStream;
 use tonic::codegen::InterceptedService;
 use tonic::Response as TonicResponse;
 use tonic::Status as TonicStatus;
-use tonic::{transport::Channel, Streaming};
+use tonic::Streaming;
 
 /// Google Dialogflow sessions client.
 /// Used for streaming detect intent API. Is NOT cloneable!
 #[derive(Debug)]
 pub struct SessionsClient {
     /// internal GRPC dialogflow sessions client
-    sessions_client: GrpcSessionsClient<InterceptedService<Channel, TokenInterceptor>>,
+    sessions_client: GrpcSessionsClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
 
     /// channel for sending audio data
     audio_sender: Option<mpsc::Sender<StreamingDetectIntentRequest>>,
@@ -37,6 +47,9 @@ pub struct SessionsClient {
     /// where STT results will be sent. Library client is using respective
     /// receiver to get the results.
     result_sender: Option<mpsc::Sender<StreamingDetectIntentResponse>>,
+
+    /// usage meter of billable units, see metering module
+    usage_meter: Option<UsageMeter>,
 }
 
 impl SessionsClient {
@@ -51,14 +64,31 @@ impl SessionsClient {
         // If not provided defaults to 1000.
         buffer_size: Option<usize>,
     ) -> Result<Self> {
-        let channel = new_grpc_channel(
-            "dialogflow.googleapis.com",
-            "https://dialogflow.googleapis.com",
-            None,
+        SessionsClient::create_with_config(
+            google_credentials,
+            streaming_detect_intent_req,
+            buffer_size,
+            ClientConfig::current().unwrap_or_default(),
         )
-        .await?;
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        // initial configuration request
+        streaming_detect_intent_req: StreamingDetectIntentRequest,
+        // Capacity of audio sink (tokio channel used by caller to send audio data).
+        // If not provided defaults to 1000.
+        buffer_size: Option<usize>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
 
-        let token_header_val = get_token(google_credentials)?;
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
 
         let sessions_client =
             GrpcSessionsClient::with_interceptor(channel, new_interceptor(token_header_val));
@@ -73,6 +103,7 @@ impl SessionsClient {
             audio_sender: Some(audio_sender),
             audio_receiver: Some(audio_receiver),
             result_sender: None,
+            usage_meter: None,
         })
     }
 
@@ -117,6 +148,12 @@ impl SessionsClient {
         result_receiver
     }
 
+    /// Sets usage meter recording billable units consumed by this client
+    /// (see metering module).
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.usage_meter = Some(usage_meter);
+    }
+
     /// Convenience function so that client does not have to create full StreamingDetectIntentRequest
     /// and can just pass audio bytes vector instead.
     #[allow(deprecated)]
@@ -155,54 +192,184 @@ impl SessionsClient {
     #[allow(unreachable_code)]
     pub async fn streaming_detect_intent_async_stream(
         &mut self,
+        options: Option<CallOptions>,
     ) -> impl Stream<Item = Result<StreamingDetectIntentResponse>> + '_ {
         try_stream! {
                 // yank self.audio_receiver so that we can consume it
                 if let Some(audio_receiver) = self.audio_receiver.take() {
+                    let options = options.or_else(CallOptions::current);
+                    let session = StreamingSession::start("SessionsClient.streaming_detect_intent_async_stream");
+                    session.meter_usage(self.usage_meter.as_ref(), UsageMetric::DetectIntentAudioRequests, options.as_ref());
+                    let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
                     let streaming_recognize_result: StdResult<
                         TonicResponse<Streaming<StreamingDetectIntentResponse>>,
                         TonicStatus,
-                    > = self.sessions_client.streaming_detect_intent(ReceiverStream::new(audio_receiver)).await;
+                    > = session.instrument(self.sessions_client.streaming_detect_intent(new_request(requests, options)?)).await;
 
                     let mut response_stream: Streaming<StreamingDetectIntentResponse> =
-                        streaming_recognize_result?.into_inner();
+                        session.track(streaming_recognize_result)?.into_inner();
 
                     trace!("streaming_detect_intent_async_stream: entering loop");
-                    while let Some(streaming_detect_intent_response) = response_stream.message().await? {
+                    while let Some(streaming_detect_intent_response) = session.track(response_stream.message().await)? {
+                        session.response_received(&streaming_detect_intent_response);
                         yield streaming_detect_intent_response;
                     }
                     trace!("streaming_detect_intent_async_stream: leaving loop");
+                    session.finish(Ok(StreamingOutcome::Completed));
                 }
         }
     }
 
+    /// Consumes sessions client and initiates bidirectional streaming. Returns audio sink
+    /// and owned (Send + 'static) stream of streaming detect intent results, i.e. stream can be
+    /// moved into spawned task or stored in a struct. Stream is closed once the audio sink
+    /// (and all its clones retrieved via get_audio_sink) is dropped and remaining results are received.
+    /// IMPORTANT: see streaming_detect_intent_async_stream for half-close limitation.
+    pub fn into_streaming_session(
+        mut self,
+        options: Option<CallOptions>,
+    ) -> Result<(
+        mpsc::Sender<StreamingDetectIntentRequest>,
+        impl Stream<Item = Result<StreamingDetectIntentResponse>> + Send + 'static,
+    )> {
+        let (audio_sender, audio_receiver) =
+            match (self.audio_sender.take(), self.audio_receiver.take()) {
+                (Some(audio_sender), Some(audio_receiver)) => (audio_sender, audio_receiver),
+                _ => {
+                    return Err(Error::new(
+                        "SessionsClient.into_streaming_session: audio sink not available"
+                            .to_string(),
+                    ))
+                }
+            };
+        let mut sessions_client = self.sessions_client;
+        let usage_meter = self.usage_meter;
+        let options = options.or_else(CallOptions::current);
+
+        let stream = try_stream! {
+            let session = StreamingSession::start("SessionsClient.into_streaming_session");
+            session.meter_usage(usage_meter.as_ref(), UsageMetric::DetectIntentAudioRequests, options.as_ref());
+            let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+            let streaming_detect_intent_result: StdResult<
+                TonicResponse<Streaming<StreamingDetectIntentResponse>>,
+                TonicStatus,
+            > = session.instrument(sessions_client.streaming_detect_intent(new_request(requests, options)?)).await;
+
+            let mut response_stream: Streaming<StreamingDetectIntentResponse> =
+                session.track(streaming_detect_intent_result)?.into_inner();
+
+            trace!("into_streaming_session: entering loop");
+            while let Some(streaming_detect_intent_response) = session.track(response_stream.message().await)? {
+                session.response_received(&streaming_detect_intent_response);
+                yield streaming_detect_intent_response;
+            }
+            trace!("into_streaming_session: leaving loop");
+            session.finish(Ok(StreamingOutcome::Completed));
+        };
+
+        Ok((audio_sender, stream))
+    }
+
     /// Initiates bidirectional streaming. This call should be spawned
     /// into separate tokio task. Results can be then retrieved via
     /// channel receiver returned by method get_streaming_result_receiver.
     /// IMPORTANT: currently streaming_detect_intent does not work properly
     /// because half-close operation is not implemented. Details here(go example):
     /// https://cloud.google.com/dialogflow/es/docs/how/detect-intent-stream#detect-intent-stream-go
-    pub async fn streaming_detect_intent(&mut self) -> Result<()> {
+    pub async fn streaming_detect_intent(&mut self, options: Option<CallOptions>) -> Result<()> {
         // yank self.audio_receiver so that we can consume it
         if let Some(audio_receiver) = self.audio_receiver.take() {
-            let streaming_recognize_result: StdResult<
-                tonic::Response<Streaming<StreamingDetectIntentResponse>>,
-                tonic::Status,
-            > = self
-                .sessions_client
-                .streaming_detect_intent(ReceiverStream::new(audio_receiver))
-                .await;
+            let options = options.or_else(CallOptions::current);
+            let session = StreamingSession::start("SessionsClient.streaming_detect_intent");
+            session.meter_usage(
+                self.usage_meter.as_ref(),
+                UsageMetric::DetectIntentAudioRequests,
+                options.as_ref(),
+            );
+            let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+            let result = session
+                .instrument(async {
+                    let streaming_recognize_result: StdResult<
+                        tonic::Response<Streaming<StreamingDetectIntentResponse>>,
+                        tonic::Status,
+                    > = self
+                        .sessions_client
+                        .streaming_detect_intent(new_request(requests, options)?)
+                        .await;
 
-            let mut response_stream: Streaming<StreamingDetectIntentResponse> =
-                streaming_recognize_result?.into_inner();
+                    let mut response_stream: Streaming<StreamingDetectIntentResponse> =
+                        streaming_recognize_result?.into_inner();
 
-            while let Some(streaming_detect_intent_response) = response_stream.message().await? {
-                if let Some(result_sender) = &self.result_sender {
-                    result_sender.send(streaming_detect_intent_response).await?;
-                }
-            }
+                    while let Some(streaming_detect_intent_response) =
+                        response_stream.message().await?
+                    {
+                        session.response_received(&streaming_detect_intent_response);
+                        if let Some(result_sender) = &self.result_sender {
+                            result_sender.send(streaming_detect_intent_response).await?;
+                        }
+                    }
+                    Ok(())
+                })
+                .await;
+            session.finish(result.as_ref().map(|_| StreamingOutcome::Completed));
+            result?;
         }
 
         Ok(())
     }
+
+    /// Initiates bidirectional streaming which can be aborted or gracefully shut down
+    /// via cancellation token. This call should be spawned into separate tokio task.
+    /// Results are sent into channel receiver returned by method get_streaming_result_receiver,
+    /// result channel is closed once this method returns. Returns Completed if the stream
+    /// was closed by the API, Cancelled or GracePeriodElapsed if it was aborted.
+    pub async fn streaming_detect_intent_cancellable(
+        &mut self,
+        token: CancellationToken,
+        options: Option<CallOptions>,
+    ) -> Result<StreamingOutcome> {
+        // yank self.audio_receiver so that we can consume it
+        if let Some(audio_receiver) = self.audio_receiver.take() {
+            let result_sender = self.result_sender.take();
+            let options = options.or_else(CallOptions::current);
+            let session =
+                StreamingSession::start("SessionsClient.streaming_detect_intent_cancellable");
+            session.meter_usage(
+                self.usage_meter.as_ref(),
+                UsageMetric::DetectIntentAudioRequests,
+                options.as_ref(),
+            );
+            let requests =
+                session.meter_requests(cancellable_request_stream(audio_receiver, token.clone()));
+            let result = session
+                .instrument(async {
+                    let streaming_result: StdResult<
+                        tonic::Response<Streaming<StreamingDetectIntentResponse>>,
+                        tonic::Status,
+                    > = self
+                        .sessions_client
+                        .streaming_detect_intent(new_request(requests, options)?)
+                        .await;
+
+                    let mut response_stream: Streaming<StreamingDetectIntentResponse> =
+                        streaming_result?.into_inner();
+
+                    forward_responses(
+                        &mut response_stream,
+                        result_sender.as_ref(),
+                        &token,
+                        &session,
+                    )
+                    .await
+                })
+                .await;
+            session.finish(result.as_ref().copied());
+            result
+        } else {
+            Err(Error::new(
+                "SessionsClient.streaming_detect_intent_cancellable: audio receiver not available"
+                    .to_string(),
+            ))
+        }
+    }
 }
@@ -7,10 +7,16 @@ use crate::api::grpc::google::cloud::speechtotext::v1::{
 };
 use crate::api::grpc::google::cloud::speechtotext::v1p1beta1::StreamingRecognizeRequest as StreamingRecognizeRequestBeta;
 use crate::api::grpc::google::cloud::speechtotext::v1p1beta1::StreamingRecognizeResponse as StreamingRecognizeResponseBeta;
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+use crate::api::grpc::google::cloud::speechtotext::v2::{
+    StreamingRecognizeRequest as StreamingRecognizeRequestV2,
+    StreamingRecognizeResponse as StreamingRecognizeResponseV2,
+};
 use gouth::Error as GAuthError;
 use prost::DecodeError as ProstDecodeError;
 use std::result;
 use tokio::sync::mpsc::error::SendError;
+use tokio::task::JoinError;
 use tonic::metadata::errors::InvalidMetadataValue;
 use tonic::transport::Error as TTError;
 use tonic::Status as TStatus;
@@ -19,6 +25,8 @@ use tonic::Status as TStatus;
 pub struct Error {
     pub message: String,
     pub code: Option<String>,
+    /// numeric GRPC status code of failed call or long running operation (if any)
+    pub grpc_code: Option<i32>,
 }
 
 impl Error {
@@ -26,12 +34,14 @@ impl Error {
         Error {
             message,
             code: None,
+            grpc_code: None,
         }
     }
     pub fn new_with_code(message: String, code: String) -> Self {
         Error {
             message,
             code: Some(code),
+            grpc_code: None,
         }
     }
 }
@@ -43,6 +53,7 @@ impl From<serde_json::error::Error> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -52,6 +63,7 @@ impl From<std::io::Error> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -61,6 +73,7 @@ impl From<TTError> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -70,6 +83,7 @@ impl From<GAuthError> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -79,6 +93,7 @@ impl From<TStatus> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: Some(error.code() as i32),
         }
     }
 }
@@ -88,6 +103,7 @@ impl From<SendError<StreamingRecognizeRequest>> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -97,6 +113,7 @@ impl From<SendError<StreamingRecognizeRequestBeta>> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -106,6 +123,7 @@ impl From<SendError<StreamingRecognizeResponse>> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -115,6 +133,29 @@ impl From<SendError<StreamingRecognizeResponseBeta>> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
+        }
+    }
+}
+
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+impl From<SendError<StreamingRecognizeRequestV2>> for Error {
+    fn from(error: SendError<StreamingRecognizeRequestV2>) -> Error {
+        Error {
+            message: format!("{}", error),
+            code: None,
+            grpc_code: None,
+        }
+    }
+}
+
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+impl From<SendError<StreamingRecognizeResponseV2>> for Error {
+    fn from(error: SendError<StreamingRecognizeResponseV2>) -> Error {
+        Error {
+            message: format!("{}", error),
+            code: None,
+            grpc_code: None,
         }
     }
 }
@@ -124,6 +165,7 @@ impl From<SendError<StreamingDetectIntentRequest>> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -133,6 +175,7 @@ impl From<SendError<StreamingDetectIntentResponse>> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -142,6 +185,7 @@ impl From<ProstDecodeError> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
         }
     }
 }
@@ -151,6 +195,17 @@ impl From<InvalidMetadataValue> for Error {
         Error {
             message: format!("{}", error),
             code: None,
+            grpc_code: None,
+        }
+    }
+}
+
+impl From<JoinError> for Error {
+    fn from(error: JoinError) -> Error {
+        Error {
+            message: format!("{}", error),
+            code: None,
+            grpc_code: None,
         }
     }
 }
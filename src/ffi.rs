@@ -0,0 +1,486 @@
+//! Contains C ABI bindings (feature `ffi`) of the blocking clients: streaming recognizer,
+//! synthesizer and dialogflow sessions client. C header is located in include/google_cognitive_apis.h
+//! (generated by cbindgen, see cbindgen.toml). Static/dynamic library is built by:
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! cargo rustc --release --features ffi --crate-type staticlib
+//! ```
+//!
+//! All functions return GCAPI_OK (0) on success, GRPC status code (1-16) if the API call failed
+//! or one of GCAPI_ERROR_* codes. Message of the last error of the calling thread is returned
+//! by gcapi_last_error_message. Objects created by gcapi_*_create functions must be released by
+//! respective gcapi_*_free function, buffers and strings returned by the library must be released
+//! by gcapi_buffer_free and gcapi_string_free. Functions must not be called from tokio runtime threads.
+#![allow(clippy::missing_safety_doc)]
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    query_input::Input, DetectIntentRequest, QueryInput, TextInput,
+};
+use crate::api::grpc::google::cloud::speechtotext::v1::{
+    StreamingRecognitionConfig, StreamingRecognizeResponse,
+};
+use crate::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, AudioConfig, SynthesisInput, SynthesizeSpeechRequest,
+    VoiceSelectionParams,
+};
+use crate::api::rest::google::cloud::speechtotext::v1::deserialize_recognition_config;
+use crate::blocking::recognizer::{Recognizer, StreamingRecognition};
+use crate::blocking::sessions_client::SessionsClient;
+use crate::blocking::synthesizer::Synthesizer;
+use crate::errors::Error;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// Call succeeded.
+pub const GCAPI_OK: i32 = 0;
+
+/// Invalid argument passed to the library (null pointer, invalid UTF-8, invalid JSON config).
+pub const GCAPI_ERROR_INVALID_ARGUMENT: i32 = 100;
+
+/// Library error without GRPC status code (e.g. connection or authentication error).
+pub const GCAPI_ERROR_OTHER: i32 = 101;
+
+/// Library panicked, the object which was used should be released.
+pub const GCAPI_ERROR_PANIC: i32 = 102;
+
+/// Callback receiving streaming recognition results (first alternative of every result)
+/// during gcapi_recognizer_poll. Transcript is valid only during the callback.
+pub type GcapiRecognitionCallback = Option<
+    unsafe extern "C" fn(
+        user_data: *mut c_void,
+        transcript: *const c_char,
+        confidence: f32,
+        stability: f32,
+        is_final: bool,
+    ),
+>;
+
+/// Streaming recognizer (v1 STT API).
+pub struct GcapiRecognizer {
+    streaming: StreamingRecognition,
+}
+
+/// Text-to-speech synthesizer.
+pub struct GcapiSynthesizer {
+    synthesizer: Synthesizer,
+}
+
+/// Dialogflow sessions client.
+pub struct GcapiSessionsClient {
+    sessions_client: SessionsClient,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).ok();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+}
+
+/// Returns C error code of the library error: GRPC status code or GCAPI_ERROR_OTHER.
+pub fn error_code(error: &Error) -> i32 {
+    match error.grpc_code {
+        Some(code) if (1..=16).contains(&code) => code,
+        _ => GCAPI_ERROR_OTHER,
+    }
+}
+
+/// Error of the C API call, invalid arguments are distinguished from library errors.
+enum FfiError {
+    InvalidArgument(String),
+    Library(Error),
+}
+
+impl From<Error> for FfiError {
+    fn from(error: Error) -> Self {
+        FfiError::Library(error)
+    }
+}
+
+type FfiResult<T> = std::result::Result<T, FfiError>;
+
+fn invalid_argument(message: &str) -> FfiError {
+    FfiError::InvalidArgument(message.to_string())
+}
+
+/// Runs the call, converts its result (or panic) into C error code.
+fn ffi_call(call: impl FnOnce() -> FfiResult<()>) -> i32 {
+    match catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(())) => GCAPI_OK,
+        Ok(Err(FfiError::InvalidArgument(message))) => {
+            set_last_error(&message);
+            GCAPI_ERROR_INVALID_ARGUMENT
+        }
+        Ok(Err(FfiError::Library(error))) => {
+            set_last_error(&error.message);
+            error_code(&error)
+        }
+        Err(_) => {
+            set_last_error("panic in google-cognitive-apis library");
+            GCAPI_ERROR_PANIC
+        }
+    }
+}
+
+unsafe fn to_str<'a>(value: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if value.is_null() {
+        return Err(invalid_argument(&format!("{} must not be null", name)));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| invalid_argument(&format!("{} is not valid UTF-8", name)))
+}
+
+unsafe fn to_optional_str<'a>(value: *const c_char, name: &str) -> FfiResult<Option<&'a str>> {
+    if value.is_null() {
+        Ok(None)
+    } else {
+        to_str(value, name).map(Some)
+    }
+}
+
+unsafe fn to_mut<'a, T>(value: *mut T, name: &str) -> FfiResult<&'a mut T> {
+    value
+        .as_mut()
+        .ok_or_else(|| invalid_argument(&format!("{} must not be null", name)))
+}
+
+fn to_c_string(value: String) -> *mut c_char {
+    CString::new(value.replace('\0', " "))
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Returns message of the last error of the calling thread (or NULL).
+/// Message is valid until next call of the library on the same thread.
+#[no_mangle]
+pub extern "C" fn gcapi_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map(|message| message.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
+
+/// Creates streaming recognizer and starts streaming recognition. Recognition config
+/// is JSON of RecognitionConfig of REST API
+/// (https://cloud.google.com/speech-to-text/docs/reference/rest/v1/RecognitionConfig).
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_recognizer_create(
+    credentials: *const c_char,
+    recognition_config_json: *const c_char,
+    interim_results: bool,
+    single_utterance: bool,
+    out: *mut *mut GcapiRecognizer,
+) -> i32 {
+    ffi_call(|| {
+        let out = to_mut(out, "out")?;
+        let credentials = to_str(credentials, "credentials")?;
+        let config_json = to_str(recognition_config_json, "recognition_config_json")?;
+        let config = deserialize_recognition_config(config_json)
+            .map_err(|error| invalid_argument(&error.message))?;
+        let streaming_config = StreamingRecognitionConfig {
+            config: Some(config.into()),
+            single_utterance,
+            interim_results,
+        };
+        let recognizer =
+            Recognizer::create_streaming_recognizer(credentials, streaming_config, None)?;
+        let streaming = recognizer.streaming_recognize(None)?;
+        *out = Box::into_raw(Box::new(GcapiRecognizer { streaming }));
+        Ok(())
+    })
+}
+
+/// Pushes audio bytes into streaming recognition, blocks if the audio sink is full.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_recognizer_push_audio(
+    recognizer: *mut GcapiRecognizer,
+    audio: *const u8,
+    audio_len: usize,
+) -> i32 {
+    ffi_call(|| {
+        let recognizer = to_mut(recognizer, "recognizer")?;
+        if audio.is_null() && audio_len > 0 {
+            return Err(invalid_argument("audio must not be null"));
+        }
+        let audio_bytes = if audio_len > 0 {
+            std::slice::from_raw_parts(audio, audio_len).to_vec()
+        } else {
+            vec![]
+        };
+        Ok(recognizer.streaming.send_audio(audio_bytes)?)
+    })
+}
+
+/// Closes the audio, API returns remaining results and closes the stream.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_recognizer_close_audio(recognizer: *mut GcapiRecognizer) -> i32 {
+    ffi_call(|| {
+        to_mut(recognizer, "recognizer")?.streaming.close_audio();
+        Ok(())
+    })
+}
+
+fn deliver_results(
+    response: &StreamingRecognizeResponse,
+    callback: GcapiRecognitionCallback,
+    user_data: *mut c_void,
+) {
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return,
+    };
+    for result in &response.results {
+        if let Some(alternative) = result.alternatives.first() {
+            let transcript =
+                CString::new(alternative.transcript.replace('\0', " ")).unwrap_or_default();
+            unsafe {
+                callback(
+                    user_data,
+                    transcript.as_ptr(),
+                    alternative.confidence,
+                    result.stability,
+                    result.is_final,
+                )
+            };
+        }
+    }
+}
+
+/// Delivers results received so far to the callback (does not block). Sets finished
+/// to true once the stream is closed and all results were delivered. Returns error
+/// if the streaming recognition failed.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_recognizer_poll(
+    recognizer: *mut GcapiRecognizer,
+    callback: GcapiRecognitionCallback,
+    user_data: *mut c_void,
+    finished: *mut bool,
+) -> i32 {
+    ffi_call(|| {
+        let recognizer = to_mut(recognizer, "recognizer")?;
+        while let Some(response) = recognizer.streaming.try_next() {
+            deliver_results(&response?, callback, user_data);
+        }
+        if let Some(finished) = finished.as_mut() {
+            *finished = recognizer.streaming.is_finished();
+        }
+        Ok(())
+    })
+}
+
+/// Releases recognizer, running streaming recognition is aborted.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_recognizer_free(recognizer: *mut GcapiRecognizer) {
+    if !recognizer.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(recognizer))));
+    }
+}
+
+/// Creates text-to-speech synthesizer.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_synthesizer_create(
+    credentials: *const c_char,
+    out: *mut *mut GcapiSynthesizer,
+) -> i32 {
+    ffi_call(|| {
+        let out = to_mut(out, "out")?;
+        let synthesizer = Synthesizer::create(to_str(credentials, "credentials")?)?;
+        *out = Box::into_raw(Box::new(GcapiSynthesizer { synthesizer }));
+        Ok(())
+    })
+}
+
+/// Synthesizes text (or SSML if ssml is true) into audio buffer which must be released
+/// by gcapi_buffer_free. Voice name is optional (NULL), audio encoding is AudioEncoding
+/// enum value of text-to-speech API (e.g. 1 = LINEAR16, 5 = MULAW), sample rate 0 means default.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_synthesizer_synthesize(
+    synthesizer: *mut GcapiSynthesizer,
+    text: *const c_char,
+    ssml: bool,
+    language_code: *const c_char,
+    voice_name: *const c_char,
+    audio_encoding: i32,
+    sample_rate_hertz: i32,
+    audio_out: *mut *mut u8,
+    audio_len_out: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let synthesizer = to_mut(synthesizer, "synthesizer")?;
+        let audio_out = to_mut(audio_out, "audio_out")?;
+        let audio_len_out = to_mut(audio_len_out, "audio_len_out")?;
+        let text = to_str(text, "text")?.to_string();
+        let input_source = if ssml {
+            InputSource::Ssml(text)
+        } else {
+            InputSource::Text(text)
+        };
+        let request = SynthesizeSpeechRequest {
+            input: Some(SynthesisInput {
+                input_source: Some(input_source),
+            }),
+            voice: Some(VoiceSelectionParams {
+                language_code: to_str(language_code, "language_code")?.to_string(),
+                name: to_optional_str(voice_name, "voice_name")?
+                    .unwrap_or_default()
+                    .to_string(),
+                ..Default::default()
+            }),
+            audio_config: Some(AudioConfig {
+                audio_encoding,
+                sample_rate_hertz,
+                ..Default::default()
+            }),
+        };
+        let response = synthesizer.synthesizer.synthesize_speech(request, None)?;
+        let audio = response.audio_content.into_boxed_slice();
+        *audio_len_out = audio.len();
+        *audio_out = Box::into_raw(audio) as *mut u8;
+        Ok(())
+    })
+}
+
+/// Releases synthesizer.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_synthesizer_free(synthesizer: *mut GcapiSynthesizer) {
+    if !synthesizer.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(synthesizer))));
+    }
+}
+
+/// Creates dialogflow sessions client.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_sessions_client_create(
+    credentials: *const c_char,
+    out: *mut *mut GcapiSessionsClient,
+) -> i32 {
+    ffi_call(|| {
+        let out = to_mut(out, "out")?;
+        let sessions_client = SessionsClient::create(to_str(credentials, "credentials")?)?;
+        *out = Box::into_raw(Box::new(GcapiSessionsClient { sessions_client }));
+        Ok(())
+    })
+}
+
+/// Detects intent of the text. Session is full session path
+/// (projects/<project>/agent/sessions/<session>). Fulfillment text and intent display
+/// name (both optional, NULL if not needed) must be released by gcapi_string_free.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_sessions_client_detect_intent_text(
+    sessions_client: *mut GcapiSessionsClient,
+    session: *const c_char,
+    text: *const c_char,
+    language_code: *const c_char,
+    fulfillment_text_out: *mut *mut c_char,
+    intent_out: *mut *mut c_char,
+) -> i32 {
+    ffi_call(|| {
+        let sessions_client = to_mut(sessions_client, "sessions_client")?;
+        let request = DetectIntentRequest {
+            session: to_str(session, "session")?.to_string(),
+            query_input: Some(QueryInput {
+                input: Some(Input::Text(TextInput {
+                    text: to_str(text, "text")?.to_string(),
+                    language_code: to_str(language_code, "language_code")?.to_string(),
+                })),
+            }),
+            ..Default::default()
+        };
+        let response = sessions_client
+            .sessions_client
+            .detect_intent(request, None)?;
+        let query_result = response.query_result.unwrap_or_default();
+        if let Some(fulfillment_text_out) = fulfillment_text_out.as_mut() {
+            *fulfillment_text_out = to_c_string(query_result.fulfillment_text);
+        }
+        if let Some(intent_out) = intent_out.as_mut() {
+            let intent = query_result
+                .intent
+                .map(|intent| intent.display_name)
+                .unwrap_or_default();
+            *intent_out = to_c_string(intent);
+        }
+        Ok(())
+    })
+}
+
+/// Releases sessions client.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_sessions_client_free(sessions_client: *mut GcapiSessionsClient) {
+    if !sessions_client.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(sessions_client))));
+    }
+}
+
+/// Releases audio buffer returned by gcapi_synthesizer_synthesize.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_buffer_free(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer, len)));
+    }
+}
+
+/// Releases string returned by the library.
+#[no_mangle]
+pub unsafe extern "C" fn gcapi_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test --features ffi -- --show-output test_ffi_errors
+    #[test]
+    fn test_ffi_errors() {
+        let mut synthesizer: *mut GcapiSynthesizer = ptr::null_mut();
+        let code = unsafe { gcapi_synthesizer_create(ptr::null(), &mut synthesizer) };
+        assert_eq!(code, GCAPI_ERROR_INVALID_ARGUMENT);
+        assert!(synthesizer.is_null());
+        let message = unsafe { CStr::from_ptr(gcapi_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "credentials must not be null");
+
+        let error = Error::from(tonic::Status::unavailable("endpoint unavailable"));
+        assert_eq!(error.code, None);
+        assert_eq!(error_code(&error), 14);
+        assert_eq!(
+            error_code(&Error::new("no code".to_string())),
+            GCAPI_ERROR_OTHER
+        );
+        // library error codes are not mistaken for C API error codes
+        let error = Error::new_with_code("other".to_string(), "100".to_string());
+        assert_eq!(error_code(&error), GCAPI_ERROR_OTHER);
+
+        let mut recognizer: *mut GcapiRecognizer = ptr::null_mut();
+        let credentials = CString::new("{}").unwrap();
+        let config = CString::new("{ invalid json").unwrap();
+        let code = unsafe {
+            gcapi_recognizer_create(
+                credentials.as_ptr(),
+                config.as_ptr(),
+                true,
+                false,
+                &mut recognizer,
+            )
+        };
+        assert_eq!(code, GCAPI_ERROR_INVALID_ARGUMENT);
+
+        // releasing null objects is no-op
+        unsafe {
+            gcapi_recognizer_free(ptr::null_mut());
+            gcapi_string_free(ptr::null_mut());
+            gcapi_buffer_free(ptr::null_mut(), 0);
+        }
+    }
+}
@@ -566,6 +566,9 @@ pub struct RecognizeResponse {
     /// sequential portions of audio.
     #[prost(message, repeated, tag="2")]
     pub results: ::prost::alloc::vec::Vec<SpeechRecognitionResult>,
+    /// When available, billed audio seconds for the corresponding request.
+    #[prost(message, optional, tag="3")]
+    pub total_billed_time: ::core::option::Option<::prost_types::Duration>,
 }
 /// The only message returned to the client by the `LongRunningRecognize` method.
 /// It contains the result as zero or more sequential `SpeechRecognitionResult`
@@ -578,6 +581,9 @@ pub struct LongRunningRecognizeResponse {
     /// sequential portions of audio.
     #[prost(message, repeated, tag="2")]
     pub results: ::prost::alloc::vec::Vec<SpeechRecognitionResult>,
+    /// When available, billed audio seconds for the corresponding request.
+    #[prost(message, optional, tag="3")]
+    pub total_billed_time: ::core::option::Option<::prost_types::Duration>,
 }
 /// Describes the progress of a long-running `LongRunningRecognize` call. It is
 /// included in the `metadata` field of the `Operation` returned by the
@@ -659,6 +665,10 @@ pub struct StreamingRecognizeResponse {
     /// Indicates the type of speech event.
     #[prost(enumeration="streaming_recognize_response::SpeechEventType", tag="4")]
     pub speech_event_type: i32,
+    /// When available, billed audio seconds for the stream.
+    /// Set only if this is the last response in the stream.
+    #[prost(message, optional, tag="5")]
+    pub total_billed_time: ::core::option::Option<::prost_types::Duration>,
 }
 /// Nested message and enum types in `StreamingRecognizeResponse`.
 pub mod streaming_recognize_response {
@@ -0,0 +1,1316 @@
+/// Request message for the
+/// \[CreateRecognizer][google.cloud.speech.v2.Speech.CreateRecognizer\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateRecognizerRequest {
+    /// Required. The Recognizer to create.
+    #[prost(message, optional, tag="1")]
+    pub recognizer: ::core::option::Option<Recognizer>,
+    /// If set, validate the request and preview the Recognizer, but do not
+    /// actually create it.
+    #[prost(bool, tag="2")]
+    pub validate_only: bool,
+    /// The ID to use for the Recognizer, which will become the final component of
+    /// the Recognizer's resource name.
+    ///
+    /// This value should be 4-63 characters, and valid characters
+    /// are /\[a-z][0-9\]-/.
+    #[prost(string, tag="3")]
+    pub recognizer_id: ::prost::alloc::string::String,
+    /// Required. The project and location where this Recognizer will be created.
+    /// The expected format is `projects/{project}/locations/{location}`.
+    #[prost(string, tag="4")]
+    pub parent: ::prost::alloc::string::String,
+}
+/// Represents the metadata of a long-running operation.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OperationMetadata {
+    /// The time the operation was created.
+    #[prost(message, optional, tag="1")]
+    pub create_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// The time the operation was last updated.
+    #[prost(message, optional, tag="2")]
+    pub update_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// The resource path for the target of the operation.
+    #[prost(string, tag="3")]
+    pub resource: ::prost::alloc::string::String,
+    /// The method that triggered the operation.
+    #[prost(string, tag="4")]
+    pub method: ::prost::alloc::string::String,
+    /// The percent progress of the Operation. Values can range from 0-100. If the
+    /// value is 100, then the operation is finished.
+    #[prost(int32, tag="22")]
+    pub progress_percent: i32,
+}
+/// Request message for the
+/// \[ListRecognizers][google.cloud.speech.v2.Speech.ListRecognizers\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRecognizersRequest {
+    /// Required. The project and location of Recognizers to list. The expected
+    /// format is `projects/{project}/locations/{location}`.
+    #[prost(string, tag="1")]
+    pub parent: ::prost::alloc::string::String,
+    /// The maximum number of Recognizers to return. The service may return fewer
+    /// than this value. If unspecified, at most 5 Recognizers will be returned.
+    /// The maximum value is 100; values above 100 will be coerced to 100.
+    #[prost(int32, tag="2")]
+    pub page_size: i32,
+    /// A page token, received from a previous
+    /// \[ListRecognizers][google.cloud.speech.v2.Speech.ListRecognizers\] call.
+    /// Provide this to retrieve the subsequent page.
+    #[prost(string, tag="3")]
+    pub page_token: ::prost::alloc::string::String,
+    /// Whether, or not, to show resources that have been deleted.
+    #[prost(bool, tag="4")]
+    pub show_deleted: bool,
+}
+/// Response message for the
+/// \[ListRecognizers][google.cloud.speech.v2.Speech.ListRecognizers\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRecognizersResponse {
+    /// The list of requested Recognizers.
+    #[prost(message, repeated, tag="1")]
+    pub recognizers: ::prost::alloc::vec::Vec<Recognizer>,
+    /// A token, which can be sent as
+    /// \[page_token][google.cloud.speech.v2.ListRecognizersRequest.page_token\] to
+    /// retrieve the next page. If this field is omitted, there are no subsequent
+    /// pages. This token expires after 72 hours.
+    #[prost(string, tag="2")]
+    pub next_page_token: ::prost::alloc::string::String,
+}
+/// Request message for the
+/// \[GetRecognizer][google.cloud.speech.v2.Speech.GetRecognizer\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetRecognizerRequest {
+    /// Required. The name of the Recognizer to retrieve. The expected format is
+    /// `projects/{project}/locations/{location}/recognizers/{recognizer}`.
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+}
+/// Request message for the
+/// \[UpdateRecognizer][google.cloud.speech.v2.Speech.UpdateRecognizer\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateRecognizerRequest {
+    /// Required. The Recognizer to update.
+    #[prost(message, optional, tag="1")]
+    pub recognizer: ::core::option::Option<Recognizer>,
+    /// The list of fields to update. If empty, all non-default valued fields are
+    /// considered for update. Use `*` to update the entire Recognizer resource.
+    #[prost(message, optional, tag="2")]
+    pub update_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// If set, validate the request and preview the updated Recognizer, but do not
+    /// actually update it.
+    #[prost(bool, tag="4")]
+    pub validate_only: bool,
+}
+/// Request message for the
+/// \[DeleteRecognizer][google.cloud.speech.v2.Speech.DeleteRecognizer\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteRecognizerRequest {
+    /// Required. The name of the Recognizer to delete.
+    /// Format: `projects/{project}/locations/{location}/recognizers/{recognizer}`
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    /// If set, validate the request and preview the deleted Recognizer, but do not
+    /// actually delete it.
+    #[prost(bool, tag="2")]
+    pub validate_only: bool,
+    /// If set to true, and the Recognizer is not found, the request will succeed
+    /// and  be a no-op (no Operation is recorded in this case).
+    #[prost(bool, tag="4")]
+    pub allow_missing: bool,
+    /// This checksum is computed by the server based on the value of other
+    /// fields. This may be sent on update, undelete, and delete requests to ensure
+    /// the client has an up-to-date value before proceeding.
+    #[prost(string, tag="3")]
+    pub etag: ::prost::alloc::string::String,
+}
+/// Request message for the
+/// \[UndeleteRecognizer][google.cloud.speech.v2.Speech.UndeleteRecognizer\]
+/// method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UndeleteRecognizerRequest {
+    /// Required. The name of the Recognizer to undelete.
+    /// Format: `projects/{project}/locations/{location}/recognizers/{recognizer}`
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    /// If set, validate the request and preview the undeleted Recognizer, but do
+    /// not actually undelete it.
+    #[prost(bool, tag="3")]
+    pub validate_only: bool,
+    /// This checksum is computed by the server based on the value of other
+    /// fields. This may be sent on update, undelete, and delete requests to ensure
+    /// the client has an up-to-date value before proceeding.
+    #[prost(string, tag="4")]
+    pub etag: ::prost::alloc::string::String,
+}
+/// A Recognizer message. Stores recognition configuration and metadata.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Recognizer {
+    /// Output only. Identifier. The resource name of the Recognizer.
+    /// Format: `projects/{project}/locations/{location}/recognizers/{recognizer}`.
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    /// Output only. System-assigned unique identifier for the Recognizer.
+    #[prost(string, tag="2")]
+    pub uid: ::prost::alloc::string::String,
+    /// User-settable, human-readable name for the Recognizer. Must be 63
+    /// characters or less.
+    #[prost(string, tag="3")]
+    pub display_name: ::prost::alloc::string::String,
+    /// Optional. This field is now deprecated. Prefer the
+    /// \[`model`][google.cloud.speech.v2.RecognitionConfig.model\] field in the
+    /// \[`RecognitionConfig`][google.cloud.speech.v2.RecognitionConfig\] message.
+    #[deprecated]
+    #[prost(string, tag="4")]
+    pub model: ::prost::alloc::string::String,
+    /// Optional. This field is now deprecated. Prefer the
+    /// \[`language_codes`][google.cloud.speech.v2.RecognitionConfig.language_codes\]
+    /// field in the
+    /// \[`RecognitionConfig`][google.cloud.speech.v2.RecognitionConfig\] message.
+    #[deprecated]
+    #[prost(string, repeated, tag="17")]
+    pub language_codes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Default configuration to use for requests with this Recognizer.
+    /// This can be overwritten by inline configuration in the
+    /// \[RecognizeRequest.config][google.cloud.speech.v2.RecognizeRequest.config\]
+    /// field.
+    #[prost(message, optional, tag="6")]
+    pub default_recognition_config: ::core::option::Option<RecognitionConfig>,
+    /// Allows users to store small amounts of arbitrary data.
+    /// Both the key and the value must be 63 characters or less each.
+    /// At most 100 annotations.
+    #[prost(map="string, string", tag="7")]
+    pub annotations: ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
+    /// Output only. The Recognizer lifecycle state.
+    #[prost(enumeration="recognizer::State", tag="8")]
+    pub state: i32,
+    /// Output only. Creation time.
+    #[prost(message, optional, tag="9")]
+    pub create_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// Output only. The most recent time this Recognizer was modified.
+    #[prost(message, optional, tag="10")]
+    pub update_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// Output only. The time at which this Recognizer was requested for deletion.
+    #[prost(message, optional, tag="11")]
+    pub delete_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// Output only. The time at which this Recognizer will be purged.
+    #[prost(message, optional, tag="14")]
+    pub expire_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// Output only. This checksum is computed by the server based on the value of
+    /// other fields. This may be sent on update, undelete, and delete requests to
+    /// ensure the client has an up-to-date value before proceeding.
+    #[prost(string, tag="12")]
+    pub etag: ::prost::alloc::string::String,
+    /// Output only. Whether or not this Recognizer is in the process of being
+    /// updated.
+    #[prost(bool, tag="13")]
+    pub reconciling: bool,
+}
+/// Nested message and enum types in `Recognizer`.
+pub mod recognizer {
+    /// Set of states that define the lifecycle of a Recognizer.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum State {
+        /// The default value. This value is used if the state is omitted.
+        Unspecified = 0,
+        /// The Recognizer is active and ready for use.
+        Active = 2,
+        /// This Recognizer has been deleted.
+        Deleted = 4,
+    }
+    impl State {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                State::Unspecified => "STATE_UNSPECIFIED",
+                State::Active => "ACTIVE",
+                State::Deleted => "DELETED",
+            }
+        }
+    }
+}
+/// Automatically detected decoding parameters.
+/// Supported for the following encodings:
+///
+/// * WAV_LINEAR16: 16-bit signed little-endian PCM samples in a WAV container.
+///
+/// * WAV_MULAW: 8-bit companded mulaw samples in a WAV container.
+///
+/// * WAV_ALAW: 8-bit companded alaw samples in a WAV container.
+///
+/// * RFC4867_5_AMR: AMR frames with an rfc4867.5 header.
+///
+/// * RFC4867_5_AMRWB: AMR-WB frames with an rfc4867.5 header.
+///
+/// * FLAC: FLAC frames in the "native FLAC" container format.
+///
+/// * MP3: MPEG audio frames with optional (ignored) ID3 metadata.
+///
+/// * OGG_OPUS: Opus audio frames in an Ogg container.
+///
+/// * WEBM_OPUS: Opus audio frames in a WebM container.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AutoDetectDecodingConfig {
+}
+/// Explicitly specified decoding parameters.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExplicitDecodingConfig {
+    /// Required. Encoding of the audio data sent for recognition.
+    #[prost(enumeration="explicit_decoding_config::AudioEncoding", tag="1")]
+    pub encoding: i32,
+    /// Sample rate in Hertz of the audio data sent for recognition. Valid
+    /// values are: 8000-48000. 16000 is optimal.
+    #[prost(int32, tag="2")]
+    pub sample_rate_hertz: i32,
+    /// Number of channels present in the audio data sent for recognition.
+    /// The maximum allowed value is 8.
+    #[prost(int32, tag="3")]
+    pub audio_channel_count: i32,
+}
+/// Nested message and enum types in `ExplicitDecodingConfig`.
+pub mod explicit_decoding_config {
+    /// Supported audio data encodings.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum AudioEncoding {
+        /// Default value. This value is unused.
+        Unspecified = 0,
+        /// Headerless 16-bit signed little-endian PCM samples.
+        Linear16 = 1,
+        /// Headerless 8-bit companded mulaw samples.
+        Mulaw = 2,
+        /// Headerless 8-bit companded alaw samples.
+        Alaw = 3,
+        /// AMR frames with an rfc4867.5 header.
+        Amr = 4,
+        /// AMR-WB frames with an rfc4867.5 header.
+        AmrWb = 5,
+        /// FLAC frames in the "native FLAC" container format.
+        Flac = 6,
+        /// MPEG audio frames with optional (ignored) ID3 metadata.
+        Mp3 = 7,
+        /// Opus audio frames in an Ogg container.
+        OggOpus = 8,
+        /// Opus audio frames in a WebM container.
+        WebmOpus = 9,
+    }
+    impl AudioEncoding {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                AudioEncoding::Unspecified => "AUDIO_ENCODING_UNSPECIFIED",
+                AudioEncoding::Linear16 => "LINEAR16",
+                AudioEncoding::Mulaw => "MULAW",
+                AudioEncoding::Alaw => "ALAW",
+                AudioEncoding::Amr => "AMR",
+                AudioEncoding::AmrWb => "AMR_WB",
+                AudioEncoding::Flac => "FLAC",
+                AudioEncoding::Mp3 => "MP3",
+                AudioEncoding::OggOpus => "OGG_OPUS",
+                AudioEncoding::WebmOpus => "WEBM_OPUS",
+            }
+        }
+    }
+}
+/// Configuration to enable speaker diarization.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SpeakerDiarizationConfig {
+    /// Required. Minimum number of speakers in the conversation.
+    #[prost(int32, tag="2")]
+    pub min_speaker_count: i32,
+    /// Required. Maximum number of speakers in the conversation.
+    #[prost(int32, tag="3")]
+    pub max_speaker_count: i32,
+}
+/// Available recognition features.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecognitionFeatures {
+    /// If set to `true`, the server will attempt to filter out profanities.
+    #[prost(bool, tag="1")]
+    pub profanity_filter: bool,
+    /// If `true`, the top result includes a list of words and the start and end
+    /// time offsets (timestamps) for those words.
+    #[prost(bool, tag="2")]
+    pub enable_word_time_offsets: bool,
+    /// If `true`, the top result includes a list of words and the confidence for
+    /// those words.
+    #[prost(bool, tag="3")]
+    pub enable_word_confidence: bool,
+    /// If `true`, adds punctuation to recognition result hypotheses.
+    #[prost(bool, tag="4")]
+    pub enable_automatic_punctuation: bool,
+    /// The spoken punctuation behavior for the call.
+    #[prost(bool, tag="14")]
+    pub enable_spoken_punctuation: bool,
+    /// The spoken emoji behavior for the call.
+    #[prost(bool, tag="15")]
+    pub enable_spoken_emojis: bool,
+    /// Mode for recognizing multi-channel audio.
+    #[prost(enumeration="recognition_features::MultiChannelMode", tag="17")]
+    pub multi_channel_mode: i32,
+    /// Configuration to enable speaker diarization and set additional
+    /// parameters to make diarization better suited for your application.
+    #[prost(message, optional, tag="9")]
+    pub diarization_config: ::core::option::Option<SpeakerDiarizationConfig>,
+    /// Maximum number of recognition hypotheses to be returned.
+    #[prost(int32, tag="16")]
+    pub max_alternatives: i32,
+}
+/// Nested message and enum types in `RecognitionFeatures`.
+pub mod recognition_features {
+    /// Options for how to recognize multi-channel audio.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum MultiChannelMode {
+        /// Default value for the multi-channel mode. If the audio contains
+        /// multiple channels, only the first channel will be transcribed; other
+        /// channels will be ignored.
+        Unspecified = 0,
+        /// If selected, each channel in the provided audio is transcribed
+        /// independently. This cannot be selected if the selected
+        /// \[model][google.cloud.speech.v2.Recognizer.model\] is `latest_short`.
+        SeparateRecognitionPerChannel = 1,
+    }
+    impl MultiChannelMode {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                MultiChannelMode::Unspecified => "MULTI_CHANNEL_MODE_UNSPECIFIED",
+                MultiChannelMode::SeparateRecognitionPerChannel => "SEPARATE_RECOGNITION_PER_CHANNEL",
+            }
+        }
+    }
+}
+/// Transcription normalization configuration. Use transcription normalization
+/// to automatically replace parts of the transcript with phrases of your
+/// choosing.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TranscriptNormalization {
+    /// A list of replacement entries. We will perform replacement with one entry
+    /// at a time.
+    #[prost(message, repeated, tag="1")]
+    pub entries: ::prost::alloc::vec::Vec<transcript_normalization::Entry>,
+}
+/// Nested message and enum types in `TranscriptNormalization`.
+pub mod transcript_normalization {
+    /// A single replacement configuration.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Entry {
+        /// What to replace. Max length is 100 characters.
+        #[prost(string, tag="1")]
+        pub search: ::prost::alloc::string::String,
+        /// What to replace with. Max length is 100 characters.
+        #[prost(string, tag="2")]
+        pub replace: ::prost::alloc::string::String,
+        /// Whether the search is case sensitive.
+        #[prost(bool, tag="3")]
+        pub case_sensitive: bool,
+    }
+}
+/// Translation configuration. Use to translate the given audio into text for the
+/// desired language.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TranslationConfig {
+    /// Required. The language code to translate to.
+    #[prost(string, tag="1")]
+    pub target_language: ::prost::alloc::string::String,
+}
+/// Provides information to the Recognizer that specifies how to process the
+/// recognition request.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecognitionConfig {
+    /// Optional. Which model to use for recognition requests. Select the model
+    /// best suited to your domain to get best results (e.g. `long`, `short`,
+    /// `telephony` or `chirp`).
+    #[prost(string, tag="9")]
+    pub model: ::prost::alloc::string::String,
+    /// Optional. The language of the supplied audio as a
+    /// \[BCP-47\](<https://www.rfc-editor.org/rfc/bcp/bcp47.txt>) language tag.
+    /// Language tags are normalized to BCP-47 before they are used eg "en-us"
+    /// becomes "en-US".
+    #[prost(string, repeated, tag="10")]
+    pub language_codes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Speech recognition features to enable.
+    #[prost(message, optional, tag="2")]
+    pub features: ::core::option::Option<RecognitionFeatures>,
+    /// Optional. Use transcription normalization to automatically replace parts of
+    /// the transcript with phrases of your choosing.
+    #[prost(message, optional, tag="11")]
+    pub transcript_normalization: ::core::option::Option<TranscriptNormalization>,
+    /// Optional. Optional configuration used to automatically run translation on
+    /// the given audio to the desired language for supported models.
+    #[prost(message, optional, tag="15")]
+    pub translation_config: ::core::option::Option<TranslationConfig>,
+    /// Decoding parameters for audio being sent for recognition.
+    #[prost(oneof="recognition_config::DecodingConfig", tags="7, 8")]
+    pub decoding_config: ::core::option::Option<recognition_config::DecodingConfig>,
+}
+/// Nested message and enum types in `RecognitionConfig`.
+pub mod recognition_config {
+    /// Decoding parameters for audio being sent for recognition.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum DecodingConfig {
+        /// Automatically detect decoding parameters.
+        /// Preferred for supported formats.
+        #[prost(message, tag="7")]
+        AutoDecodingConfig(super::AutoDetectDecodingConfig),
+        /// Explicitly specified decoding parameters.
+        /// Required if using headerless PCM audio (linear16, mulaw, alaw).
+        #[prost(message, tag="8")]
+        ExplicitDecodingConfig(super::ExplicitDecodingConfig),
+    }
+}
+/// Request message for the
+/// \[Recognize][google.cloud.speech.v2.Speech.Recognize\] method. Either
+/// `content` or `uri` must be supplied. Supplying both or neither returns
+/// \[INVALID_ARGUMENT][google.rpc.Code.INVALID_ARGUMENT\].
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecognizeRequest {
+    /// Required. The name of the Recognizer to use during recognition. The
+    /// expected format is
+    /// `projects/{project}/locations/{location}/recognizers/{recognizer}`. The
+    /// {recognizer} segment may be set to `_` to use an empty implicit Recognizer.
+    #[prost(string, tag="3")]
+    pub recognizer: ::prost::alloc::string::String,
+    /// Features and audio metadata to use for the Automatic Speech Recognition.
+    /// This field in combination with the
+    /// \[config_mask][google.cloud.speech.v2.RecognizeRequest.config_mask\] field
+    /// can be used to override parts of the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the Recognizer resource.
+    #[prost(message, optional, tag="1")]
+    pub config: ::core::option::Option<RecognitionConfig>,
+    /// The list of fields in
+    /// \[config][google.cloud.speech.v2.RecognizeRequest.config\] that override the
+    /// values in the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the recognizer during this recognition request.
+    #[prost(message, optional, tag="8")]
+    pub config_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// The audio source, which is either inline content or a Google Cloud
+    /// Storage URI.
+    #[prost(oneof="recognize_request::AudioSource", tags="5, 6")]
+    pub audio_source: ::core::option::Option<recognize_request::AudioSource>,
+}
+/// Nested message and enum types in `RecognizeRequest`.
+pub mod recognize_request {
+    /// The audio source, which is either inline content or a Google Cloud
+    /// Storage URI.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum AudioSource {
+        /// The audio data bytes encoded as specified in
+        /// \[RecognitionConfig][google.cloud.speech.v2.RecognitionConfig\]. As
+        /// with all bytes fields, proto buffers use a pure binary representation,
+        /// whereas JSON representations use base64.
+        #[prost(bytes, tag="5")]
+        Content(::prost::alloc::vec::Vec<u8>),
+        /// URI that points to a file that contains audio data bytes as specified in
+        /// \[RecognitionConfig][google.cloud.speech.v2.RecognitionConfig\]. The file
+        /// must not be compressed (for example, gzip).
+        #[prost(string, tag="6")]
+        Uri(::prost::alloc::string::String),
+    }
+}
+/// Metadata about the recognition request and response.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecognitionResponseMetadata {
+    /// Global request identifier auto-generated by the API.
+    #[prost(string, tag="9")]
+    pub request_id: ::prost::alloc::string::String,
+    /// When available, billed audio seconds for the corresponding request.
+    #[prost(message, optional, tag="6")]
+    pub total_billed_duration: ::core::option::Option<::prost_types::Duration>,
+}
+/// Alternative hypotheses (a.k.a. n-best list).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SpeechRecognitionAlternative {
+    /// Transcript text representing the words that the user spoke.
+    #[prost(string, tag="1")]
+    pub transcript: ::prost::alloc::string::String,
+    /// The confidence estimate between 0.0 and 1.0. A higher number
+    /// indicates an estimated greater likelihood that the recognized words are
+    /// correct.
+    #[prost(float, tag="2")]
+    pub confidence: f32,
+    /// A list of word-specific information for each recognized word.
+    #[prost(message, repeated, tag="3")]
+    pub words: ::prost::alloc::vec::Vec<WordInfo>,
+}
+/// Word-specific information for recognized words.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WordInfo {
+    /// Time offset relative to the beginning of the audio,
+    /// and corresponding to the start of the spoken word.
+    #[prost(message, optional, tag="1")]
+    pub start_offset: ::core::option::Option<::prost_types::Duration>,
+    /// Time offset relative to the beginning of the audio,
+    /// and corresponding to the end of the spoken word.
+    #[prost(message, optional, tag="2")]
+    pub end_offset: ::core::option::Option<::prost_types::Duration>,
+    /// The word corresponding to this set of information.
+    #[prost(string, tag="3")]
+    pub word: ::prost::alloc::string::String,
+    /// The confidence estimate between 0.0 and 1.0.
+    #[prost(float, tag="4")]
+    pub confidence: f32,
+    /// A distinct label is assigned for every speaker within the audio.
+    #[prost(string, tag="6")]
+    pub speaker_label: ::prost::alloc::string::String,
+}
+/// A speech recognition result corresponding to a portion of the audio.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SpeechRecognitionResult {
+    /// May contain one or more recognition hypotheses. These alternatives are
+    /// ordered in terms of accuracy, with the top (first) alternative being the
+    /// most probable, as ranked by the recognizer.
+    #[prost(message, repeated, tag="1")]
+    pub alternatives: ::prost::alloc::vec::Vec<SpeechRecognitionAlternative>,
+    /// For multi-channel audio, this is the channel number corresponding to the
+    /// recognized result for the audio from that channel.
+    /// For `audio_channel_count` = `N`, its output values can range from `1` to
+    /// `N`.
+    #[prost(int32, tag="2")]
+    pub channel_tag: i32,
+    /// Time offset of the end of this result relative to the beginning of the
+    /// audio.
+    #[prost(message, optional, tag="4")]
+    pub result_end_offset: ::core::option::Option<::prost_types::Duration>,
+    /// Output only. The \[BCP-47\](<https://www.rfc-editor.org/rfc/bcp/bcp47.txt>)
+    /// language tag of the language in this result.
+    #[prost(string, tag="5")]
+    pub language_code: ::prost::alloc::string::String,
+}
+/// Response message for the
+/// \[Recognize][google.cloud.speech.v2.Speech.Recognize\] method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecognizeResponse {
+    /// Sequential list of transcription results corresponding to sequential
+    /// portions of audio.
+    #[prost(message, repeated, tag="3")]
+    pub results: ::prost::alloc::vec::Vec<SpeechRecognitionResult>,
+    /// Metadata about the recognition.
+    #[prost(message, optional, tag="2")]
+    pub metadata: ::core::option::Option<RecognitionResponseMetadata>,
+}
+/// Available recognition features specific to streaming recognition requests.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamingRecognitionFeatures {
+    /// If `true`, responses with voice activity speech events will be returned as
+    /// they are detected.
+    #[prost(bool, tag="1")]
+    pub enable_voice_activity_events: bool,
+    /// Whether or not to stream interim results to the client. If set to true,
+    /// interim results will be streamed to the client. Otherwise, only the final
+    /// response will be streamed back.
+    #[prost(bool, tag="2")]
+    pub interim_results: bool,
+    /// If set, the server will automatically close the stream after the specified
+    /// duration has elapsed after the last VOICE_ACTIVITY speech event has been
+    /// sent. The field `voice_activity_events` must also be set to true.
+    #[prost(message, optional, tag="3")]
+    pub voice_activity_timeout: ::core::option::Option<streaming_recognition_features::VoiceActivityTimeout>,
+    /// Optional. Endpointing sensitivity for this stream.
+    #[prost(enumeration="streaming_recognition_features::EndpointingSensitivity", tag="8")]
+    pub endpointing_sensitivity: i32,
+}
+/// Nested message and enum types in `StreamingRecognitionFeatures`.
+pub mod streaming_recognition_features {
+    /// Events that a timeout can be set on for voice activity.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct VoiceActivityTimeout {
+        /// Duration to timeout the stream if no speech begins. If this is set and
+        /// no speech is detected in this duration at the start of the stream, the
+        /// server will close the stream.
+        #[prost(message, optional, tag="1")]
+        pub speech_start_timeout: ::core::option::Option<::prost_types::Duration>,
+        /// Duration to timeout the stream after speech ends. If this is set and no
+        /// speech is detected in this duration after speech was detected, the server
+        /// will close the stream.
+        #[prost(message, optional, tag="2")]
+        pub speech_end_timeout: ::core::option::Option<::prost_types::Duration>,
+    }
+    /// Endpointing sensitivity is intended for applications that want to
+    /// minimize result latency, possibly at the expense of quality.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum EndpointingSensitivity {
+        /// If no value is specified, the values for
+        /// ENDPOINTING_SENSITIVITY_STANDARD will be used.
+        Unspecified = 0,
+        /// Standard sensitivity, no optimization for latency.
+        Standard = 1,
+        /// Super short sensitivity, optimized for super short utterances.
+        Supershort = 2,
+        /// Short sensitivity, optimized for short utterances.
+        Short = 3,
+    }
+    impl EndpointingSensitivity {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                EndpointingSensitivity::Unspecified => "ENDPOINTING_SENSITIVITY_UNSPECIFIED",
+                EndpointingSensitivity::Standard => "ENDPOINTING_SENSITIVITY_STANDARD",
+                EndpointingSensitivity::Supershort => "ENDPOINTING_SENSITIVITY_SUPERSHORT",
+                EndpointingSensitivity::Short => "ENDPOINTING_SENSITIVITY_SHORT",
+            }
+        }
+    }
+}
+/// Provides configuration information for the StreamingRecognize request.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamingRecognitionConfig {
+    /// Required. Features and audio metadata to use for the Automatic Speech
+    /// Recognition. This field in combination with the
+    /// \[config_mask][google.cloud.speech.v2.StreamingRecognitionConfig.config_mask\]
+    /// field can be used to override parts of the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the Recognizer resource.
+    #[prost(message, optional, tag="1")]
+    pub config: ::core::option::Option<RecognitionConfig>,
+    /// The list of fields in
+    /// \[config][google.cloud.speech.v2.StreamingRecognitionConfig.config\] that
+    /// override the values in the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the recognizer during this recognition request.
+    #[prost(message, optional, tag="3")]
+    pub config_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// Speech recognition features to enable specific to streaming audio
+    /// recognition requests.
+    #[prost(message, optional, tag="2")]
+    pub streaming_features: ::core::option::Option<StreamingRecognitionFeatures>,
+}
+/// Request message for the
+/// \[StreamingRecognize][google.cloud.speech.v2.Speech.StreamingRecognize\]
+/// method. Multiple
+/// \[StreamingRecognizeRequest][google.cloud.speech.v2.StreamingRecognizeRequest\]
+/// messages are sent in one call.
+///
+/// The first message must contain a
+/// \[recognizer][google.cloud.speech.v2.StreamingRecognizeRequest.recognizer\]
+/// and a
+/// \[streaming_config][google.cloud.speech.v2.StreamingRecognizeRequest.streaming_config\]
+/// message, all subsequent messages must only have
+/// \[audio][google.cloud.speech.v2.StreamingRecognizeRequest.audio\] set.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamingRecognizeRequest {
+    /// Required. The name of the Recognizer to use during recognition. The
+    /// expected format is
+    /// `projects/{project}/locations/{location}/recognizers/{recognizer}`. The
+    /// {recognizer} segment may be set to `_` to use an empty implicit Recognizer.
+    #[prost(string, tag="3")]
+    pub recognizer: ::prost::alloc::string::String,
+    #[prost(oneof="streaming_recognize_request::StreamingRequest", tags="6, 5")]
+    pub streaming_request: ::core::option::Option<streaming_recognize_request::StreamingRequest>,
+}
+/// Nested message and enum types in `StreamingRecognizeRequest`.
+pub mod streaming_recognize_request {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum StreamingRequest {
+        /// StreamingRecognitionConfig to be used in this recognition attempt.
+        /// If provided, it will override the default RecognitionConfig stored in the
+        /// Recognizer.
+        #[prost(message, tag="6")]
+        StreamingConfig(super::StreamingRecognitionConfig),
+        /// Inline audio bytes to be Recognized.
+        /// Maximum size for this field is 15 KB per request.
+        #[prost(bytes, tag="5")]
+        Audio(::prost::alloc::vec::Vec<u8>),
+    }
+}
+/// Request message for the
+/// \[BatchRecognize][google.cloud.speech.v2.Speech.BatchRecognize\]
+/// method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchRecognizeRequest {
+    /// Required. The name of the Recognizer to use during recognition. The
+    /// expected format is
+    /// `projects/{project}/locations/{location}/recognizers/{recognizer}`. The
+    /// {recognizer} segment may be set to `_` to use an empty implicit Recognizer.
+    #[prost(string, tag="1")]
+    pub recognizer: ::prost::alloc::string::String,
+    /// Features and audio metadata to use for the Automatic Speech Recognition.
+    #[prost(message, optional, tag="4")]
+    pub config: ::core::option::Option<RecognitionConfig>,
+    /// The list of fields in
+    /// \[config][google.cloud.speech.v2.BatchRecognizeRequest.config\] that override
+    /// the values in the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the recognizer during this recognition request.
+    #[prost(message, optional, tag="5")]
+    pub config_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// Audio files with file metadata for ASR.
+    /// The maximum number of files allowed to be specified is 15.
+    #[prost(message, repeated, tag="3")]
+    pub files: ::prost::alloc::vec::Vec<BatchRecognizeFileMetadata>,
+    /// Configuration options for where to output the transcripts of each file.
+    #[prost(message, optional, tag="6")]
+    pub recognition_output_config: ::core::option::Option<RecognitionOutputConfig>,
+    /// Processing strategy to use for this request.
+    #[prost(enumeration="batch_recognize_request::ProcessingStrategy", tag="7")]
+    pub processing_strategy: i32,
+}
+/// Nested message and enum types in `BatchRecognizeRequest`.
+pub mod batch_recognize_request {
+    /// Possible processing strategies for batch requests.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ProcessingStrategy {
+        /// Default value for the processing strategy. The request is processed as
+        /// soon as its received.
+        Unspecified = 0,
+        /// If selected, processes the request during lower utilization periods for a
+        /// price discount. The request is fulfilled within 24 hours.
+        DynamicBatching = 1,
+    }
+    impl ProcessingStrategy {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                ProcessingStrategy::Unspecified => "PROCESSING_STRATEGY_UNSPECIFIED",
+                ProcessingStrategy::DynamicBatching => "DYNAMIC_BATCHING",
+            }
+        }
+    }
+}
+/// Output configurations for Cloud Storage.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GcsOutputConfig {
+    /// The Cloud Storage URI prefix with which recognition results will be
+    /// written.
+    #[prost(string, tag="1")]
+    pub uri: ::prost::alloc::string::String,
+}
+/// Output configurations for inline response.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InlineOutputConfig {
+}
+/// Configuration options for the output(s) of recognition.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecognitionOutputConfig {
+    #[prost(oneof="recognition_output_config::Output", tags="1, 2")]
+    pub output: ::core::option::Option<recognition_output_config::Output>,
+}
+/// Nested message and enum types in `RecognitionOutputConfig`.
+pub mod recognition_output_config {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Output {
+        /// If this message is populated, recognition results are written to the
+        /// provided Google Cloud Storage URI.
+        #[prost(message, tag="1")]
+        GcsOutputConfig(super::GcsOutputConfig),
+        /// If this message is populated, recognition results are provided in the
+        /// \[BatchRecognizeResponse][google.cloud.speech.v2.BatchRecognizeResponse\]
+        /// message of the Operation when completed. This is only supported when
+        /// calling \[BatchRecognize][google.cloud.speech.v2.Speech.BatchRecognize\]
+        /// with just one audio file.
+        #[prost(message, tag="2")]
+        InlineResponseConfig(super::InlineOutputConfig),
+    }
+}
+/// Response message for
+/// \[BatchRecognize][google.cloud.speech.v2.Speech.BatchRecognize\] that is
+/// packaged into a longrunning \[Operation][google.longrunning.Operation\].
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchRecognizeResponse {
+    /// Map from filename to the final result for that file.
+    #[prost(map="string, message", tag="1")]
+    pub results: ::std::collections::HashMap<::prost::alloc::string::String, BatchRecognizeFileResult>,
+    /// When available, billed audio seconds for the corresponding request.
+    #[prost(message, optional, tag="2")]
+    pub total_billed_duration: ::core::option::Option<::prost_types::Duration>,
+}
+/// Output type for Cloud Storage of BatchRecognize transcripts. Though this
+/// proto isn't returned in this API anywhere, the Cloud Storage transcripts will
+/// be this proto serialized and should be parsed as such.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchRecognizeResults {
+    /// Sequential list of transcription results corresponding to sequential
+    /// portions of audio.
+    #[prost(message, repeated, tag="1")]
+    pub results: ::prost::alloc::vec::Vec<SpeechRecognitionResult>,
+    /// Metadata about the recognition.
+    #[prost(message, optional, tag="2")]
+    pub metadata: ::core::option::Option<RecognitionResponseMetadata>,
+}
+/// Final results written to Cloud Storage.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloudStorageResult {
+    /// The Cloud Storage URI to which recognition results were written.
+    #[prost(string, tag="1")]
+    pub uri: ::prost::alloc::string::String,
+}
+/// Final results returned inline in the recognition response.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InlineResult {
+    /// The transcript for the audio file.
+    #[prost(message, optional, tag="1")]
+    pub transcript: ::core::option::Option<BatchRecognizeResults>,
+}
+/// Final results for a single file.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchRecognizeFileResult {
+    /// Error if one was encountered.
+    #[prost(message, optional, tag="2")]
+    pub error: ::core::option::Option<super::super::super::rpc::Status>,
+    #[prost(message, optional, tag="3")]
+    pub metadata: ::core::option::Option<RecognitionResponseMetadata>,
+    #[prost(oneof="batch_recognize_file_result::Result", tags="5, 6")]
+    pub result: ::core::option::Option<batch_recognize_file_result::Result>,
+}
+/// Nested message and enum types in `BatchRecognizeFileResult`.
+pub mod batch_recognize_file_result {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Result {
+        /// Recognition results written to Cloud Storage. This is
+        /// populated only when
+        /// \[GcsOutputConfig][google.cloud.speech.v2.GcsOutputConfig\] is set in
+        /// the
+        /// \[RecognitionOutputConfig][[google.cloud.speech.v2.RecognitionOutputConfig\].
+        #[prost(message, tag="5")]
+        CloudStorageResult(super::CloudStorageResult),
+        /// Recognition results. This is populated only when
+        /// \[InlineOutputConfig][google.cloud.speech.v2.InlineOutputConfig\] is set in
+        /// the
+        /// \[RecognitionOutputConfig][[google.cloud.speech.v2.RecognitionOutputConfig\].
+        #[prost(message, tag="6")]
+        InlineResult(super::InlineResult),
+    }
+}
+/// Metadata about a single file in a batch for BatchRecognize.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BatchRecognizeFileMetadata {
+    /// Features and audio metadata to use for the Automatic Speech Recognition.
+    /// This field in combination with the
+    /// \[config_mask][google.cloud.speech.v2.BatchRecognizeFileMetadata.config_mask\]
+    /// field can be used to override parts of the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the Recognizer resource as well as the
+    /// \[config][google.cloud.speech.v2.BatchRecognizeRequest.config\] at the
+    /// request level.
+    #[prost(message, optional, tag="4")]
+    pub config: ::core::option::Option<RecognitionConfig>,
+    /// The list of fields in
+    /// \[config][google.cloud.speech.v2.BatchRecognizeFileMetadata.config\] that
+    /// override the values in the
+    /// \[default_recognition_config][google.cloud.speech.v2.Recognizer.default_recognition_config\]
+    /// of the recognizer during this recognition request. If no mask is provided,
+    /// all non-default valued fields in
+    /// \[config][google.cloud.speech.v2.BatchRecognizeFileMetadata.config\] override
+    /// the values in the recognizer for this recognition request.
+    #[prost(message, optional, tag="5")]
+    pub config_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// The audio source, which is a Google Cloud Storage URI.
+    #[prost(oneof="batch_recognize_file_metadata::AudioSource", tags="1")]
+    pub audio_source: ::core::option::Option<batch_recognize_file_metadata::AudioSource>,
+}
+/// Nested message and enum types in `BatchRecognizeFileMetadata`.
+pub mod batch_recognize_file_metadata {
+    /// The audio source, which is a Google Cloud Storage URI.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum AudioSource {
+        /// Cloud Storage URI for the audio file.
+        #[prost(string, tag="1")]
+        Uri(::prost::alloc::string::String),
+    }
+}
+/// A streaming speech recognition result corresponding to a portion of the audio
+/// that is currently being processed.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamingRecognitionResult {
+    /// May contain one or more recognition hypotheses. These alternatives are
+    /// ordered in terms of accuracy, with the top (first) alternative being the
+    /// most probable, as ranked by the recognizer.
+    #[prost(message, repeated, tag="1")]
+    pub alternatives: ::prost::alloc::vec::Vec<SpeechRecognitionAlternative>,
+    /// If `false`, this
+    /// \[StreamingRecognitionResult][google.cloud.speech.v2.StreamingRecognitionResult\]
+    /// represents an interim result that may change. If `true`, this is the final
+    /// time the speech service will return this particular
+    /// \[StreamingRecognitionResult][google.cloud.speech.v2.StreamingRecognitionResult\],
+    /// the recognizer will not return any further hypotheses for this portion of
+    /// the transcript and corresponding audio.
+    #[prost(bool, tag="2")]
+    pub is_final: bool,
+    /// An estimate of the likelihood that the recognizer will not change its guess
+    /// about this interim result. Values range from 0.0 (completely unstable)
+    /// to 1.0 (completely stable). This field is only provided for interim results
+    /// (\[is_final][google.cloud.speech.v2.StreamingRecognitionResult.is_final\]=`false`).
+    /// The default of 0.0 is a sentinel value indicating `stability` was not set.
+    #[prost(float, tag="3")]
+    pub stability: f32,
+    /// Time offset of the end of this result relative to the beginning of the
+    /// audio.
+    #[prost(message, optional, tag="4")]
+    pub result_end_offset: ::core::option::Option<::prost_types::Duration>,
+    /// For multi-channel audio, this is the channel number corresponding to the
+    /// recognized result for the audio from that channel.
+    /// For
+    /// `audio_channel_count` = `N`, its output values can range from `1` to `N`.
+    #[prost(int32, tag="5")]
+    pub channel_tag: i32,
+    /// Output only. The \[BCP-47\](<https://www.rfc-editor.org/rfc/bcp/bcp47.txt>)
+    /// language tag of the language in this result.
+    #[prost(string, tag="6")]
+    pub language_code: ::prost::alloc::string::String,
+}
+/// `StreamingRecognizeResponse` is the only message returned to the client by
+/// `StreamingRecognize`. A series of zero or more `StreamingRecognizeResponse`
+/// messages are streamed back to the client. If there is no recognizable
+/// audio then no messages are streamed back to the client.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamingRecognizeResponse {
+    /// This repeated list contains zero or more results that
+    /// correspond to consecutive portions of the audio currently being processed.
+    /// It contains zero or one
+    /// \[is_final][google.cloud.speech.v2.StreamingRecognitionResult.is_final\]=`true`
+    /// result (the newly settled portion), followed by zero or more
+    /// \[is_final][google.cloud.speech.v2.StreamingRecognitionResult.is_final\]=`false`
+    /// results (the interim results).
+    #[prost(message, repeated, tag="6")]
+    pub results: ::prost::alloc::vec::Vec<StreamingRecognitionResult>,
+    /// Indicates the type of speech event.
+    #[prost(enumeration="streaming_recognize_response::SpeechEventType", tag="3")]
+    pub speech_event_type: i32,
+    /// Time offset between the beginning of the audio and event emission.
+    #[prost(message, optional, tag="7")]
+    pub speech_event_offset: ::core::option::Option<::prost_types::Duration>,
+    /// Metadata about the recognition.
+    #[prost(message, optional, tag="5")]
+    pub metadata: ::core::option::Option<RecognitionResponseMetadata>,
+}
+/// Nested message and enum types in `StreamingRecognizeResponse`.
+pub mod streaming_recognize_response {
+    /// Indicates the type of speech event.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum SpeechEventType {
+        /// No speech event specified.
+        Unspecified = 0,
+        /// This event indicates that the server has detected the end of the user's
+        /// speech utterance and expects no additional speech. Therefore, the server
+        /// will not process additional audio and will close the gRPC bidirectional
+        /// stream. This event is only sent if there was a force cutoff due to
+        /// silence being detected early. This event is only available through the
+        /// `latest_short` \[model][google.cloud.speech.v2.Recognizer.model\].
+        EndOfSingleUtterance = 1,
+        /// This event indicates that the server has detected the beginning of human
+        /// voice activity in the stream. This event can be returned multiple times
+        /// if speech starts and stops repeatedly throughout the stream. This event
+        /// is only sent if `voice_activity_events` is set to true.
+        SpeechActivityBegin = 2,
+        /// This event indicates that the server has detected the end of human voice
+        /// activity in the stream. This event can be returned multiple times if
+        /// speech starts and stops repeatedly throughout the stream. This event is
+        /// only sent if `voice_activity_events` is set to true.
+        SpeechActivityEnd = 3,
+    }
+    impl SpeechEventType {
+        /// String value of the enum field names used in the ProtoBuf definition.
+        ///
+        /// The values are not transformed in any way and thus are considered stable
+        /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+        pub fn as_str_name(&self) -> &'static str {
+            match self {
+                SpeechEventType::Unspecified => "SPEECH_EVENT_TYPE_UNSPECIFIED",
+                SpeechEventType::EndOfSingleUtterance => "END_OF_SINGLE_UTTERANCE",
+                SpeechEventType::SpeechActivityBegin => "SPEECH_ACTIVITY_BEGIN",
+                SpeechEventType::SpeechActivityEnd => "SPEECH_ACTIVITY_END",
+            }
+        }
+    }
+}
+/// Generated client implementations.
+pub mod speech_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Enables speech transcription and resource management.
+    #[derive(Debug, Clone)]
+    pub struct SpeechClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl SpeechClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> SpeechClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> SpeechClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            SpeechClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Creates a [Recognizer][google.cloud.speech.v2.Recognizer].
+        pub async fn create_recognizer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateRecognizerRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::super::longrunning::Operation>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/CreateRecognizer",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Lists Recognizers.
+        pub async fn list_recognizers(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListRecognizersRequest>,
+        ) -> Result<tonic::Response<super::ListRecognizersResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/ListRecognizers",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Returns the requested
+        /// [Recognizer][google.cloud.speech.v2.Recognizer]. Fails with
+        /// [NOT_FOUND][google.rpc.Code.NOT_FOUND] if the requested Recognizer doesn't
+        /// exist.
+        pub async fn get_recognizer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRecognizerRequest>,
+        ) -> Result<tonic::Response<super::Recognizer>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/GetRecognizer",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Updates the [Recognizer][google.cloud.speech.v2.Recognizer].
+        pub async fn update_recognizer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateRecognizerRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::super::longrunning::Operation>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/UpdateRecognizer",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Deletes the [Recognizer][google.cloud.speech.v2.Recognizer].
+        pub async fn delete_recognizer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteRecognizerRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::super::longrunning::Operation>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/DeleteRecognizer",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Undeletes the [Recognizer][google.cloud.speech.v2.Recognizer].
+        pub async fn undelete_recognizer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UndeleteRecognizerRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::super::longrunning::Operation>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/UndeleteRecognizer",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Performs synchronous Speech recognition: receive results after all audio
+        /// has been sent and processed.
+        pub async fn recognize(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RecognizeRequest>,
+        ) -> Result<tonic::Response<super::RecognizeResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/Recognize",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        /// Performs bidirectional streaming speech recognition: receive results while
+        /// sending audio. This method is only available via the gRPC API (not REST).
+        pub async fn streaming_recognize(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<
+                Message = super::StreamingRecognizeRequest,
+            >,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::StreamingRecognizeResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/StreamingRecognize",
+            );
+            self.inner.streaming(request.into_streaming_request(), path, codec).await
+        }
+        /// Performs batch asynchronous speech recognition: send a request with N
+        /// audio files and receive a long running operation that can be polled to see
+        /// when the transcriptions are finished.
+        pub async fn batch_recognize(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BatchRecognizeRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::super::longrunning::Operation>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.speech.v2.Speech/BatchRecognize",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
@@ -337,3 +337,141 @@ pub mod text_to_speech_client {
         }
     }
 }
+/// The top-level message sent by the client for the
+/// `SynthesizeLongAudio` method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SynthesizeLongAudioRequest {
+    /// The resource states of the request in the form of
+    /// `projects/*/locations/*`.
+    #[prost(string, tag="1")]
+    pub parent: ::prost::alloc::string::String,
+    /// Required. The Synthesizer requires either plain text or SSML as input.
+    #[prost(message, optional, tag="2")]
+    pub input: ::core::option::Option<SynthesisInput>,
+    /// Required. The configuration of the synthesized audio.
+    #[prost(message, optional, tag="3")]
+    pub audio_config: ::core::option::Option<AudioConfig>,
+    /// Required. Specifies a Cloud Storage URI for the synthesis results. Must be
+    /// specified in the format: `gs://bucket_name/object_name`, and the bucket
+    /// must already exist.
+    #[prost(string, tag="4")]
+    pub output_gcs_uri: ::prost::alloc::string::String,
+    /// Required. The desired voice of the synthesized audio.
+    #[prost(message, optional, tag="5")]
+    pub voice: ::core::option::Option<VoiceSelectionParams>,
+}
+/// The message returned to the client by the `SynthesizeLongAudio` method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SynthesizeLongAudioResponse {
+}
+/// Metadata for response returned by the `SynthesizeLongAudio` method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SynthesizeLongAudioMetadata {
+    /// Time when the request was received.
+    #[prost(message, optional, tag="1")]
+    pub start_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// Deprecated. Do not use.
+    #[deprecated]
+    #[prost(message, optional, tag="2")]
+    pub last_update_time: ::core::option::Option<::prost_types::Timestamp>,
+    /// The progress of the most recent processing update in percentage, ie. 70.0%.
+    #[prost(double, tag="3")]
+    pub progress_percentage: f64,
+}
+/// Generated client implementations.
+pub mod text_to_speech_long_audio_synthesize_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Service that implements Google Cloud Text-to-Speech API.
+    #[derive(Debug, Clone)]
+    pub struct TextToSpeechLongAudioSynthesizeClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl TextToSpeechLongAudioSynthesizeClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> TextToSpeechLongAudioSynthesizeClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> TextToSpeechLongAudioSynthesizeClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            TextToSpeechLongAudioSynthesizeClient::new(
+                InterceptedService::new(inner, interceptor),
+            )
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Synthesizes long form text asynchronously.
+        pub async fn synthesize_long_audio(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SynthesizeLongAudioRequest>,
+        ) -> Result<
+            tonic::Response<super::super::super::super::longrunning::Operation>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/google.cloud.texttospeech.v1.TextToSpeechLongAudioSynthesize/SynthesizeLongAudio",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
@@ -7,8 +7,22 @@
 pub const CERTIFICATES: &[u8] = include_bytes!("../res/certs/roots.pem");
 
 pub mod api;
+pub mod audio;
+pub mod blocking;
+pub mod call_options;
+pub mod circuit_breaker;
 pub mod common;
 pub mod dialogflow;
 pub mod errors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod longrunning;
+pub mod metering;
+pub mod rate_limit;
+pub mod recording;
 pub mod speechtotext;
+pub mod streaming;
+pub mod telemetry;
 pub mod texttospeech;
+pub mod transport;
+pub mod voice_agent;
@@ -0,0 +1,342 @@
+//! Contains client for Google long running operations.
+//! Long running operations are returned by APIs performing
+//! computations that take longer time to complete (e.g. long running speech recognition,
+//! export/import/training of dialogflow agent). This client can be used to check
+//! status of such operations, cancel them or wait for their completion.
+use crate::api::grpc::google::longrunning::{
+    operation::Result as OperationResult,
+    operations_client::OperationsClient as GrpcOperationsClient, CancelOperationRequest,
+    GetOperationRequest, Operation,
+};
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::errors::{Error, Result};
+use crate::metering::PendingUsage;
+use crate::transport::{ClientConfig, GrpcChannel};
+use log::*;
+use prost::Message;
+use std::io::Cursor;
+use std::marker::PhantomData;
+use std::time::Duration;
+use tokio::time::sleep;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+/// Google long running operations client. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct OperationsClient {
+    /// internal GRPC google long running operations client
+    operations_client: GrpcOperationsClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+}
+
+impl OperationsClient {
+    /// Creates new long running operations client using GCP project JSON credentials.
+    /// Operations must be checked using the same API endpoint which created them,
+    /// e.g. speech.googleapis.com & https://speech.googleapis.com for long running recognition.
+    pub async fn create(
+        google_credentials: impl AsRef<str>,
+        domain_name: &'static str,
+        channel_url: &'static str,
+    ) -> Result<Self> {
+        OperationsClient::create_with_config(
+            google_credentials,
+            domain_name,
+            channel_url,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        domain_name: &'static str,
+        channel_url: &'static str,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(domain_name, channel_url, None, &client_config).await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(OperationsClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new long running operations client from existing GRPC channel and token interceptor.
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        OperationsClient {
+            operations_client: GrpcOperationsClient::with_interceptor(channel, token_interceptor),
+        }
+    }
+
+    /// Returns latest state of long running operation.
+    pub async fn get_operation(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let request = GetOperationRequest { name: name.into() };
+        let response: TonicResponse<Operation> = self
+            .operations_client
+            .get_operation(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Starts asynchronous cancellation of long running operation.
+    /// Cancellation is not guaranteed, use get_operation
+    /// to check whether operation was really cancelled.
+    pub async fn cancel_operation(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = CancelOperationRequest { name: name.into() };
+        self.operations_client
+            .cancel_operation(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Waits for completion of long running operation and returns
+    /// its final state. Function checks operation status regularly using get_operation
+    /// which is called every check_interval_ms ms. If check_interval_ms
+    /// is not specified default interval check is 1 sec.
+    pub async fn wait_operation(
+        &mut self,
+        operation: Operation,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let mut operation = operation;
+        while !operation.done {
+            sleep(Duration::from_millis(check_interval_ms.unwrap_or(1000))).await;
+            operation = self
+                .get_operation(operation.name.clone(), options.clone())
+                .await?;
+        }
+        Ok(operation)
+    }
+
+    /// Waits for completion of long running operation. Long running operation
+    /// result is then casted into respective response struct R (e.g. LongRunningRecognizeResponse).
+    /// If operation finished with error, this error is returned.
+    /// For operations without response (google.protobuf.Empty) use R = ().
+    pub async fn long_running_wait<R: Message + Default>(
+        &mut self,
+        operation: Operation,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<R>> {
+        let operation = self
+            .wait_operation(operation, check_interval_ms, options)
+            .await?;
+        decode_operation_result(operation)
+    }
+}
+
+/// Typed handle of long running operation. R is the type of operation
+/// response (e.g. SynthesizeLongAudioResponse), M is the type of operation
+/// metadata (e.g. SynthesizeLongAudioMetadata). Handle keeps latest known
+/// state of the operation and operations client of the API endpoint which created it.
+#[derive(Debug, Clone)]
+pub struct OperationHandle<R, M> {
+    operation: Operation,
+    operations_client: OperationsClient,
+    /// usage of the operation recorded once it succeeds
+    usage: Option<PendingUsage>,
+    _types: PhantomData<(R, M)>,
+}
+
+impl<R: Message + Default, M: Message + Default> OperationHandle<R, M> {
+    /// Creates new typed operation handle.
+    pub fn new(operation: Operation, operations_client: OperationsClient) -> Self {
+        OperationHandle {
+            operation,
+            operations_client,
+            usage: None,
+            _types: PhantomData,
+        }
+    }
+
+    /// Sets usage recorded on successful completion of the operation.
+    pub(crate) fn with_usage(mut self, usage: Option<PendingUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Returns name of the operation.
+    pub fn name(&self) -> &str {
+        &self.operation.name
+    }
+
+    /// Returns latest known state of the operation.
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    /// Returns whether operation was done when its state was last retrieved.
+    pub fn is_done(&self) -> bool {
+        self.operation.done
+    }
+
+    /// Returns operation metadata (from latest known state) decoded into M.
+    pub fn metadata(&self) -> Result<Option<M>> {
+        decode_operation_metadata(&self.operation)
+    }
+
+    /// Retrieves latest state of the operation from the API.
+    pub async fn refresh(&mut self, options: Option<CallOptions>) -> Result<&Operation> {
+        self.operation = self
+            .operations_client
+            .get_operation(self.operation.name.clone(), options)
+            .await?;
+        Ok(&self.operation)
+    }
+
+    /// Starts asynchronous cancellation of the operation.
+    pub async fn cancel(&mut self, options: Option<CallOptions>) -> Result<()> {
+        self.operations_client
+            .cancel_operation(self.operation.name.clone(), options)
+            .await
+    }
+
+    /// Waits for completion of the operation and returns its result decoded into R.
+    /// See OperationsClient::long_running_wait for details. Usage of the operation
+    /// (if metered) is recorded only if the operation succeeds.
+    pub async fn wait(
+        mut self,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<R>> {
+        let operation = self.operation.clone();
+        let result = self
+            .operations_client
+            .long_running_wait(operation, check_interval_ms, options)
+            .await;
+        if let (Ok(Some(_)), Some(usage)) = (&result, self.usage) {
+            usage.record(None);
+        }
+        result
+    }
+}
+
+/// Converts metadata of long running operation into respective metadata struct M.
+/// Returns None if operation does not contain any metadata.
+pub fn decode_operation_metadata<M: Message + Default>(operation: &Operation) -> Result<Option<M>> {
+    match &operation.metadata {
+        Some(metadata) => Ok(Some(M::decode(&mut Cursor::new(&metadata.value))?)),
+        None => Ok(None),
+    }
+}
+
+/// Converts result of finished long running operation into respective response struct R.
+/// Returns error if operation finished with error and None if operation
+/// does not contain any result (e.g. it is not done yet).
+pub fn decode_operation_result<R: Message + Default>(operation: Operation) -> Result<Option<R>> {
+    if let Some(operation_result) = operation.result {
+        match operation_result {
+            OperationResult::Error(rpc_status) => {
+                error!("long running operation rpc error {:?}", rpc_status);
+                let mut error =
+                    Error::new_with_code(rpc_status.message, rpc_status.code.to_string());
+                error.grpc_code = Some(rpc_status.code);
+                Err(error)
+            }
+            OperationResult::Response(any_response) => {
+                let response: R = R::decode(&mut Cursor::new(any_response.value))?;
+                Ok(Some(response))
+            }
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::api::grpc::google::rpc::Status as RpcStatus;
+
+    // cargo test -- --show-output test_decode_operation_result
+    #[test]
+    fn test_decode_operation_result() {
+        let response = prost_types::Duration {
+            seconds: 42,
+            nanos: 0,
+        };
+        let operation = Operation {
+            name: "operations/123".to_string(),
+            metadata: None,
+            done: true,
+            result: Some(OperationResult::Response(prost_types::Any {
+                type_url: "type.googleapis.com/google.protobuf.Duration".to_string(),
+                value: response.encode_to_vec(),
+            })),
+        };
+        let decoded: Option<prost_types::Duration> = decode_operation_result(operation).unwrap();
+        assert_eq!(decoded.unwrap().seconds, 42);
+    }
+
+    // cargo test -- --show-output test_decode_operation_error
+    #[test]
+    fn test_decode_operation_error() {
+        let operation = Operation {
+            name: "operations/123".to_string(),
+            metadata: None,
+            done: true,
+            result: Some(OperationResult::Error(RpcStatus {
+                code: 3,
+                message: "invalid agent".to_string(),
+                details: vec![],
+            })),
+        };
+        let err = decode_operation_result::<()>(operation).unwrap_err();
+        assert_eq!(err.message, "invalid agent");
+        assert_eq!(err.code.unwrap(), "3");
+        assert_eq!(err.grpc_code, Some(3));
+
+        let operation = Operation {
+            name: "operations/123".to_string(),
+            metadata: None,
+            done: false,
+            result: None,
+        };
+        assert!(decode_operation_result::<()>(operation).unwrap().is_none());
+    }
+
+    // cargo test -- --show-output test_decode_operation_metadata
+    #[test]
+    fn test_decode_operation_metadata() {
+        let metadata = prost_types::Timestamp {
+            seconds: 1700000000,
+            nanos: 0,
+        };
+        let mut operation = Operation {
+            name: "operations/123".to_string(),
+            metadata: Some(prost_types::Any {
+                type_url: "type.googleapis.com/google.protobuf.Timestamp".to_string(),
+                value: metadata.encode_to_vec(),
+            }),
+            done: false,
+            result: None,
+        };
+        let decoded: Option<prost_types::Timestamp> =
+            decode_operation_metadata(&operation).unwrap();
+        assert_eq!(decoded.unwrap().seconds, 1700000000);
+
+        operation.metadata = None;
+        assert!(
+            decode_operation_metadata::<prost_types::Timestamp>(&operation)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
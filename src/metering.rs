@@ -0,0 +1,500 @@
+//! Contains usage metering (cost accounting) of the billable units consumed by the clients
+//! of this crate:
+//!
+//! * speech-to-text: seconds of audio (billed duration reported by the API, otherwise
+//!   duration of LINEAR16/MULAW/ALAW audio sent, requests of unknown duration are
+//!   recorded as UnmeteredRequests)
+//! * text-to-speech: characters of synthesized text (SSML tags included) by voice tier
+//! * dialogflow: detect intent requests by input type (audio vs. text/event)
+//!
+//! Usage is recorded by UsageMeter set on the client (set_usage_meter) and passed to the exporters,
+//! e.g. in-memory UsageAggregator. Every record is tagged with labels of the meter merged with
+//! usage labels of the call options (CallOptions::usage_label), e.g.:
+//!
+//! ```ignore
+//! let aggregator = Arc::new(UsageAggregator::new());
+//! synthesizer.set_usage_meter(UsageMeter::new().with_exporter(aggregator.clone()));
+//! let options = CallOptions::new().usage_label("tenant", "acme");
+//! synthesizer.synthesize_speech(request, Some(options)).await?;
+//! let characters = aggregator.total_for(UsageMetric::TextToSpeechCharacters(VoiceTier::Wavenet), "tenant", "acme");
+//! ```
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    query_input::Input, DetectIntentRequest,
+};
+use crate::api::grpc::google::cloud::speechtotext::{v1, v1p1beta1};
+use crate::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, SynthesisInput, VoiceSelectionParams,
+};
+use crate::call_options::CallOptions;
+use crate::telemetry::{recognition_bytes_per_second, recognition_bytes_per_second_beta};
+use log::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Labels of the usage record (e.g. tenant=acme).
+pub type UsageLabels = BTreeMap<String, String>;
+
+/// Pricing tier of text-to-speech voice, derived from the voice name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VoiceTier {
+    Standard,
+    Wavenet,
+    Neural2,
+    News,
+    Studio,
+    Polyglot,
+    Journey,
+}
+
+impl VoiceTier {
+    /// Returns tier of the voice, e.g. en-US-Wavenet-D -> Wavenet.
+    /// Voices without explicit tier (or not specified voice name) are Standard.
+    pub fn from_voice_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let tiers = [
+            ("wavenet", VoiceTier::Wavenet),
+            ("neural2", VoiceTier::Neural2),
+            ("news", VoiceTier::News),
+            ("studio", VoiceTier::Studio),
+            ("polyglot", VoiceTier::Polyglot),
+            ("journey", VoiceTier::Journey),
+        ];
+        tiers
+            .iter()
+            .find(|(tier_name, _)| name.split('-').any(|part| part == *tier_name))
+            .map(|(_, tier)| *tier)
+            .unwrap_or(VoiceTier::Standard)
+    }
+}
+
+/// Billable unit being metered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UsageMetric {
+    /// Seconds of recognized audio (speech-to-text).
+    SpeechToTextSeconds,
+
+    /// Characters of synthesized text (text-to-speech) by voice tier.
+    TextToSpeechCharacters(VoiceTier),
+
+    /// Detect intent requests (including streaming sessions) with audio input.
+    DetectIntentAudioRequests,
+
+    /// Detect intent requests with text or event input.
+    DetectIntentTextRequests,
+
+    /// Billable requests of unknown quantity, e.g. speech-to-text of compressed
+    /// or Cloud Storage audio when billed duration is not reported by the API.
+    UnmeteredRequests,
+}
+
+/// Single usage record passed to the exporters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageRecord {
+    pub metric: UsageMetric,
+    pub quantity: f64,
+    pub labels: UsageLabels,
+    pub timestamp: SystemTime,
+}
+
+/// Exporter of usage records, e.g. into metrics backend or billing database.
+/// Called synchronously by the client once the billable call is finished,
+/// i.e. implementation should not block.
+pub trait UsageExporter: Send + Sync {
+    fn export(&self, record: &UsageRecord);
+}
+
+/// Aggregated usage of one metric and label set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageSummary {
+    pub metric: UsageMetric,
+    pub labels: UsageLabels,
+    pub quantity: f64,
+}
+
+/// In-memory exporter summing up usage by metric and labels.
+#[derive(Debug, Default)]
+pub struct UsageAggregator {
+    totals: Mutex<BTreeMap<(UsageMetric, UsageLabels), f64>>,
+}
+
+impl UsageAggregator {
+    pub fn new() -> Self {
+        UsageAggregator::default()
+    }
+
+    /// Returns aggregated usage.
+    pub fn snapshot(&self) -> Vec<UsageSummary> {
+        match self.totals.lock() {
+            Ok(totals) => summaries(totals.clone()),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns aggregated usage and resets the aggregator (e.g. at the end of billing period).
+    pub fn take(&self) -> Vec<UsageSummary> {
+        match self.totals.lock() {
+            Ok(mut totals) => summaries(std::mem::take(&mut *totals)),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns total usage of the metric regardless of labels.
+    pub fn total(&self, metric: UsageMetric) -> f64 {
+        self.snapshot()
+            .iter()
+            .filter(|summary| summary.metric == metric)
+            .map(|summary| summary.quantity)
+            .sum()
+    }
+
+    /// Returns total usage of the metric for records with given label value (e.g. tenant=acme).
+    pub fn total_for(&self, metric: UsageMetric, label: &str, value: &str) -> f64 {
+        self.snapshot()
+            .iter()
+            .filter(|summary| {
+                summary.metric == metric
+                    && summary.labels.get(label).map(String::as_str) == Some(value)
+            })
+            .map(|summary| summary.quantity)
+            .sum()
+    }
+}
+
+fn summaries(totals: BTreeMap<(UsageMetric, UsageLabels), f64>) -> Vec<UsageSummary> {
+    totals
+        .into_iter()
+        .map(|((metric, labels), quantity)| UsageSummary {
+            metric,
+            labels,
+            quantity,
+        })
+        .collect()
+}
+
+impl UsageExporter for UsageAggregator {
+    fn export(&self, record: &UsageRecord) {
+        if let Ok(mut totals) = self.totals.lock() {
+            *totals
+                .entry((record.metric, record.labels.clone()))
+                .or_insert(0.0) += record.quantity;
+        }
+    }
+}
+
+/// Usage meter set on the client. Is cheaply cloneable, clones share the exporters.
+#[derive(Clone, Default)]
+pub struct UsageMeter {
+    exporters: Vec<Arc<dyn UsageExporter>>,
+    labels: UsageLabels,
+}
+
+impl fmt::Debug for UsageMeter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageMeter")
+            .field("exporters", &self.exporters.len())
+            .field("labels", &self.labels)
+            .finish()
+    }
+}
+
+impl UsageMeter {
+    pub fn new() -> Self {
+        UsageMeter::default()
+    }
+
+    /// Adds exporter of the usage records.
+    pub fn with_exporter(mut self, exporter: Arc<dyn UsageExporter>) -> Self {
+        self.exporters.push(exporter);
+        self
+    }
+
+    /// Adds label of all records recorded by this meter (e.g. tenant, application).
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns labels of the meter merged with usage labels of the current call options
+    /// (call options take precedence).
+    pub fn current_labels(&self) -> UsageLabels {
+        self.labels(CallOptions::current().as_ref())
+    }
+
+    /// Returns labels of the meter merged with usage labels of given call options
+    /// (call options take precedence).
+    pub fn labels(&self, options: Option<&CallOptions>) -> UsageLabels {
+        let mut labels = self.labels.clone();
+        if let Some(call_options) = options {
+            labels.extend(call_options.usage_labels.clone());
+        }
+        labels
+    }
+
+    /// Records usage tagged with current labels.
+    pub fn record(&self, metric: UsageMetric, quantity: f64) {
+        self.record_with_labels(metric, quantity, self.current_labels());
+    }
+
+    /// Records usage tagged with given labels.
+    pub fn record_with_labels(&self, metric: UsageMetric, quantity: f64, labels: UsageLabels) {
+        let record = UsageRecord {
+            metric,
+            quantity,
+            labels,
+            timestamp: SystemTime::now(),
+        };
+        trace!("usage: {:?}", record);
+        for exporter in &self.exporters {
+            exporter.export(&record);
+        }
+    }
+}
+
+/// Returns number of billable characters of synthesis input.
+fn synthesis_characters(input: Option<&SynthesisInput>) -> usize {
+    match input.and_then(|input| input.input_source.as_ref()) {
+        Some(InputSource::Text(text)) => text.chars().count(),
+        Some(InputSource::Ssml(ssml)) => ssml.chars().count(),
+        None => 0,
+    }
+}
+
+/// Returns metric (characters by voice tier) and billable characters of text-to-speech request.
+pub(crate) fn synthesis_usage(
+    input: Option<&SynthesisInput>,
+    voice: Option<&VoiceSelectionParams>,
+) -> (UsageMetric, f64) {
+    let tier = VoiceTier::from_voice_name(voice.map(|voice| voice.name.as_str()).unwrap_or(""));
+    (
+        UsageMetric::TextToSpeechCharacters(tier),
+        synthesis_characters(input) as f64,
+    )
+}
+
+/// Returns metric of detect intent request (audio vs. text/event input).
+pub(crate) fn detect_intent_usage(request: &DetectIntentRequest) -> UsageMetric {
+    let audio = !request.input_audio.is_empty()
+        || matches!(
+            request
+                .query_input
+                .as_ref()
+                .and_then(|input| input.input.as_ref()),
+            Some(Input::AudioConfig(_))
+        );
+    if audio {
+        UsageMetric::DetectIntentAudioRequests
+    } else {
+        UsageMetric::DetectIntentTextRequests
+    }
+}
+
+/// Recognition request carrying audio inline (synchronous or long running recognition).
+pub trait RecognitionAudioRequest {
+    /// Duration of the audio if it is sent inline in LINEAR16 or MULAW encoding.
+    fn audio_seconds(&self) -> Option<f64>;
+}
+
+fn inline_audio_seconds(content_len: Option<usize>, bytes_per_second: Option<f64>) -> Option<f64> {
+    Some(content_len? as f64 / bytes_per_second?)
+}
+
+impl RecognitionAudioRequest for v1::RecognizeRequest {
+    fn audio_seconds(&self) -> Option<f64> {
+        inline_audio_seconds(
+            v1_content_len(self.audio.as_ref()),
+            recognition_bytes_per_second(self.config.as_ref()?),
+        )
+    }
+}
+
+impl RecognitionAudioRequest for v1::LongRunningRecognizeRequest {
+    fn audio_seconds(&self) -> Option<f64> {
+        inline_audio_seconds(
+            v1_content_len(self.audio.as_ref()),
+            recognition_bytes_per_second(self.config.as_ref()?),
+        )
+    }
+}
+
+impl RecognitionAudioRequest for v1p1beta1::RecognizeRequest {
+    fn audio_seconds(&self) -> Option<f64> {
+        inline_audio_seconds(
+            beta_content_len(self.audio.as_ref()),
+            recognition_bytes_per_second_beta(self.config.as_ref()?),
+        )
+    }
+}
+
+impl RecognitionAudioRequest for v1p1beta1::LongRunningRecognizeRequest {
+    fn audio_seconds(&self) -> Option<f64> {
+        inline_audio_seconds(
+            beta_content_len(self.audio.as_ref()),
+            recognition_bytes_per_second_beta(self.config.as_ref()?),
+        )
+    }
+}
+
+fn v1_content_len(audio: Option<&v1::RecognitionAudio>) -> Option<usize> {
+    match audio?.audio_source.as_ref()? {
+        v1::recognition_audio::AudioSource::Content(content) => Some(content.len()),
+        _ => None,
+    }
+}
+
+fn beta_content_len(audio: Option<&v1p1beta1::RecognitionAudio>) -> Option<usize> {
+    match audio?.audio_source.as_ref()? {
+        v1p1beta1::recognition_audio::AudioSource::Content(content) => Some(content.len()),
+        _ => None,
+    }
+}
+
+/// Converts protobuf duration into seconds.
+pub(crate) fn duration_seconds(duration: &prost_types::Duration) -> f64 {
+    duration.seconds as f64 + duration.nanos as f64 / 1e9
+}
+
+/// Records quantity of the metric. If quantity is not known request is recorded as unmetered.
+pub(crate) fn record_quantity(
+    meter: &UsageMeter,
+    metric: UsageMetric,
+    quantity: Option<f64>,
+    labels: UsageLabels,
+) {
+    match quantity {
+        Some(quantity) => meter.record_with_labels(metric, quantity, labels),
+        None => {
+            debug!("usage: quantity of {:?} is not known", metric);
+            meter.record_with_labels(UsageMetric::UnmeteredRequests, 1.0, labels)
+        }
+    }
+}
+
+/// Records seconds of successful speech-to-text request (if usage meter is set). Billed duration
+/// reported by the API takes precedence over duration of the audio sent.
+pub(crate) fn meter_recognition(
+    meter: Option<&UsageMeter>,
+    billed_seconds: Option<f64>,
+    audio_seconds: Option<f64>,
+    options: Option<&CallOptions>,
+) {
+    if let Some(meter) = meter {
+        record_quantity(
+            meter,
+            UsageMetric::SpeechToTextSeconds,
+            billed_seconds.or(audio_seconds),
+            meter.labels(options),
+        );
+    }
+}
+
+/// Usage of long running operation recorded once the operation succeeds,
+/// failed or cancelled operations are not billed.
+#[derive(Debug, Clone)]
+pub(crate) struct PendingUsage {
+    meter: UsageMeter,
+    metric: UsageMetric,
+    /// quantity known when the operation was started (e.g. duration of the audio sent)
+    quantity: Option<f64>,
+    labels: UsageLabels,
+}
+
+impl PendingUsage {
+    pub(crate) fn new(
+        meter: &UsageMeter,
+        metric: UsageMetric,
+        quantity: Option<f64>,
+        options: Option<&CallOptions>,
+    ) -> Self {
+        PendingUsage {
+            meter: meter.clone(),
+            metric,
+            quantity,
+            labels: meter.labels(options),
+        }
+    }
+
+    /// Records usage of succeeded operation. Billed quantity
+    /// reported by the API takes precedence.
+    pub(crate) fn record(self, billed_quantity: Option<f64>) {
+        record_quantity(
+            &self.meter,
+            self.metric,
+            billed_quantity.or(self.quantity),
+            self.labels,
+        );
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_usage_aggregator
+    #[tokio::test]
+    async fn test_usage_aggregator() {
+        assert_eq!(
+            VoiceTier::from_voice_name("en-US-Wavenet-D"),
+            VoiceTier::Wavenet
+        );
+        assert_eq!(
+            VoiceTier::from_voice_name("en-GB-Neural2-A"),
+            VoiceTier::Neural2
+        );
+        assert_eq!(
+            VoiceTier::from_voice_name("en-US-Standard-B"),
+            VoiceTier::Standard
+        );
+        assert_eq!(VoiceTier::from_voice_name(""), VoiceTier::Standard);
+
+        let aggregator = Arc::new(UsageAggregator::new());
+        let meter = UsageMeter::new()
+            .with_exporter(aggregator.clone())
+            .label("app", "ivr");
+
+        meter.record(UsageMetric::SpeechToTextSeconds, 1.5);
+        CallOptions::new()
+            .usage_label("tenant", "acme")
+            .scope(async {
+                meter.record(UsageMetric::SpeechToTextSeconds, 2.0);
+                meter.record(UsageMetric::SpeechToTextSeconds, 0.5);
+            })
+            .await;
+
+        assert_eq!(aggregator.total(UsageMetric::SpeechToTextSeconds), 4.0);
+        assert_eq!(
+            aggregator.total_for(UsageMetric::SpeechToTextSeconds, "tenant", "acme"),
+            2.5
+        );
+        assert_eq!(
+            aggregator.total_for(UsageMetric::SpeechToTextSeconds, "app", "ivr"),
+            4.0
+        );
+
+        let summaries = aggregator.take();
+        assert_eq!(summaries.len(), 2);
+        assert!(aggregator.snapshot().is_empty());
+    }
+
+    // cargo test -- --show-output test_pending_usage
+    #[test]
+    fn test_pending_usage() {
+        let aggregator = Arc::new(UsageAggregator::new());
+        let meter = UsageMeter::new().with_exporter(aggregator.clone());
+
+        // billed duration takes precedence over duration of audio sent
+        meter_recognition(Some(&meter), Some(3.0), Some(2.5), None);
+        meter_recognition(Some(&meter), None, Some(2.5), None);
+        meter_recognition(Some(&meter), None, None, None);
+        assert_eq!(aggregator.total(UsageMetric::SpeechToTextSeconds), 5.5);
+        assert_eq!(aggregator.total(UsageMetric::UnmeteredRequests), 1.0);
+
+        let metric = UsageMetric::SpeechToTextSeconds;
+        let pending = PendingUsage::new(&meter, metric, Some(2.0), None);
+        // failed operation: pending usage is dropped without recording
+        drop(pending.clone());
+        pending.record(Some(4.0));
+        assert_eq!(aggregator.total(UsageMetric::SpeechToTextSeconds), 9.5);
+    }
+}
@@ -0,0 +1,404 @@
+//! Contains client-side rate limiting. Calls are limited before they leave the process
+//! by token buckets configured per API (GRPC service, e.g. google.cloud.speech.v1.Speech)
+//! or per method (e.g. google.cloud.texttospeech.v1.TextToSpeech/SynthesizeSpeech)
+//! and by maximal number of concurrent streaming calls. Calls exceeding the limits
+//! are either queued (up to max wait) or rejected with RESOURCE_EXHAUSTED status.
+//!
+//! Limiter adapts to server side quotas: when RESOURCE_EXHAUSTED error is returned by the API
+//! rate of respective bucket is halved, then it is restored gradually with every successful call.
+//!
+//! Limiter is set via ClientConfig (see transport module) and can be shared by multiple clients:
+//!
+//! ```ignore
+//! let limiter = RateLimiter::new()
+//!     .limit("google.cloud.texttospeech.v1.TextToSpeech/SynthesizeSpeech", 600)
+//!     .max_concurrent_streams(50)
+//!     .on_limit(LimitBehavior::Queue(Duration::from_secs(10)));
+//! let synthesizer = ClientConfig::new()
+//!     .rate_limiter(limiter.clone())
+//!     .scope(Synthesizer::create(credentials))
+//!     .await?;
+//! ```
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{sleep, timeout, Instant};
+use tonic::{Code, Status};
+
+/// Rate factor after RESOURCE_EXHAUSTED error is multiplied by this value.
+const BACKOFF_FACTOR: f64 = 0.5;
+
+/// Minimal rate factor (i.e. rate is never reduced below 10% of configured rate).
+const MIN_RATE_FACTOR: f64 = 0.1;
+
+/// Rate factor increase after every successful call.
+const RECOVERY_STEP: f64 = 0.05;
+
+/// Behavior of the limiter when the call exceeds the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitBehavior {
+    /// Call is rejected immediately with RESOURCE_EXHAUSTED status.
+    Reject,
+
+    /// Call waits until it is allowed, at most for specified duration.
+    /// Calls which would wait longer are rejected with RESOURCE_EXHAUSTED status.
+    Queue(Duration),
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    requests_per_minute: u32,
+    burst: u32,
+    tokens: f64,
+    rate_factor: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_minute: u32, burst: u32) -> Self {
+        TokenBucket {
+            requests_per_minute,
+            burst,
+            tokens: burst as f64,
+            rate_factor: 1.0,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Tokens per second (adapted).
+    fn rate(&self) -> f64 {
+        self.requests_per_minute as f64 / 60.0 * self.rate_factor
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate()).min(self.burst as f64);
+        self.updated = now;
+    }
+
+    /// Takes the token and returns time the caller must wait before the call.
+    /// Returns None (token not taken) if the wait would exceed max_wait.
+    fn reserve(&mut self, max_wait: Duration) -> Option<Duration> {
+        self.refill();
+        let wait = if self.tokens >= 1.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.rate())
+        };
+        if wait > max_wait {
+            return None;
+        }
+        self.tokens -= 1.0;
+        Some(wait)
+    }
+
+    fn backoff(&mut self) {
+        self.refill();
+        self.rate_factor = (self.rate_factor * BACKOFF_FACTOR).max(MIN_RATE_FACTOR);
+        self.tokens = self.tokens.min(0.0);
+    }
+
+    fn recover(&mut self) {
+        if self.rate_factor < 1.0 {
+            self.refill();
+            self.rate_factor = (self.rate_factor + RECOVERY_STEP).min(1.0);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    streams: Option<Arc<Semaphore>>,
+    max_concurrent_streams: Option<usize>,
+    behavior: LimitBehavior,
+}
+
+/// Client-side rate limiter. Is cheaply cloneable, all clones share the same buckets.
+/// Limiter must be configured before it is used by the clients.
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: Arc<LimiterState>,
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("behavior", &self.state.behavior)
+            .field("max_concurrent_streams", &self.state.max_concurrent_streams)
+            .finish()
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new()
+    }
+}
+
+/// Permit of the call allowed by the limiter. Holds the slot of the streaming call
+/// (if limited) until the call is finished.
+#[derive(Debug)]
+pub(crate) struct RateLimitPermit {
+    limiter: RateLimiter,
+    bucket: Option<String>,
+    _stream: Option<OwnedSemaphorePermit>,
+}
+
+impl RateLimitPermit {
+    /// Reports GRPC status of the finished call, RESOURCE_EXHAUSTED reduces rate of the bucket.
+    pub(crate) fn finish(self, code: Code) {
+        if let Some(bucket) = &self.bucket {
+            self.limiter.adapt(bucket, code);
+        }
+    }
+}
+
+/// Returns true for streaming methods (StreamingRecognize, StreamingDetectIntent, ...).
+fn is_streaming(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .map(|method| method.starts_with("Streaming"))
+        .unwrap_or(false)
+}
+
+fn resource_exhausted(message: String) -> Status {
+    Status::resource_exhausted(format!("client-side rate limit: {}", message))
+}
+
+impl RateLimiter {
+    /// Creates limiter without any limits, rejecting calls exceeding the limits.
+    pub fn new() -> Self {
+        RateLimiter {
+            state: Arc::new(LimiterState {
+                buckets: Mutex::new(HashMap::new()),
+                streams: None,
+                max_concurrent_streams: None,
+                behavior: LimitBehavior::Reject,
+            }),
+        }
+    }
+
+    fn update(mut self, update: impl FnOnce(&mut LimiterState)) -> Self {
+        match Arc::get_mut(&mut self.state) {
+            Some(state) => update(state),
+            None => log::warn!("RateLimiter: limiter already shared, configuration ignored"),
+        }
+        self
+    }
+
+    /// Limits requests per minute of API (GRPC service, e.g. google.cloud.speech.v1.Speech) or
+    /// single method (e.g. google.cloud.speech.v1.Speech/Recognize). Method limit takes precedence
+    /// over API limit. Allows burst of up to one second worth of requests (at least 1).
+    pub fn limit(self, key: impl Into<String>, requests_per_minute: u32) -> Self {
+        let burst = (requests_per_minute / 60).max(1);
+        self.limit_with_burst(key, requests_per_minute, burst)
+    }
+
+    /// Same as limit with explicit burst (capacity of the token bucket).
+    pub fn limit_with_burst(
+        self,
+        key: impl Into<String>,
+        requests_per_minute: u32,
+        burst: u32,
+    ) -> Self {
+        let key = key.into().trim_start_matches('/').to_string();
+        self.update(|state| {
+            if let Ok(mut buckets) = state.buckets.lock() {
+                buckets.insert(
+                    key,
+                    TokenBucket::new(requests_per_minute.max(1), burst.max(1)),
+                );
+            }
+        })
+    }
+
+    /// Limits number of concurrent streaming calls (across all APIs).
+    pub fn max_concurrent_streams(self, max_concurrent_streams: usize) -> Self {
+        self.update(|state| {
+            state.max_concurrent_streams = Some(max_concurrent_streams);
+            state.streams = Some(Arc::new(Semaphore::new(max_concurrent_streams)));
+        })
+    }
+
+    /// Sets behavior of the limiter when the call exceeds the limits (default Reject).
+    pub fn on_limit(self, behavior: LimitBehavior) -> Self {
+        self.update(|state| state.behavior = behavior)
+    }
+
+    /// Returns current (adapted) requests per minute of the bucket.
+    pub fn current_rate(&self, key: &str) -> Option<f64> {
+        let buckets = self.state.buckets.lock().ok()?;
+        buckets
+            .get(key.trim_start_matches('/'))
+            .map(|bucket| bucket.rate() * 60.0)
+    }
+
+    /// Returns key of the bucket limiting the call (method bucket takes precedence).
+    fn bucket_key(&self, path: &str) -> Option<String> {
+        let method = path.trim_start_matches('/');
+        let service = method.split('/').next().unwrap_or_default();
+        let buckets = self.state.buckets.lock().ok()?;
+        if buckets.contains_key(method) {
+            Some(method.to_string())
+        } else if buckets.contains_key(service) {
+            Some(service.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn max_wait(&self) -> Duration {
+        match self.state.behavior {
+            LimitBehavior::Reject => Duration::from_secs(0),
+            LimitBehavior::Queue(max_wait) => max_wait,
+        }
+    }
+
+    /// Waits until the call with given GRPC path is allowed or rejects it.
+    /// Slot of the streaming call is taken first, so the call rejected
+    /// for lack of stream slots does not consume the token of the bucket.
+    pub(crate) async fn acquire(&self, path: &str) -> Result<RateLimitPermit, Status> {
+        let started = Instant::now();
+
+        let stream = match (&self.state.streams, is_streaming(path)) {
+            (Some(streams), true) => {
+                let permit = match self.state.behavior {
+                    LimitBehavior::Reject => streams.clone().try_acquire_owned().ok(),
+                    LimitBehavior::Queue(max_wait) => {
+                        timeout(max_wait, streams.clone().acquire_owned())
+                            .await
+                            .ok()
+                            .and_then(|permit| permit.ok())
+                    }
+                };
+                match permit {
+                    Some(permit) => Some(permit),
+                    None => {
+                        return Err(resource_exhausted(
+                            "maximal number of concurrent streams reached".to_string(),
+                        ))
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let bucket = self.bucket_key(path);
+        if let Some(key) = &bucket {
+            let max_wait = self
+                .max_wait()
+                .checked_sub(started.elapsed())
+                .unwrap_or_default();
+            let reserved = match self.state.buckets.lock() {
+                Ok(mut buckets) => buckets
+                    .get_mut(key)
+                    .and_then(|bucket| bucket.reserve(max_wait)),
+                Err(_) => Some(Duration::from_secs(0)),
+            };
+            match reserved {
+                Some(wait) if wait > Duration::from_secs(0) => sleep(wait).await,
+                Some(_) => {}
+                // stream slot (if any) is released when dropped
+                None => return Err(resource_exhausted(format!("rate of {} exceeded", key))),
+            }
+        }
+
+        Ok(RateLimitPermit {
+            limiter: self.clone(),
+            bucket,
+            _stream: stream,
+        })
+    }
+
+    fn adapt(&self, key: &str, code: Code) {
+        if let Ok(mut buckets) = self.state.buckets.lock() {
+            if let Some(bucket) = buckets.get_mut(key) {
+                match code {
+                    Code::ResourceExhausted => {
+                        bucket.backoff();
+                        log::debug!(
+                            "RateLimiter: RESOURCE_EXHAUSTED, rate of {} reduced to {:.1}/min",
+                            key,
+                            bucket.rate() * 60.0
+                        );
+                    }
+                    Code::Ok => bucket.recover(),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_rate_limiter
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let limiter = RateLimiter::new()
+            .limit_with_burst("google.cloud.speech.v1.Speech", 60, 2)
+            .limit("/google.cloud.speech.v1.Speech/Recognize", 6000)
+            .max_concurrent_streams(1);
+        let streaming = "/google.cloud.speech.v1.Speech/StreamingRecognize";
+
+        // service bucket allows burst of 2 calls, the third one is rejected
+        let first = limiter.acquire(streaming).await.unwrap();
+        let second = limiter.acquire("/google.cloud.speech.v1.Speech/LongRunningRecognize");
+        assert!(second.await.is_ok());
+        let rejected = limiter.acquire("/google.cloud.speech.v1.Speech/LongRunningRecognize");
+        let rejected = rejected.await.unwrap_err();
+        assert_eq!(rejected.code(), Code::ResourceExhausted);
+
+        // method bucket takes precedence, concurrent streams limited only for streaming methods
+        assert!(limiter
+            .acquire("/google.cloud.speech.v1.Speech/Recognize")
+            .await
+            .is_ok());
+        assert!(limiter
+            .acquire("/google.cloud.texttospeech.v1.TextToSpeech/ListVoices")
+            .await
+            .is_ok());
+
+        // RESOURCE_EXHAUSTED from API halves the rate, success restores it gradually
+        first.finish(Code::ResourceExhausted);
+        let rate = limiter
+            .current_rate("google.cloud.speech.v1.Speech")
+            .unwrap();
+        assert!((rate - 30.0).abs() < 0.001);
+        limiter.adapt("google.cloud.speech.v1.Speech", Code::Ok);
+        let rate = limiter
+            .current_rate("google.cloud.speech.v1.Speech")
+            .unwrap();
+        assert!((rate - 33.0).abs() < 0.001);
+
+        // stream slot is released when the permit is dropped
+        let limiter = RateLimiter::new().max_concurrent_streams(1);
+        let stream = limiter.acquire(streaming).await.unwrap();
+        assert!(limiter.acquire(streaming).await.is_err());
+        drop(stream);
+        assert!(limiter.acquire(streaming).await.is_ok());
+    }
+
+    // cargo test -- --show-output test_rate_limiter_stream_slot
+    #[tokio::test]
+    async fn test_rate_limiter_stream_slot() {
+        let limiter = RateLimiter::new()
+            .limit_with_burst("google.cloud.speech.v1.Speech", 60, 2)
+            .max_concurrent_streams(1);
+        let streaming = "/google.cloud.speech.v1.Speech/StreamingRecognize";
+
+        // stream rejected for lack of stream slots does not consume the token
+        let _stream = limiter.acquire(streaming).await.unwrap();
+        assert!(limiter.acquire(streaming).await.is_err());
+        assert!(limiter
+            .acquire("/google.cloud.speech.v1.Speech/Recognize")
+            .await
+            .is_ok());
+    }
+}
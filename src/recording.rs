@@ -0,0 +1,729 @@
+//! Contains record & replay of GRPC calls. Recorder captures calls of the clients
+//! (request and response messages with their timings, final GRPC status) so that they can be
+//! saved into JSON file. Replayer plays recorded calls back through the same high level clients
+//! without any network access (clients do not connect nor authenticate), e.g. in regression tests.
+//!
+//! Recorder and replayer are set via ClientConfig (see transport module):
+//!
+//! ```ignore
+//! // record real session
+//! let recorder = Recorder::new();
+//! let mut recognizer = ClientConfig::new()
+//!     .recorder(recorder.clone())
+//!     .scope(Recognizer::create_streaming_recognizer(credentials, config, None))
+//!     .await?;
+//! ...
+//! recorder.recording().save("tests/data/streaming_recognize.json")?;
+//!
+//! // replay it offline
+//! let replayer = Replayer::new(Recording::load("tests/data/streaming_recognize.json")?);
+//! let mut recognizer = ClientConfig::new()
+//!     .replay(replayer)
+//!     .scope(Recognizer::create_streaming_recognizer("", config, None))
+//!     .await?;
+//! ```
+//!
+//! Recorded responses are replayed once the client sent the same amount of request data
+//! (e.g. audio) as it had sent when the response was recorded, optionally with original timing.
+//! Replayer is strict by default: request messages sent by the client must match recorded
+//! requests, otherwise the call fails with FAILED_PRECONDITION status describing the mismatch.
+use crate::errors::Result;
+use crate::transport::ResponseBody;
+use http_body::Body;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep_until, Instant};
+use tonic::body::BoxBody;
+use tonic::codegen::{http, Bytes, StdError};
+use tonic::{Code, Status};
+
+/// Request message (chunk of request body) sent by the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// Milliseconds since the start of the call.
+    pub offset_ms: u64,
+
+    /// Base64 encoded GRPC frame(s).
+    pub data: String,
+}
+
+/// Response message (chunk of response body) received from the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// Milliseconds since the start of the call.
+    pub offset_ms: u64,
+
+    /// Number of request bytes sent before the response was received.
+    pub request_bytes: u64,
+
+    /// Base64 encoded GRPC frame(s).
+    pub data: String,
+}
+
+/// Single recorded GRPC call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// GRPC path, e.g. /google.cloud.speech.v1.Speech/StreamingRecognize.
+    pub path: String,
+
+    pub requests: Vec<RecordedRequest>,
+
+    pub responses: Vec<RecordedResponse>,
+
+    /// Final GRPC status code.
+    pub status: i32,
+
+    /// GRPC trailers (grpc-status, grpc-message, grpc-status-details-bin).
+    pub trailers: BTreeMap<String, String>,
+
+    /// Milliseconds since the start of the call when the call finished.
+    pub finished_ms: u64,
+
+    /// Number of request bytes sent before the call finished.
+    pub request_bytes: u64,
+}
+
+/// Recorded calls, can be saved to / loaded from JSON file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub calls: Vec<RecordedCall>,
+}
+
+impl Recording {
+    /// Loads recording from JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Recording::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Saves recording into JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn millis(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+/// Captures GRPC calls of the clients. All clones share the same recording.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    calls: Arc<Mutex<Vec<RecordedCall>>>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder::default()
+    }
+
+    /// Returns calls recorded so far. Calls which are still in progress are included
+    /// with status UNKNOWN.
+    pub fn recording(&self) -> Recording {
+        Recording {
+            calls: self
+                .calls
+                .lock()
+                .map(|calls| calls.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Starts recording of the call.
+    pub(crate) fn start(&self, path: &str) -> CallRecorder {
+        let index = match self.calls.lock() {
+            Ok(mut calls) => {
+                calls.push(RecordedCall {
+                    path: path.to_string(),
+                    requests: vec![],
+                    responses: vec![],
+                    status: Code::Unknown as i32,
+                    trailers: BTreeMap::new(),
+                    finished_ms: 0,
+                    request_bytes: 0,
+                });
+                calls.len() - 1
+            }
+            Err(_) => usize::MAX,
+        };
+        CallRecorder {
+            inner: Arc::new(CallRecorderInner {
+                recorder: self.clone(),
+                index,
+                started: Instant::now(),
+                request_bytes: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CallRecorderInner {
+    recorder: Recorder,
+    index: usize,
+    started: Instant,
+    request_bytes: AtomicU64,
+}
+
+/// Recording of single call, shared by request and response body.
+#[derive(Debug, Clone)]
+pub(crate) struct CallRecorder {
+    inner: Arc<CallRecorderInner>,
+}
+
+impl CallRecorder {
+    fn update(&self, update: impl FnOnce(&mut RecordedCall)) {
+        if let Ok(mut calls) = self.inner.recorder.calls.lock() {
+            if let Some(call) = calls.get_mut(self.inner.index) {
+                update(call);
+            }
+        }
+    }
+
+    pub(crate) fn record_request(&self, data: &Bytes) {
+        self.inner
+            .request_bytes
+            .fetch_add(data.len() as u64, Ordering::Relaxed);
+        let offset_ms = millis(self.inner.started);
+        self.update(|call| {
+            call.requests.push(RecordedRequest {
+                offset_ms,
+                data: base64::encode(data),
+            })
+        });
+    }
+
+    pub(crate) fn record_response(&self, data: &Bytes) {
+        let request_bytes = self.inner.request_bytes.load(Ordering::Relaxed);
+        let offset_ms = millis(self.inner.started);
+        self.update(|call| {
+            call.responses.push(RecordedResponse {
+                offset_ms,
+                request_bytes,
+                data: base64::encode(data),
+            })
+        });
+    }
+
+    /// Records GRPC status headers (trailers or headers of trailers-only response).
+    pub(crate) fn record_trailers(&self, headers: &http::HeaderMap) {
+        let trailers: Vec<(String, String)> = headers
+            .iter()
+            .filter(|(name, _)| name.as_str().starts_with("grpc-"))
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        self.update(|call| call.trailers.extend(trailers));
+    }
+
+    pub(crate) fn finish(&self, code: Code) {
+        let request_bytes = self.inner.request_bytes.load(Ordering::Relaxed);
+        let finished_ms = millis(self.inner.started);
+        self.update(|call| {
+            call.status = code as i32;
+            call.finished_ms = finished_ms;
+            call.request_bytes = request_bytes;
+        });
+    }
+}
+
+/// Splits complete GRPC frames off the buffer and returns their messages.
+fn drain_messages(buffer: &mut Vec<u8>) -> Vec<Vec<u8>> {
+    let mut messages = vec![];
+    while buffer.len() >= 5 {
+        let len = u32::from_be_bytes([buffer[1], buffer[2], buffer[3], buffer[4]]) as usize;
+        if buffer.len() < 5 + len {
+            break;
+        }
+        let frame: Vec<u8> = buffer.drain(..5 + len).collect();
+        messages.push(frame[5..].to_vec());
+    }
+    messages
+}
+
+fn replay_mismatch(path: &str, message: String) -> Box<Status> {
+    Box::new(Status::failed_precondition(format!(
+        "replay mismatch of {}: {}",
+        path, message
+    )))
+}
+
+/// Compares request messages sent by the client with recorded requests (strict replay).
+struct RequestVerifier {
+    path: String,
+    recorded: Vec<Vec<u8>>,
+    sent: usize,
+    buffer: Vec<u8>,
+}
+
+impl RequestVerifier {
+    fn new(call: &RecordedCall) -> Self {
+        let mut recorded = vec![];
+        for request in &call.requests {
+            match base64::decode(&request.data) {
+                Ok(data) => recorded.extend(data),
+                Err(err) => log::error!(
+                    "Replayer: invalid recorded request of {}: {}",
+                    call.path,
+                    err
+                ),
+            }
+        }
+        RequestVerifier {
+            path: call.path.clone(),
+            recorded: drain_messages(&mut recorded),
+            sent: 0,
+            buffer: vec![],
+        }
+    }
+
+    /// Verifies request messages completed by the data sent by the client.
+    fn verify(&mut self, data: &[u8]) -> std::result::Result<(), Box<Status>> {
+        self.buffer.extend_from_slice(data);
+        for message in drain_messages(&mut self.buffer) {
+            let recorded = self.recorded.get(self.sent).ok_or_else(|| {
+                replay_mismatch(
+                    &self.path,
+                    format!(
+                        "unexpected request message #{} (recording contains {} request messages)",
+                        self.sent + 1,
+                        self.recorded.len()
+                    ),
+                )
+            })?;
+            if *recorded != message {
+                let offset = recorded
+                    .iter()
+                    .zip(message.iter())
+                    .position(|(recorded, sent)| recorded != sent)
+                    .unwrap_or_else(|| recorded.len().min(message.len()));
+                return Err(replay_mismatch(
+                    &self.path,
+                    format!(
+                        "request message #{} differs from recorded one at byte {} (sent {} bytes, recorded {} bytes)",
+                        self.sent + 1,
+                        offset,
+                        message.len(),
+                        recorded.len()
+                    ),
+                ));
+            }
+            self.sent += 1;
+        }
+        Ok(())
+    }
+
+    /// Verifies that all recorded request messages were sent once request stream is closed.
+    fn finish(&self) -> std::result::Result<(), Box<Status>> {
+        if self.sent < self.recorded.len() {
+            return Err(replay_mismatch(
+                &self.path,
+                format!(
+                    "client sent {} request messages, recording contains {}",
+                    self.sent,
+                    self.recorded.len()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Plays recorded calls back. Calls are matched by GRPC path in recorded order,
+/// every recorded call is replayed once. All clones share the same state.
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    recording: Arc<Recording>,
+    replayed: Arc<Mutex<Vec<bool>>>,
+    realtime: bool,
+    strict: bool,
+}
+
+impl Replayer {
+    pub fn new(recording: Recording) -> Self {
+        let replayed = vec![false; recording.calls.len()];
+        Replayer {
+            recording: Arc::new(recording),
+            replayed: Arc::new(Mutex::new(replayed)),
+            realtime: false,
+            strict: true,
+        }
+    }
+
+    /// Enables or disables verification of request messages against the recording
+    /// (enabled by default). Calls sending different requests than the recorded ones
+    /// fail with FAILED_PRECONDITION status.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Replays responses with original timing (by default responses are replayed
+    /// as soon as the client sends respective request data).
+    pub fn realtime(mut self, realtime: bool) -> Self {
+        self.realtime = realtime;
+        self
+    }
+
+    /// Returns number of recorded calls which were not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.replayed
+            .lock()
+            .map(|replayed| replayed.iter().filter(|replayed| !**replayed).count())
+            .unwrap_or(0)
+    }
+
+    fn next_call(&self, path: &str) -> Option<RecordedCall> {
+        let mut replayed = self.replayed.lock().ok()?;
+        let index = self
+            .recording
+            .calls
+            .iter()
+            .enumerate()
+            .position(|(index, call)| !replayed[index] && call.path == path)?;
+        replayed[index] = true;
+        Some(self.recording.calls[index].clone())
+    }
+
+    /// Replays the call, request body is consumed in the background.
+    pub(crate) fn replay(
+        &self,
+        request: http::Request<BoxBody>,
+    ) -> std::result::Result<http::Response<ResponseBody>, Box<Status>> {
+        let path = request.uri().path().to_string();
+        let call = self
+            .next_call(&path)
+            .ok_or_else(|| Box::new(Status::not_found(format!("no recorded call for {}", path))))?;
+
+        let mut trailers = http::HeaderMap::new();
+        for (name, value) in &call.trailers {
+            if let (Ok(name), Ok(value)) = (
+                http::header::HeaderName::from_bytes(name.as_bytes()),
+                http::HeaderValue::from_str(value),
+            ) {
+                trailers.insert(name, value);
+            }
+        }
+        if !trailers.contains_key("grpc-status") {
+            trailers.insert("grpc-status", http::HeaderValue::from(call.status));
+        }
+
+        let started = Instant::now();
+        let (request_bytes_tx, mut request_bytes) = watch::channel(0u64);
+        let mut request_body = request.into_body();
+        let mut verifier = self.strict.then(|| RequestVerifier::new(&call));
+        let mismatch: Arc<Mutex<Option<Box<Status>>>> = Arc::default();
+        let request_mismatch = mismatch.clone();
+        tokio::spawn(async move {
+            let mut sent = 0u64;
+            let mut verified = Ok(());
+            while let Some(Ok(data)) = request_body.data().await {
+                sent += data.len() as u64;
+                if let Some(verifier) = &mut verifier {
+                    verified = verifier.verify(&data);
+                    if verified.is_err() {
+                        break;
+                    }
+                }
+                let _ = request_bytes_tx.send(sent);
+            }
+            if let (Ok(()), Some(verifier)) = (&verified, &verifier) {
+                verified = verifier.finish();
+            }
+            if let Err(status) = verified {
+                log::warn!("Replayer: {}", status.message());
+                if let Ok(mut mismatch) = request_mismatch.lock() {
+                    *mismatch = Some(status);
+                }
+            }
+            // request stream closed, client cannot send more data
+            let _ = request_bytes_tx.send(u64::MAX);
+        });
+
+        let (data_tx, data_rx) = mpsc::unbounded_channel();
+        let realtime = self.realtime;
+        tokio::spawn(async move {
+            let steps = call
+                .responses
+                .iter()
+                .map(|response| {
+                    (
+                        response.request_bytes,
+                        response.offset_ms,
+                        Some(&response.data),
+                    )
+                })
+                .chain(std::iter::once((
+                    call.request_bytes,
+                    call.finished_ms,
+                    None,
+                )));
+            for (awaited_bytes, offset_ms, data) in steps {
+                while *request_bytes.borrow() < awaited_bytes {
+                    if request_bytes.changed().await.is_err() {
+                        break;
+                    }
+                }
+                if realtime {
+                    sleep_until(started + Duration::from_millis(offset_ms)).await;
+                }
+                // call sending different requests fails instead of replaying the responses
+                if let Some(status) = mismatch
+                    .lock()
+                    .ok()
+                    .and_then(|mut mismatch| mismatch.take())
+                {
+                    let _ = data_tx.send(Err(status));
+                    return;
+                }
+                if let Some(data) = data {
+                    let data = match base64::decode(data) {
+                        Ok(data) => data,
+                        Err(err) => {
+                            log::error!("Replayer: invalid recorded data for {}: {}", path, err);
+                            continue;
+                        }
+                    };
+                    if data_tx.send(Ok(Bytes::from(data))).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let body = ReplayBody {
+            data: data_rx,
+            trailers: Some(trailers),
+        };
+        http::Response::builder()
+            .status(200)
+            .header("content-type", "application/grpc")
+            .body(body.boxed_unsync())
+            .map_err(|err| {
+                Box::new(Status::internal(format!(
+                    "invalid replayed response: {}",
+                    err
+                )))
+            })
+    }
+}
+
+/// Response body of replayed call. Replay mismatch replaces recorded trailers.
+struct ReplayBody {
+    data: mpsc::UnboundedReceiver<std::result::Result<Bytes, Box<Status>>>,
+    trailers: Option<http::HeaderMap>,
+}
+
+impl Body for ReplayBody {
+    type Data = Bytes;
+    type Error = StdError;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Self::Data, Self::Error>>> {
+        match self.data.poll_recv(cx) {
+            Poll::Ready(Some(Ok(data))) => Poll::Ready(Some(Ok(data))),
+            Poll::Ready(Some(Err(status))) => {
+                let mut trailers = http::HeaderMap::new();
+                trailers.insert("grpc-status", http::HeaderValue::from(status.code() as i32));
+                if let Ok(message) = http::HeaderValue::from_str(status.message()) {
+                    trailers.insert("grpc-message", message);
+                }
+                self.trailers = Some(trailers);
+                Poll::Ready(None)
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<std::result::Result<Option<http::HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(self.trailers.take()))
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::api::grpc::google::cloud::texttospeech::v1::{
+        synthesis_input::InputSource, SynthesisInput, SynthesizeSpeechRequest,
+        SynthesizeSpeechResponse,
+    };
+    #[allow(unused_imports)]
+    use crate::texttospeech::synthesizer::Synthesizer;
+    #[allow(unused_imports)]
+    use crate::transport::ClientConfig;
+    #[allow(unused_imports)]
+    use prost::Message;
+
+    /// GRPC frame: not compressed, big endian length, message
+    #[allow(dead_code)]
+    fn grpc_frame(message: impl Message) -> Vec<u8> {
+        let message = message.encode_to_vec();
+        let mut frame = vec![0u8];
+        frame.extend((message.len() as u32).to_be_bytes());
+        frame.extend(message);
+        frame
+    }
+
+    #[allow(dead_code)]
+    fn synthesize_request(text: &str) -> SynthesizeSpeechRequest {
+        SynthesizeSpeechRequest {
+            input: Some(SynthesisInput {
+                input_source: Some(InputSource::Text(text.to_string())),
+            }),
+            voice: None,
+            audio_config: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn synthesize_recording(text: &str) -> Recording {
+        let frame = grpc_frame(SynthesizeSpeechResponse {
+            audio_content: b"replayed audio".to_vec(),
+        });
+        let request = grpc_frame(synthesize_request(text));
+        // response is replayed once the whole request is sent (and verified)
+        let request_bytes = request.len() as u64;
+        Recording {
+            calls: vec![RecordedCall {
+                path: "/google.cloud.texttospeech.v1.TextToSpeech/SynthesizeSpeech".to_string(),
+                requests: vec![RecordedRequest {
+                    offset_ms: 0,
+                    data: base64::encode(request),
+                }],
+                responses: vec![RecordedResponse {
+                    offset_ms: 0,
+                    request_bytes,
+                    data: base64::encode(&frame),
+                }],
+                status: 0,
+                trailers: BTreeMap::new(),
+                finished_ms: 0,
+                request_bytes,
+            }],
+        }
+    }
+
+    // cargo test -- --show-output test_replay
+    #[tokio::test]
+    async fn test_replay() {
+        let frame = grpc_frame(SynthesizeSpeechResponse {
+            audio_content: b"replayed audio".to_vec(),
+        });
+
+        let path = "/google.cloud.texttospeech.v1.TextToSpeech/SynthesizeSpeech";
+        let recording = Recording {
+            calls: vec![RecordedCall {
+                path: path.to_string(),
+                requests: vec![RecordedRequest {
+                    offset_ms: 0,
+                    data: base64::encode(grpc_frame(synthesize_request("hello"))),
+                }],
+                responses: vec![RecordedResponse {
+                    offset_ms: 0,
+                    request_bytes: 0,
+                    data: base64::encode(&frame),
+                }],
+                status: 0,
+                trailers: BTreeMap::new(),
+                finished_ms: 0,
+                request_bytes: 0,
+            }],
+        };
+        let recording = Recording::from_json(&recording.to_json().unwrap()).unwrap();
+
+        let replayer = Replayer::new(recording);
+        let recorder = Recorder::new();
+        // replayed clients do not need credentials
+        let mut synthesizer = ClientConfig::new()
+            .replay(replayer.clone())
+            .recorder(recorder.clone())
+            .scope(Synthesizer::create(""))
+            .await
+            .unwrap();
+
+        let request = synthesize_request("hello");
+        let response = synthesizer
+            .synthesize_speech(request.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.audio_content, b"replayed audio".to_vec());
+        assert_eq!(replayer.remaining(), 0);
+
+        // replayed call can be recorded again
+        let recorded = recorder.recording();
+        assert_eq!(recorded.calls.len(), 1);
+        assert_eq!(recorded.calls[0].responses[0].data, base64::encode(&frame));
+        assert_eq!(recorded.calls[0].status, 0);
+        assert_eq!(recorded.calls[0].requests.len(), 1);
+
+        // every recorded call is replayed once
+        let status = synthesizer
+            .synthesize_speech(request, None)
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", status).contains("no recorded call"));
+    }
+
+    // cargo test -- --show-output test_replay_with_config
+    #[tokio::test]
+    async fn test_replay_with_config() {
+        // config passed explicitly is applied without any scope
+        let config = ClientConfig::new().replay(Replayer::new(Recording::default()));
+        let mut synthesizer = Synthesizer::create_with_config("", config).await.unwrap();
+        let status = synthesizer
+            .synthesize_speech(SynthesizeSpeechRequest::default(), None)
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", status).contains("no recorded call"));
+    }
+
+    // cargo test -- --show-output test_replay_strict
+    #[tokio::test]
+    async fn test_replay_strict() {
+        // request differing from the recorded one fails the call
+        let config = ClientConfig::new().replay(Replayer::new(synthesize_recording("hello")));
+        let mut synthesizer = Synthesizer::create_with_config("", config).await.unwrap();
+        let status = synthesizer
+            .synthesize_speech(synthesize_request("bye"), None)
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", status).contains("replay mismatch"));
+
+        // requests are not verified by non-strict replayer
+        let replayer = Replayer::new(synthesize_recording("hello")).strict(false);
+        let config = ClientConfig::new().replay(replayer);
+        let mut synthesizer = Synthesizer::create_with_config("", config).await.unwrap();
+        let response = synthesizer
+            .synthesize_speech(synthesize_request("bye"), None)
+            .await
+            .unwrap();
+        assert_eq!(response.audio_content, b"replayed audio".to_vec());
+    }
+}
@@ -1,7 +1,23 @@
 //! Contains speech-to-text recognizers.
 
+/// Maximal size of audio sent inline (as content) in single recognition request.
+pub const MAX_INLINE_AUDIO_BYTES: usize = 10 * 1024 * 1024;
+
 /// v1 STT API
 pub mod recognizer;
 
 /// v1p1beta1 API
 pub mod recognizer_beta;
+
+/// v1p1beta1 adaptation API (phrase sets & custom classes)
+pub mod adaptation;
+
+/// v2 STT API (recognizer resources, batch recognition, location specific endpoints)
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+pub mod recognizer_v2;
+
+/// multi-channel recognition (v1 API)
+pub mod multichannel;
+
+/// batch transcription (v1 API)
+pub mod batch;
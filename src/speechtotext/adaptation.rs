@@ -0,0 +1,382 @@
+//! This module contains Google Speech adaptation client (v1p1beta1 API)
+//! that can be used to create, retrieve, list, update and delete
+//! phrase sets and custom classes. Persisted resources can be then
+//! referenced by name from SpeechAdaptation of recognition config
+//! (phrase_set_references) instead of sending large boost vocabularies
+//! inline with every request. This is simple request/response client,
+//! no streaming is involved. As a result this client is easily cloneable.
+use crate::api::grpc::google::cloud::speechtotext::v1p1beta1::{
+    adaptation_client::AdaptationClient as GrpcAdaptationClient, CreateCustomClassRequest,
+    CreatePhraseSetRequest, CustomClass, DeleteCustomClassRequest, DeletePhraseSetRequest,
+    GetCustomClassRequest, GetPhraseSetRequest, ListCustomClassesRequest,
+    ListCustomClassesResponse, ListPhraseSetRequest, ListPhraseSetResponse, PhraseSet,
+    SpeechAdaptation, UpdateCustomClassRequest, UpdatePhraseSetRequest,
+};
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::errors::Result;
+use crate::speechtotext::recognizer_v2::get_location_string;
+use crate::transport::{ClientConfig, GrpcChannel};
+use prost_types::FieldMask;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+
+const GRPC_API_DOMAIN: &str = "speech.googleapis.com";
+const GRPC_API_URL: &str = "https://speech.googleapis.com";
+
+/// Google Speech adaptation client.
+/// Used for management of phrase sets and custom classes. Is cloneable!
+#[derive(Debug, Clone)]
+pub struct AdaptationClient {
+    /// internal GRPC speech adaptation client
+    adaptation_client: GrpcAdaptationClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+}
+
+impl AdaptationClient {
+    /// Creates new adaptation client using GCP project JSON credentials.
+    pub async fn create(google_credentials: impl AsRef<str>) -> Result<Self> {
+        AdaptationClient::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+
+        Ok(AdaptationClient::new(
+            channel,
+            new_interceptor(token_header_val),
+        ))
+    }
+
+    /// Creates new adaptation client from existing GRPC channel and token interceptor.
+    pub(crate) fn new(channel: GrpcChannel, token_interceptor: TokenInterceptor) -> Self {
+        AdaptationClient {
+            adaptation_client: GrpcAdaptationClient::with_interceptor(channel, token_interceptor),
+        }
+    }
+
+    /// Creates phrase set in given parent. Parent is specified in format
+    /// projects/<Project ID>/locations/<Location ID> (see get_location_string).
+    /// Phrase set can be provided either as GRPC struct or as
+    /// api::rest::google::cloud::speechtotext::v1p1beta1::PhraseSet.
+    pub async fn create_phrase_set(
+        &mut self,
+        parent: impl Into<String>,
+        phrase_set_id: impl Into<String>,
+        phrase_set: impl Into<PhraseSet>,
+        options: Option<CallOptions>,
+    ) -> Result<PhraseSet> {
+        let request = CreatePhraseSetRequest {
+            parent: parent.into(),
+            phrase_set_id: phrase_set_id.into(),
+            phrase_set: Some(phrase_set.into()),
+        };
+        let response: TonicResponse<PhraseSet> = self
+            .adaptation_client
+            .create_phrase_set(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Retrieves the specified phrase set. Phrase set name is specified in format
+    /// projects/<Project ID>/locations/<Location ID>/phraseSets/<Phrase Set ID>
+    /// (see get_phrase_set_string).
+    pub async fn get_phrase_set(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<PhraseSet> {
+        let request = GetPhraseSetRequest { name: name.into() };
+        let response: TonicResponse<PhraseSet> = self
+            .adaptation_client
+            .get_phrase_set(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns single page of phrase sets of given parent. If page_size
+    /// is not specified default page size of the API is used.
+    pub async fn list_phrase_sets(
+        &mut self,
+        parent: impl Into<String>,
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        options: Option<CallOptions>,
+    ) -> Result<ListPhraseSetResponse> {
+        let request = ListPhraseSetRequest {
+            parent: parent.into(),
+            page_size: page_size.unwrap_or(0),
+            page_token: page_token.unwrap_or_default(),
+        };
+        let response: TonicResponse<ListPhraseSetResponse> = self
+            .adaptation_client
+            .list_phrase_set(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns all phrase sets of given parent. Iterates over all
+    /// result pages returned by list_phrase_sets.
+    pub async fn list_all_phrase_sets(
+        &mut self,
+        parent: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<PhraseSet>> {
+        let parent = parent.into();
+        let mut phrase_sets = vec![];
+        let mut page_token = None;
+        loop {
+            let response = self
+                .list_phrase_sets(parent.clone(), None, page_token, options.clone())
+                .await?;
+            phrase_sets.extend(response.phrase_sets);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            page_token = Some(response.next_page_token);
+        }
+        Ok(phrase_sets)
+    }
+
+    /// Updates the specified phrase set. If update_mask is not provided
+    /// all fields of the phrase set are updated.
+    pub async fn update_phrase_set(
+        &mut self,
+        phrase_set: impl Into<PhraseSet>,
+        update_mask: Option<FieldMask>,
+        options: Option<CallOptions>,
+    ) -> Result<PhraseSet> {
+        let request = UpdatePhraseSetRequest {
+            phrase_set: Some(phrase_set.into()),
+            update_mask,
+        };
+        let response: TonicResponse<PhraseSet> = self
+            .adaptation_client
+            .update_phrase_set(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes the specified phrase set.
+    pub async fn delete_phrase_set(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeletePhraseSetRequest { name: name.into() };
+        self.adaptation_client
+            .delete_phrase_set(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Creates custom class in given parent. Parent is specified in format
+    /// projects/<Project ID>/locations/<Location ID> (see get_location_string).
+    /// Custom class can be provided either as GRPC struct or as
+    /// api::rest::google::cloud::speechtotext::v1p1beta1::CustomClass.
+    pub async fn create_custom_class(
+        &mut self,
+        parent: impl Into<String>,
+        custom_class_id: impl Into<String>,
+        custom_class: impl Into<CustomClass>,
+        options: Option<CallOptions>,
+    ) -> Result<CustomClass> {
+        let request = CreateCustomClassRequest {
+            parent: parent.into(),
+            custom_class_id: custom_class_id.into(),
+            custom_class: Some(custom_class.into()),
+        };
+        let response: TonicResponse<CustomClass> = self
+            .adaptation_client
+            .create_custom_class(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Retrieves the specified custom class. Custom class name is specified in format
+    /// projects/<Project ID>/locations/<Location ID>/customClasses/<Custom Class ID>
+    /// (see get_custom_class_string).
+    pub async fn get_custom_class(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<CustomClass> {
+        let request = GetCustomClassRequest { name: name.into() };
+        let response: TonicResponse<CustomClass> = self
+            .adaptation_client
+            .get_custom_class(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns single page of custom classes of given parent. If page_size
+    /// is not specified default page size of the API is used.
+    pub async fn list_custom_classes(
+        &mut self,
+        parent: impl Into<String>,
+        page_size: Option<i32>,
+        page_token: Option<String>,
+        options: Option<CallOptions>,
+    ) -> Result<ListCustomClassesResponse> {
+        let request = ListCustomClassesRequest {
+            parent: parent.into(),
+            page_size: page_size.unwrap_or(0),
+            page_token: page_token.unwrap_or_default(),
+        };
+        let response: TonicResponse<ListCustomClassesResponse> = self
+            .adaptation_client
+            .list_custom_classes(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns all custom classes of given parent. Iterates over all
+    /// result pages returned by list_custom_classes.
+    pub async fn list_all_custom_classes(
+        &mut self,
+        parent: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<CustomClass>> {
+        let parent = parent.into();
+        let mut custom_classes = vec![];
+        let mut page_token = None;
+        loop {
+            let response = self
+                .list_custom_classes(parent.clone(), None, page_token, options.clone())
+                .await?;
+            custom_classes.extend(response.custom_classes);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            page_token = Some(response.next_page_token);
+        }
+        Ok(custom_classes)
+    }
+
+    /// Updates the specified custom class. If update_mask is not provided
+    /// all fields of the custom class are updated.
+    pub async fn update_custom_class(
+        &mut self,
+        custom_class: impl Into<CustomClass>,
+        update_mask: Option<FieldMask>,
+        options: Option<CallOptions>,
+    ) -> Result<CustomClass> {
+        let request = UpdateCustomClassRequest {
+            custom_class: Some(custom_class.into()),
+            update_mask,
+        };
+        let response: TonicResponse<CustomClass> = self
+            .adaptation_client
+            .update_custom_class(new_request(request, options)?)
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Deletes the specified custom class.
+    pub async fn delete_custom_class(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let request = DeleteCustomClassRequest { name: name.into() };
+        self.adaptation_client
+            .delete_custom_class(new_request(request, options)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Convenience function to return properly formatted location (parent) string
+    /// in format projects/<Project ID>/locations/<Location ID>.
+    pub fn get_location_string(project_id: &str, location: &str) -> String {
+        get_location_string(project_id, location)
+    }
+
+    /// Convenience function to return properly formatted phrase set string in format
+    /// projects/<Project ID>/locations/<Location ID>/phraseSets/<Phrase Set ID>.
+    pub fn get_phrase_set_string(project_id: &str, location: &str, phrase_set_id: &str) -> String {
+        format!(
+            "{}/phraseSets/{}",
+            get_location_string(project_id, location),
+            phrase_set_id
+        )
+    }
+
+    /// Convenience function to return properly formatted custom class string in format
+    /// projects/<Project ID>/locations/<Location ID>/customClasses/<Custom Class ID>.
+    pub fn get_custom_class_string(
+        project_id: &str,
+        location: &str,
+        custom_class_id: &str,
+    ) -> String {
+        format!(
+            "{}/customClasses/{}",
+            get_location_string(project_id, location),
+            custom_class_id
+        )
+    }
+}
+
+/// Returns reference to custom class that can be used inside of phrase value,
+/// e.g. format!("fly to {}", custom_class_reference(name)) gives
+/// "fly to ${projects/p/locations/global/customClasses/airports}".
+pub fn custom_class_reference(custom_class_name: &str) -> String {
+    format!("${{{}}}", custom_class_name)
+}
+
+/// Returns speech adaptation (to be used in RecognitionConfig.adaptation)
+/// referencing persisted phrase sets by their names.
+pub fn speech_adaptation_from_references(phrase_set_names: Vec<String>) -> SpeechAdaptation {
+    SpeechAdaptation {
+        phrase_sets: vec![],
+        phrase_set_references: phrase_set_names,
+        custom_classes: vec![],
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_adaptation_resource_names
+    #[test]
+    fn test_adaptation_resource_names() {
+        let phrase_set =
+            AdaptationClient::get_phrase_set_string("my-project", "global", "products");
+        assert_eq!(
+            phrase_set,
+            "projects/my-project/locations/global/phraseSets/products"
+        );
+        let custom_class =
+            AdaptationClient::get_custom_class_string("my-project", "global", "airports");
+        assert_eq!(
+            custom_class,
+            "projects/my-project/locations/global/customClasses/airports"
+        );
+        assert_eq!(
+            format!("fly to {}", custom_class_reference(&custom_class)),
+            "fly to ${projects/my-project/locations/global/customClasses/airports}"
+        );
+        assert_eq!(
+            AdaptationClient::get_location_string("my-project", "us"),
+            "projects/my-project/locations/us"
+        );
+
+        let adaptation = speech_adaptation_from_references(vec![phrase_set.clone()]);
+        assert_eq!(adaptation.phrase_set_references, vec![phrase_set]);
+        assert!(adaptation.phrase_sets.is_empty());
+    }
+}
@@ -0,0 +1,895 @@
+//! Batch transcription of large number of audio files (v1 API). Files are specified
+//! by manifest (local files or gs:// URIs). Short clips are recognized synchronously,
+//! long recordings and gs:// URIs (their length is not known upfront) are recognized
+//! using long running recognition. Jobs are processed with bounded concurrency
+//! and optional rate limiting. Progress is checkpointed into local state file
+//! so that interrupted batches can be resumed, results are written as JSON lines.
+//! Output is idempotent by entry id: entries already present in the output
+//! (e.g. written just before the batch was interrupted) are not transcribed again.
+//! Failed entries are retried up to max_attempts times, only the final failure
+//! is written into the output.
+use crate::api::grpc::google::cloud::speechtotext::v1::{
+    recognition_audio::AudioSource, recognition_config::AudioEncoding, LongRunningRecognizeRequest,
+    RecognitionAudio, RecognitionConfig, RecognizeRequest, SpeechRecognitionResult,
+};
+use crate::audio::wav::parse_wav;
+use crate::call_options::CallOptions;
+use crate::errors::{Error, Result};
+use crate::speechtotext::recognizer::Recognizer;
+use crate::speechtotext::MAX_INLINE_AUDIO_BYTES;
+use crate::transport::ClientConfig;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::time::{sleep_until, Instant};
+
+/// Single audio file to be transcribed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEntry {
+    /// Unique identifier of the entry used in state file and output. Defaults to uri.
+    #[serde(default)]
+    pub id: String,
+
+    /// Local file path or gs:// URI.
+    pub uri: String,
+
+    /// Optional language code overriding language code of batch recognition config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_code: Option<String>,
+}
+
+impl BatchEntry {
+    pub fn new(uri: impl Into<String>) -> Self {
+        let uri = uri.into();
+        BatchEntry {
+            id: uri.clone(),
+            uri,
+            language_code: None,
+        }
+    }
+
+    /// Returns true if entry points to Google Cloud Storage object.
+    pub fn is_gcs(&self) -> bool {
+        self.uri.starts_with("gs://")
+    }
+}
+
+/// List of audio files to be transcribed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchManifest {
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchManifest {
+    /// Parses manifest. Each non-empty line (lines starting with # are comments)
+    /// contains either file path/gs:// URI or JSON object with attributes
+    /// id, uri and languageCode.
+    pub fn from_manifest_str(manifest: &str) -> Result<Self> {
+        let mut entries = vec![];
+        for (idx, line) in manifest.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut entry = if line.starts_with('{') {
+                serde_json::from_str::<BatchEntry>(line).map_err(|err| {
+                    Error::new(format!(
+                        "BatchManifest: invalid entry on line {}: {}",
+                        idx + 1,
+                        err
+                    ))
+                })?
+            } else {
+                BatchEntry::new(line)
+            };
+            if entry.id.is_empty() {
+                entry.id = entry.uri.clone();
+            }
+            entries.push(entry);
+        }
+        Ok(BatchManifest { entries })
+    }
+
+    /// Loads manifest from file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        BatchManifest::from_manifest_str(&fs::read_to_string(path)?)
+    }
+
+    /// Creates manifest from all files in directory (not recursive) having
+    /// one of provided extensions (e.g. ["wav", "flac"]). Files are sorted by name.
+    pub fn from_dir(dir: impl AsRef<Path>, extensions: &[&str]) -> Result<Self> {
+        let mut paths = vec![];
+        for dir_entry in fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            let matches = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+                .unwrap_or(false);
+            if path.is_file() && matches {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(BatchManifest {
+            entries: paths
+                .iter()
+                .map(|path| BatchEntry::new(path.to_string_lossy()))
+                .collect(),
+        })
+    }
+}
+
+/// Recognition method used for the entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecognitionMethod {
+    Sync,
+    LongRunning,
+}
+
+/// Transcript of single recognition result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSegment {
+    pub transcript: String,
+    pub confidence: f32,
+    pub channel_tag: i32,
+}
+
+/// Result of single batch entry written as one JSON line into output file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    pub id: String,
+    pub uri: String,
+    pub method: Option<RecognitionMethod>,
+    /// Transcripts of all segments joined by space.
+    pub transcript: String,
+    pub segments: Vec<BatchSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    fn from_results(
+        entry: &BatchEntry,
+        method: RecognitionMethod,
+        results: Vec<SpeechRecognitionResult>,
+    ) -> Self {
+        let segments: Vec<BatchSegment> = results
+            .into_iter()
+            .filter_map(|result| {
+                let channel_tag = result.channel_tag;
+                result
+                    .alternatives
+                    .into_iter()
+                    .next()
+                    .map(|alternative| BatchSegment {
+                        transcript: alternative.transcript.trim().to_string(),
+                        confidence: alternative.confidence,
+                        channel_tag,
+                    })
+            })
+            .collect();
+        BatchResult {
+            id: entry.id.clone(),
+            uri: entry.uri.clone(),
+            method: Some(method),
+            transcript: segments
+                .iter()
+                .map(|segment| segment.transcript.as_str())
+                .filter(|transcript| !transcript.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" "),
+            segments,
+            error: None,
+        }
+    }
+
+    fn from_error(entry: &BatchEntry, method: Option<RecognitionMethod>, error: Error) -> Self {
+        BatchResult {
+            id: entry.id.clone(),
+            uri: entry.uri.clone(),
+            method,
+            transcript: "".to_string(),
+            segments: vec![],
+            error: Some(error.message),
+        }
+    }
+}
+
+/// Checkpoint of batch progress. Contains ids of successfully processed entries
+/// and number of failed attempts of entries which did not succeed (yet).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchState {
+    pub completed: BTreeSet<String>,
+
+    #[serde(default)]
+    pub failed_attempts: BTreeMap<String, u32>,
+}
+
+impl BatchState {
+    /// Loads state from file. Returns empty state if file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(BatchState::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Merges results already written into output file (JSON lines) into the state.
+    /// Successful results mark the entry as completed, error results (written only
+    /// once all attempts failed) mark the entry as exhausted. Lines which cannot
+    /// be parsed (e.g. line truncated by interrupted write) are ignored.
+    pub fn merge_output(&mut self, output: impl Read, max_attempts: u32) -> Result<()> {
+        for line in BufReader::new(output).lines() {
+            let result = match serde_json::from_str::<BatchResult>(&line?) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
+            if result.error.is_none() {
+                self.failed_attempts.remove(&result.id);
+                self.completed.insert(result.id);
+            } else if !self.completed.contains(&result.id) {
+                let attempts = self.failed_attempts.entry(result.id).or_insert(0);
+                *attempts = (*attempts).max(max_attempts);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores state into file. State is written into temporary file which
+    /// is then renamed so that interrupted write does not corrupt the state.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Batch transcription configuration.
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    /// Recognition config used for all entries. Encoding and sample rate
+    /// of WAV and FLAC files is taken from their header (see entry_recognition_config).
+    pub recognition_config: RecognitionConfig,
+
+    /// Maximal number of concurrently processed entries.
+    pub concurrency: usize,
+
+    /// Maximal number of recognition requests started per minute.
+    pub requests_per_minute: Option<u32>,
+
+    /// Local audio files up to this duration are recognized synchronously.
+    /// Synchronous recognition supports audio up to 1 minute.
+    pub sync_max_duration: Duration,
+
+    /// State file used to checkpoint progress.
+    pub state_file: Option<PathBuf>,
+
+    /// Output file, results are appended as JSON lines.
+    pub output_file: PathBuf,
+
+    /// Interval of long running operations status checks.
+    pub check_interval_ms: Option<u64>,
+
+    /// Maximal number of attempts to transcribe single entry. Failed entries are
+    /// retried (at the end of the queue) until attempts are exhausted, attempts
+    /// are checkpointed in state file.
+    pub max_attempts: u32,
+
+    /// If true, entries which exhausted all attempts in previous runs are retried
+    /// (with new set of attempts). Otherwise they are skipped.
+    pub retry_failed: bool,
+}
+
+impl BatchConfig {
+    pub fn new(recognition_config: RecognitionConfig, output_file: impl Into<PathBuf>) -> Self {
+        BatchConfig {
+            recognition_config,
+            concurrency: 4,
+            requests_per_minute: None,
+            sync_max_duration: Duration::from_secs(55),
+            state_file: None,
+            output_file: output_file.into(),
+            check_interval_ms: None,
+            max_attempts: 3,
+            retry_failed: false,
+        }
+    }
+}
+
+/// Summary of batch run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub total: usize,
+    /// entries skipped because they were completed (or exhausted all attempts) in previous run
+    pub skipped: usize,
+    pub succeeded: usize,
+    /// entries which failed in all attempts
+    pub failed: usize,
+    /// failed attempts which were retried
+    pub retried: usize,
+}
+
+/// Returns duration of local audio if it can be determined from WAV header or
+/// from file size for raw LINEAR16/MULAW audio. Returns None otherwise.
+pub fn audio_duration(audio: &[u8], config: &RecognitionConfig) -> Option<Duration> {
+    let (bytes, bytes_per_sec) = match parse_wav(audio) {
+        Ok((format, data)) => (
+            data.len() as u64,
+            format.sample_rate as u64 * format.channels as u64 * format.bits_per_sample as u64 / 8,
+        ),
+        Err(_) => {
+            let channels = config.audio_channel_count.max(1) as u64;
+            let bytes_per_sample = if config.encoding == AudioEncoding::Linear16 as i32 {
+                2
+            } else if config.encoding == AudioEncoding::Mulaw as i32 {
+                1
+            } else {
+                return None;
+            };
+            (
+                audio.len() as u64,
+                config.sample_rate_hertz.max(0) as u64 * channels * bytes_per_sample,
+            )
+        }
+    };
+    if bytes_per_sec == 0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64))
+}
+
+/// Chooses recognition method for the entry. Synchronous recognition is
+/// used only for local files with known duration not exceeding sync_max_duration.
+pub fn choose_method(
+    entry: &BatchEntry,
+    audio: Option<&[u8]>,
+    config: &BatchConfig,
+) -> RecognitionMethod {
+    if entry.is_gcs() {
+        return RecognitionMethod::LongRunning;
+    }
+    match audio.and_then(|audio| audio_duration(audio, &config.recognition_config)) {
+        Some(duration) if duration <= config.sync_max_duration => RecognitionMethod::Sync,
+        _ => RecognitionMethod::LongRunning,
+    }
+}
+
+/// Returns recognition config for the entry. Encoding, sample rate and channel count
+/// of local WAV files are taken from WAV header. Local FLAC files and gs:// URIs
+/// with .wav/.flac extension are sent without sample rate (and encoding) so that
+/// the API reads them from the header. Configured values apply to headerless audio only.
+pub fn entry_recognition_config(
+    entry: &BatchEntry,
+    audio: Option<&[u8]>,
+    config: &RecognitionConfig,
+) -> RecognitionConfig {
+    let mut recognition_config = config.clone();
+    if let Some(language_code) = &entry.language_code {
+        recognition_config.language_code = language_code.clone();
+    }
+
+    match audio {
+        Some(audio) => {
+            if let Ok((format, _)) = parse_wav(audio) {
+                recognition_config.encoding = match (format.audio_format, format.bits_per_sample) {
+                    (1, 16) => AudioEncoding::Linear16,
+                    (7, 8) => AudioEncoding::Mulaw,
+                    _ => AudioEncoding::EncodingUnspecified,
+                } as i32;
+                recognition_config.sample_rate_hertz = format.sample_rate as i32;
+                recognition_config.audio_channel_count = format.channels as i32;
+            } else if audio.starts_with(b"fLaC") {
+                recognition_config.encoding = AudioEncoding::Flac as i32;
+                recognition_config.sample_rate_hertz = 0;
+            }
+        }
+        None => {
+            let uri = entry.uri.to_lowercase();
+            if uri.ends_with(".wav") || uri.ends_with(".flac") {
+                recognition_config.encoding = AudioEncoding::EncodingUnspecified as i32;
+                recognition_config.sample_rate_hertz = 0;
+            }
+        }
+    }
+    recognition_config
+}
+
+/// Limits number of started requests per minute.
+struct SubmitThrottle {
+    interval: Duration,
+    next: AsyncMutex<Instant>,
+}
+
+impl SubmitThrottle {
+    fn new(requests_per_minute: u32) -> Self {
+        SubmitThrottle {
+            interval: Duration::from_secs(60) / requests_per_minute.max(1),
+            next: AsyncMutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        if *next > now {
+            sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.interval;
+    }
+}
+
+/// Shared state of batch workers.
+struct BatchProgress {
+    state: BatchState,
+    output: File,
+    summary: BatchSummary,
+}
+
+/// Batch transcriber.
+pub struct BatchTranscriber {
+    google_credentials: String,
+    config: BatchConfig,
+}
+
+impl BatchTranscriber {
+    pub fn new(google_credentials: impl Into<String>, config: BatchConfig) -> Self {
+        BatchTranscriber {
+            google_credentials: google_credentials.into(),
+            config,
+        }
+    }
+
+    /// Transcribes all entries of the manifest which were not completed by previous run
+    /// (as recorded in state file). Failure of single entry does not stop the batch,
+    /// it is written into output with error attribute set.
+    /// Call options are applied to all recognition calls of the batch.
+    pub async fn run(
+        &self,
+        manifest: BatchManifest,
+        options: Option<CallOptions>,
+    ) -> Result<BatchSummary> {
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut state = match &self.config.state_file {
+            Some(state_file) => BatchState::load(state_file)?,
+            None => BatchState::default(),
+        };
+
+        let mut output = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.config.output_file)?;
+        // results written by previous run after its last checkpoint
+        state.merge_output(&mut output, max_attempts)?;
+        terminate_last_line(&mut output)?;
+        if self.config.retry_failed {
+            state.failed_attempts.clear();
+        }
+
+        let mut summary = BatchSummary {
+            total: manifest.entries.len(),
+            ..Default::default()
+        };
+        let mut queue = VecDeque::new();
+        for entry in manifest.entries {
+            let exhausted = state
+                .failed_attempts
+                .get(&entry.id)
+                .is_some_and(|attempts| *attempts >= max_attempts);
+            if state.completed.contains(&entry.id) || exhausted {
+                summary.skipped += 1;
+            } else {
+                queue.push_back(entry);
+            }
+        }
+        info!(
+            "BatchTranscriber.run: {} entries to process, {} already completed",
+            queue.len(),
+            summary.skipped
+        );
+
+        let progress = Arc::new(Mutex::new(BatchProgress {
+            state,
+            output,
+            summary,
+        }));
+        let queue = Arc::new(Mutex::new(queue));
+        let submit_throttle = Arc::new(self.config.requests_per_minute.map(SubmitThrottle::new));
+
+        // client config of the current scope is applied to recognizers of the workers
+        let client_config = ClientConfig::current().unwrap_or_default();
+        let options = options.or_else(CallOptions::current);
+        let mut workers = vec![];
+        for _ in 0..self.config.concurrency.max(1) {
+            let client_config = client_config.clone();
+            let options = options.clone();
+            let google_credentials = self.google_credentials.clone();
+            let config = self.config.clone();
+            let queue = queue.clone();
+            let progress = progress.clone();
+            let submit_throttle = submit_throttle.clone();
+            workers.push(tokio::spawn(async move {
+                let mut recognizer = Recognizer::create_asynchronous_recognizer_with_config(
+                    google_credentials,
+                    client_config,
+                )
+                .await?;
+                loop {
+                    let entry = match queue.lock().unwrap().pop_front() {
+                        Some(entry) => entry,
+                        None => break,
+                    };
+                    if let Some(submit_throttle) = submit_throttle.as_ref() {
+                        submit_throttle.acquire().await;
+                    }
+                    let result =
+                        transcribe_entry(&mut recognizer, &entry, &config, options.clone()).await;
+                    if record_result(&progress, &config, result)? {
+                        queue.lock().unwrap().push_back(entry);
+                    }
+                }
+                Ok::<(), Error>(())
+            }));
+        }
+
+        for worker in workers {
+            worker.await??;
+        }
+
+        let summary = progress.lock().unwrap().summary.clone();
+        Ok(summary)
+    }
+}
+
+/// Appends newline to the output if its last line is incomplete (interrupted write)
+/// so that next result starts on new line.
+fn terminate_last_line(output: &mut File) -> Result<()> {
+    let len = output.seek(SeekFrom::End(0))?;
+    if len > 0 {
+        output.seek(SeekFrom::Start(len - 1))?;
+        let mut last = [0u8; 1];
+        output.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            writeln!(output)?;
+        }
+    }
+    Ok(())
+}
+
+/// Updates summary and checkpoints state, writes successful results and final failures
+/// into output. Output is written before the state is saved, results written
+/// after the last checkpoint are merged into the state when the batch is resumed
+/// (see BatchState::merge_output). Returns true if failed entry should be retried.
+fn record_result(
+    progress: &Mutex<BatchProgress>,
+    config: &BatchConfig,
+    result: BatchResult,
+) -> Result<bool> {
+    let mut progress = progress.lock().unwrap();
+    let mut retry = false;
+
+    if let Some(error) = &result.error {
+        let attempts = progress
+            .state
+            .failed_attempts
+            .entry(result.id.clone())
+            .or_insert(0);
+        *attempts += 1;
+        let attempts = *attempts;
+        if attempts < config.max_attempts.max(1) {
+            warn!(
+                "BatchTranscriber: {} failed (attempt {}), will be retried: {}",
+                result.id, attempts, error
+            );
+            progress.summary.retried += 1;
+            retry = true;
+        } else {
+            warn!(
+                "BatchTranscriber: {} failed (attempt {}): {}",
+                result.id, attempts, error
+            );
+            progress.summary.failed += 1;
+            write_result(&mut progress.output, &result)?;
+        }
+    } else {
+        trace!("BatchTranscriber: {} completed", result.id);
+        progress.summary.succeeded += 1;
+        write_result(&mut progress.output, &result)?;
+        progress.state.failed_attempts.remove(&result.id);
+        progress.state.completed.insert(result.id);
+    }
+
+    if let Some(state_file) = &config.state_file {
+        progress.state.save(state_file)?;
+    }
+    Ok(retry)
+}
+
+fn write_result(output: &mut File, result: &BatchResult) -> Result<()> {
+    let line = serde_json::to_string(result)?;
+    writeln!(output, "{}", line)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Transcribes single entry. Errors are converted into BatchResult with error attribute.
+async fn transcribe_entry(
+    recognizer: &mut Recognizer,
+    entry: &BatchEntry,
+    config: &BatchConfig,
+    options: Option<CallOptions>,
+) -> BatchResult {
+    let audio = if entry.is_gcs() {
+        None
+    } else {
+        match fs::read(&entry.uri) {
+            Ok(audio) => Some(audio),
+            Err(err) => return BatchResult::from_error(entry, None, err.into()),
+        }
+    };
+    let method = choose_method(entry, audio.as_deref(), config);
+    let recognition_config =
+        entry_recognition_config(entry, audio.as_deref(), &config.recognition_config);
+
+    if let Some(audio) = &audio {
+        if audio.len() > MAX_INLINE_AUDIO_BYTES {
+            return BatchResult::from_error(
+                entry,
+                Some(method),
+                Error::new(format!(
+                    "audio file exceeds {} bytes, upload it to Google Cloud Storage and use gs:// URI",
+                    MAX_INLINE_AUDIO_BYTES
+                )),
+            );
+        }
+    }
+
+    let recognition_audio = RecognitionAudio {
+        audio_source: Some(match audio {
+            Some(audio) => AudioSource::Content(audio),
+            None => AudioSource::Uri(entry.uri.clone()),
+        }),
+    };
+
+    let results = match method {
+        RecognitionMethod::Sync => recognizer
+            .recognize(
+                RecognizeRequest {
+                    config: Some(recognition_config),
+                    audio: Some(recognition_audio),
+                },
+                options,
+            )
+            .await
+            .map(|response| response.results),
+        RecognitionMethod::LongRunning => {
+            long_running_recognize(
+                recognizer,
+                LongRunningRecognizeRequest {
+                    config: Some(recognition_config),
+                    audio: Some(recognition_audio),
+                },
+                config.check_interval_ms,
+                options,
+            )
+            .await
+        }
+    };
+
+    match results {
+        Ok(results) => BatchResult::from_results(entry, method, results),
+        Err(err) => BatchResult::from_error(entry, Some(method), err),
+    }
+}
+
+async fn long_running_recognize(
+    recognizer: &mut Recognizer,
+    request: LongRunningRecognizeRequest,
+    check_interval_ms: Option<u64>,
+    options: Option<CallOptions>,
+) -> Result<Vec<SpeechRecognitionResult>> {
+    let operation = recognizer
+        .long_running_recognize(request, options.clone())
+        .await?;
+    let response = recognizer
+        .long_running_wait(operation.into_inner(), check_interval_ms, options)
+        .await?;
+    Ok(response
+        .map(|response| response.results)
+        .unwrap_or_default())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_batch_manifest
+    #[test]
+    fn test_batch_manifest() {
+        let manifest = r#"
+            # recordings of march
+            /data/call1.wav
+            gs://bucket/call2.flac
+            {"id": "call3", "uri": "/data/call3.wav", "languageCode": "de-DE"}
+        "#;
+        let manifest = BatchManifest::from_manifest_str(manifest).unwrap();
+        assert_eq!(manifest.entries.len(), 3);
+        assert_eq!(manifest.entries[0], BatchEntry::new("/data/call1.wav"));
+        assert!(manifest.entries[1].is_gcs());
+        assert_eq!(manifest.entries[2].id, "call3");
+        assert_eq!(manifest.entries[2].language_code, Some("de-DE".to_string()));
+
+        assert!(BatchManifest::from_manifest_str("{\"id\": \"x\"}").is_err());
+    }
+
+    // cargo test -- --show-output test_batch_choose_method
+    #[test]
+    fn test_batch_choose_method() {
+        let config = BatchConfig::new(
+            RecognitionConfig {
+                encoding: AudioEncoding::Linear16 as i32,
+                sample_rate_hertz: 8000,
+                audio_channel_count: 1,
+                ..Default::default()
+            },
+            "/tmp/out.jsonl",
+        );
+        let entry = BatchEntry::new("/data/call.raw");
+
+        // 30 seconds of 8kHz LINEAR16
+        let short = vec![0u8; 8000 * 2 * 30];
+        assert_eq!(
+            audio_duration(&short, &config.recognition_config),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            choose_method(&entry, Some(&short), &config),
+            RecognitionMethod::Sync
+        );
+
+        // 2 minutes of 8kHz LINEAR16
+        let long = vec![0u8; 8000 * 2 * 120];
+        assert_eq!(
+            choose_method(&entry, Some(&long), &config),
+            RecognitionMethod::LongRunning
+        );
+
+        let gcs_entry = BatchEntry::new("gs://bucket/call.wav");
+        assert_eq!(
+            choose_method(&gcs_entry, None, &config),
+            RecognitionMethod::LongRunning
+        );
+    }
+
+    // cargo test -- --show-output test_batch_resume
+    #[test]
+    fn test_batch_resume() {
+        let dir = std::env::temp_dir().join(format!("gcapi_batch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut config = BatchConfig::new(RecognitionConfig::default(), dir.join("out.jsonl"));
+        config.state_file = Some(dir.join("state.json"));
+        config.max_attempts = 2;
+
+        // previous run wrote result of call1 but was interrupted before checkpoint,
+        // call2 exhausted all attempts, last line was truncated
+        let entry = |id: &str| BatchEntry::new(id);
+        let previous = [
+            BatchResult::from_results(&entry("call1"), RecognitionMethod::Sync, vec![]),
+            BatchResult::from_error(&entry("call2"), None, Error::new("not found".to_string())),
+        ];
+        let mut output = previous
+            .iter()
+            .map(|result| serde_json::to_string(result).unwrap() + "\n")
+            .collect::<String>();
+        output.push_str("{\"id\": \"call3\"");
+        fs::write(&config.output_file, output).unwrap();
+
+        let mut state = BatchState::default();
+        state
+            .merge_output(File::open(&config.output_file).unwrap(), 2)
+            .unwrap();
+        assert!(state.completed.contains("call1"));
+        assert_eq!(state.failed_attempts.get("call2"), Some(&2));
+
+        let mut output = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&config.output_file)
+            .unwrap();
+        terminate_last_line(&mut output).unwrap();
+
+        // first failure of call3 is retried and not written, second one is final
+        let progress = Mutex::new(BatchProgress {
+            state,
+            output,
+            summary: BatchSummary::default(),
+        });
+        let failure =
+            BatchResult::from_error(&entry("call3"), None, Error::new("timeout".to_string()));
+        assert!(record_result(&progress, &config, failure.clone()).unwrap());
+        assert!(!record_result(&progress, &config, failure).unwrap());
+
+        let summary = progress.lock().unwrap().summary.clone();
+        assert_eq!((summary.retried, summary.failed), (1, 1));
+        let state = BatchState::load(config.state_file.as_ref().unwrap()).unwrap();
+        assert_eq!(state.failed_attempts.get("call3"), Some(&2));
+
+        let output = fs::read_to_string(&config.output_file).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[3].contains("\"call3\"") && lines[3].contains("timeout"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // cargo test -- --show-output test_batch_entry_recognition_config
+    #[test]
+    fn test_batch_entry_recognition_config() {
+        let config = RecognitionConfig {
+            encoding: AudioEncoding::Mulaw as i32,
+            sample_rate_hertz: 8000,
+            audio_channel_count: 1,
+            language_code: "en-US".to_string(),
+            ..Default::default()
+        };
+
+        // stereo 16kHz LINEAR16 WAV
+        let mut wav = vec![];
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&40u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&64000u32.to_le_bytes());
+        wav.extend_from_slice(&4u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&4u32.to_le_bytes());
+        wav.extend_from_slice(&[0, 0, 0, 0]);
+        let mut entry = BatchEntry::new("/data/call.wav");
+        entry.language_code = Some("de-DE".to_string());
+        let wav_config = entry_recognition_config(&entry, Some(&wav), &config);
+        assert_eq!(wav_config.encoding, AudioEncoding::Linear16 as i32);
+        assert_eq!(wav_config.sample_rate_hertz, 16000);
+        assert_eq!(wav_config.audio_channel_count, 2);
+        assert_eq!(wav_config.language_code, "de-DE");
+
+        let flac_config =
+            entry_recognition_config(&BatchEntry::new("/data/call.flac"), Some(b"fLaC"), &config);
+        assert_eq!(flac_config.encoding, AudioEncoding::Flac as i32);
+        assert_eq!(flac_config.sample_rate_hertz, 0);
+
+        let gcs_config =
+            entry_recognition_config(&BatchEntry::new("gs://bucket/call.WAV"), None, &config);
+        assert_eq!(
+            gcs_config.encoding,
+            AudioEncoding::EncodingUnspecified as i32
+        );
+        assert_eq!(gcs_config.sample_rate_hertz, 0);
+
+        // headerless audio uses configured values
+        let raw_entry = BatchEntry::new("/data/call.ulaw");
+        assert_eq!(
+            entry_recognition_config(&raw_entry, Some(&[0u8; 8]), &config),
+            config
+        );
+    }
+}
@@ -0,0 +1,612 @@
+//! Multi-channel (e.g. stereo call recording with agent on the left channel
+//! and customer on the right channel) speech recognition. Each channel is recognized
+//! separately, results are merged into single channel-labeled transcript
+//! ordered by word time offsets.
+//!
+//! Two strategies are supported: SeparateRecognitionPerChannel sends interleaved
+//! audio as is and lets the API recognize channels separately
+//! (enable_separate_recognition_per_channel), SplitChannels splits audio into
+//! mono streams recognized in parallel by separate recognizers.
+//!
+//! Inline audio is limited to 10 MB per request (MAX_INLINE_AUDIO_BYTES). Longer recordings
+//! must be uploaded to Google Cloud Storage and recognized by recognize_multi_channel_uri.
+use crate::api::grpc::google::cloud::speechtotext::v1::{
+    recognition_audio::AudioSource, recognition_config::AudioEncoding, LongRunningRecognizeRequest,
+    RecognitionAudio, RecognitionConfig, SpeechRecognitionResult,
+};
+use crate::audio::wav::{is_wav, parse_wav};
+use crate::audio::{linear16_from_bytes, linear16_to_bytes, split_channels};
+use crate::call_options::CallOptions;
+use crate::errors::{Error, Result};
+use crate::speechtotext::recognizer::Recognizer;
+use crate::speechtotext::MAX_INLINE_AUDIO_BYTES;
+use crate::transport::ClientConfig;
+use std::time::Duration;
+
+/// How multi-channel audio is recognized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiChannelStrategy {
+    /// Single recognition request with enable_separate_recognition_per_channel set.
+    SeparateRecognitionPerChannel,
+
+    /// Audio is split into mono channels recognized in parallel.
+    SplitChannels,
+}
+
+/// Recognized word with channel information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelWord {
+    /// Channel number starting from 1 (same as channel_tag of recognition result).
+    pub channel: i32,
+    pub label: String,
+    pub word: String,
+    pub start_time: Duration,
+    pub end_time: Duration,
+}
+
+/// Single recognition result (utterance) with channel information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelUtterance {
+    /// Channel number starting from 1 (same as channel_tag of recognition result).
+    pub channel: i32,
+    pub label: String,
+    pub transcript: String,
+    pub confidence: f32,
+    pub start_time: Duration,
+    pub end_time: Duration,
+}
+
+/// Merged transcript of all channels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MultiChannelTranscript {
+    /// Utterances of all channels ordered by start time.
+    pub utterances: Vec<ChannelUtterance>,
+
+    /// Words of all channels ordered by start time.
+    pub words: Vec<ChannelWord>,
+}
+
+impl MultiChannelTranscript {
+    /// Returns transcript as text, one utterance per line prefixed with channel label.
+    pub fn to_text(&self) -> String {
+        self.utterances
+            .iter()
+            .map(|utterance| format!("{}: {}", utterance.label, utterance.transcript.trim()))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+fn to_duration(duration: &Option<prost_types::Duration>) -> Duration {
+    match duration {
+        Some(duration) if duration.seconds >= 0 && duration.nanos >= 0 => {
+            Duration::new(duration.seconds as u64, duration.nanos as u32)
+        }
+        _ => Duration::from_secs(0),
+    }
+}
+
+/// Returns label of the channel (1-based). Falls back to "channel N" if label is not provided.
+fn channel_label(labels: &[String], channel: i32) -> String {
+    labels
+        .get((channel - 1).max(0) as usize)
+        .cloned()
+        .unwrap_or_else(|| format!("channel {}", channel))
+}
+
+/// Merges recognition results of all channels into single transcript. Results
+/// are provided together with channel number, if channel number is 0 channel_tag
+/// of the result is used. Only the first (most probable) alternative is used.
+pub fn merge_channel_results(
+    results: Vec<(i32, Vec<SpeechRecognitionResult>)>,
+    labels: &[String],
+) -> MultiChannelTranscript {
+    let mut transcript = MultiChannelTranscript::default();
+
+    for (channel, channel_results) in results {
+        for result in channel_results {
+            let channel = if channel > 0 {
+                channel
+            } else {
+                result.channel_tag.max(1)
+            };
+            let label = channel_label(labels, channel);
+            let alternative = match result.alternatives.into_iter().next() {
+                Some(alternative) => alternative,
+                None => continue,
+            };
+
+            let words: Vec<ChannelWord> = alternative
+                .words
+                .iter()
+                .map(|word| ChannelWord {
+                    channel,
+                    label: label.clone(),
+                    word: word.word.clone(),
+                    start_time: to_duration(&word.start_time),
+                    end_time: to_duration(&word.end_time),
+                })
+                .collect();
+
+            if alternative.transcript.trim().is_empty() {
+                continue;
+            }
+
+            transcript.utterances.push(ChannelUtterance {
+                channel,
+                label,
+                transcript: alternative.transcript,
+                confidence: alternative.confidence,
+                start_time: words.first().map(|w| w.start_time).unwrap_or_default(),
+                end_time: words.last().map(|w| w.end_time).unwrap_or_default(),
+            });
+            transcript.words.extend(words);
+        }
+    }
+
+    // stable sort keeps original order of results of the same channel with equal offsets
+    transcript
+        .utterances
+        .sort_by_key(|utterance| (utterance.start_time, utterance.channel));
+    transcript
+        .words
+        .sort_by_key(|word| (word.start_time, word.channel));
+    transcript
+}
+
+/// Recognizes multi-channel LINEAR16 audio. Audio can be WAV file (number of channels
+/// and sample rate are then taken from WAV header) or raw interleaved LINEAR16 audio
+/// (encoding, audio_channel_count and sample_rate_hertz of config must be set). Channel labels
+/// are assigned to channels in order, e.g. vec!["agent", "customer"] for stereo call recording.
+/// Long running recognition is used so that recordings longer than one minute are supported,
+/// audio is sent inline and each request must not exceed MAX_INLINE_AUDIO_BYTES
+/// (use recognize_multi_channel_uri for larger recordings).
+pub async fn recognize_multi_channel(
+    google_credentials: impl AsRef<str>,
+    audio: &[u8],
+    config: RecognitionConfig,
+    channel_labels: Vec<String>,
+    strategy: MultiChannelStrategy,
+    check_interval_ms: Option<u64>,
+    options: Option<CallOptions>,
+) -> Result<MultiChannelTranscript> {
+    let mut config = config;
+    let audio = if is_wav(audio) {
+        let (format, data) = parse_wav(audio)?;
+        if format.audio_format != 1 || format.bits_per_sample != 16 {
+            return Err(Error::new(
+                "recognize_multi_channel: only LINEAR16 WAV audio is supported".to_string(),
+            ));
+        }
+        // header is stripped, API cannot detect the format anymore
+        config.encoding = AudioEncoding::Linear16 as i32;
+        config.audio_channel_count = format.channels as i32;
+        config.sample_rate_hertz = format.sample_rate as i32;
+        data
+    } else {
+        audio
+    };
+
+    if config.audio_channel_count < 1 {
+        return Err(Error::new(
+            "recognize_multi_channel: audio_channel_count must be specified for raw audio"
+                .to_string(),
+        ));
+    }
+    // word offsets are needed to merge the channels
+    config.enable_word_time_offsets = true;
+
+    let request_bytes = match strategy {
+        MultiChannelStrategy::SeparateRecognitionPerChannel => audio.len(),
+        MultiChannelStrategy::SplitChannels => audio.len() / config.audio_channel_count as usize,
+    };
+    if request_bytes > MAX_INLINE_AUDIO_BYTES {
+        return Err(Error::new(format!(
+            "recognize_multi_channel: audio of {} bytes exceeds inline limit of {} bytes, upload the audio to Google Cloud Storage and use recognize_multi_channel_uri",
+            request_bytes, MAX_INLINE_AUDIO_BYTES
+        )));
+    }
+
+    match strategy {
+        MultiChannelStrategy::SeparateRecognitionPerChannel => {
+            config.enable_separate_recognition_per_channel = true;
+            let results = long_running_recognize(
+                google_credentials.as_ref().to_string(),
+                config,
+                AudioSource::Content(audio.to_vec()),
+                check_interval_ms,
+                options,
+                ClientConfig::current().unwrap_or_default(),
+            )
+            .await?;
+            Ok(merge_channel_results(vec![(0, results)], &channel_labels))
+        }
+        MultiChannelStrategy::SplitChannels => {
+            if config.encoding != AudioEncoding::Linear16 as i32 {
+                return Err(Error::new(
+                    "recognize_multi_channel: only LINEAR16 audio can be split into channels"
+                        .to_string(),
+                ));
+            }
+            let channels = split_channels(
+                &linear16_from_bytes(audio),
+                config.audio_channel_count as usize,
+            );
+            config.audio_channel_count = 1;
+            config.enable_separate_recognition_per_channel = false;
+
+            // scope of the caller is not inherited by spawned tasks
+            let options = options.or_else(CallOptions::current);
+            let client_config = ClientConfig::current().unwrap_or_default();
+            let mut handles = vec![];
+            for (idx, samples) in channels.into_iter().enumerate() {
+                let google_credentials = google_credentials.as_ref().to_string();
+                let config = config.clone();
+                let options = options.clone();
+                let client_config = client_config.clone();
+                handles.push((
+                    idx as i32 + 1,
+                    tokio::spawn(long_running_recognize(
+                        google_credentials,
+                        config,
+                        AudioSource::Content(linear16_to_bytes(&samples)),
+                        check_interval_ms,
+                        options,
+                        client_config,
+                    )),
+                ));
+            }
+
+            let mut results = vec![];
+            for (channel, handle) in handles {
+                results.push((channel, handle.await??));
+            }
+            Ok(merge_channel_results(results, &channel_labels))
+        }
+    }
+}
+
+/// Recognizes multi-channel audio stored in Google Cloud Storage (uri in format
+/// gs://bucket/object). Channels are recognized separately by the API
+/// (SeparateRecognitionPerChannel strategy), audio_channel_count and sample_rate_hertz
+/// of config must be set unless the audio is WAV/FLAC file with header.
+pub async fn recognize_multi_channel_uri(
+    google_credentials: impl AsRef<str>,
+    uri: impl Into<String>,
+    config: RecognitionConfig,
+    channel_labels: Vec<String>,
+    check_interval_ms: Option<u64>,
+    options: Option<CallOptions>,
+) -> Result<MultiChannelTranscript> {
+    let uri = uri.into();
+    if !uri.starts_with("gs://") {
+        return Err(Error::new(format!(
+            "recognize_multi_channel_uri: invalid uri {}, expected gs://bucket/object",
+            uri
+        )));
+    }
+    let mut config = config;
+    config.enable_word_time_offsets = true;
+    config.enable_separate_recognition_per_channel = true;
+    let results = long_running_recognize(
+        google_credentials.as_ref().to_string(),
+        config,
+        AudioSource::Uri(uri),
+        check_interval_ms,
+        options,
+        ClientConfig::current().unwrap_or_default(),
+    )
+    .await?;
+    Ok(merge_channel_results(vec![(0, results)], &channel_labels))
+}
+
+async fn long_running_recognize(
+    google_credentials: String,
+    config: RecognitionConfig,
+    audio_source: AudioSource,
+    check_interval_ms: Option<u64>,
+    options: Option<CallOptions>,
+    client_config: ClientConfig,
+) -> Result<Vec<SpeechRecognitionResult>> {
+    let mut recognizer =
+        Recognizer::create_asynchronous_recognizer_with_config(google_credentials, client_config)
+            .await?;
+    let request = LongRunningRecognizeRequest {
+        config: Some(config),
+        audio: Some(RecognitionAudio {
+            audio_source: Some(audio_source),
+        }),
+    };
+    let operation = recognizer
+        .long_running_recognize(request, options.clone())
+        .await?;
+    let response = recognizer
+        .long_running_wait(operation.into_inner(), check_interval_ms, options)
+        .await?;
+    Ok(response
+        .map(|response| response.results)
+        .unwrap_or_default())
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::api::grpc::google::cloud::speechtotext::v1::{
+        LongRunningRecognizeResponse, SpeechRecognitionAlternative, WordInfo,
+    };
+    #[allow(unused_imports)]
+    use crate::api::grpc::google::longrunning::{operation::Result as OperationResult, Operation};
+    #[allow(unused_imports)]
+    use crate::recording::{RecordedCall, RecordedRequest, RecordedResponse, Recording, Replayer};
+    #[allow(unused_imports)]
+    use prost::Message;
+    #[allow(unused_imports)]
+    use std::collections::BTreeMap;
+
+    #[allow(dead_code)]
+    fn result(channel_tag: i32, words: &[(&str, i64)]) -> SpeechRecognitionResult {
+        SpeechRecognitionResult {
+            alternatives: vec![SpeechRecognitionAlternative {
+                transcript: words
+                    .iter()
+                    .map(|(word, _)| *word)
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+                confidence: 0.9,
+                words: words
+                    .iter()
+                    .map(|(word, start_ms)| WordInfo {
+                        start_time: Some(prost_types::Duration {
+                            seconds: start_ms / 1000,
+                            nanos: (start_ms % 1000) as i32 * 1_000_000,
+                        }),
+                        end_time: Some(prost_types::Duration {
+                            seconds: (start_ms + 300) / 1000,
+                            nanos: ((start_ms + 300) % 1000) as i32 * 1_000_000,
+                        }),
+                        word: word.to_string(),
+                        speaker_tag: 0,
+                    })
+                    .collect(),
+            }],
+            channel_tag,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn grpc_frame(message: impl Message) -> Vec<u8> {
+        let message = message.encode_to_vec();
+        let mut frame = vec![0u8];
+        frame.extend((message.len() as u32).to_be_bytes());
+        frame.extend(message);
+        frame
+    }
+
+    /// Stereo WAV (8 kHz, LINEAR16) with interleaved samples.
+    #[allow(dead_code)]
+    fn stereo_wav(samples: &[i16]) -> Vec<u8> {
+        let data = linear16_to_bytes(samples);
+        let mut wav = vec![];
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&32000u32.to_le_bytes());
+        wav.extend_from_slice(&4u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+        wav
+    }
+
+    /// Recording of long running recognitions expecting given requests,
+    /// each recognition is finished immediately with single result.
+    #[allow(dead_code)]
+    fn recognize_recording(requests: Vec<LongRunningRecognizeRequest>) -> Recording {
+        let response = LongRunningRecognizeResponse {
+            results: vec![result(0, &[("hello", 100)])],
+            ..Default::default()
+        };
+        let operation = Operation {
+            name: "operation".to_string(),
+            metadata: None,
+            done: true,
+            result: Some(OperationResult::Response(prost_types::Any {
+                type_url: "type.googleapis.com/google.cloud.speech.v1.LongRunningRecognizeResponse"
+                    .to_string(),
+                value: response.encode_to_vec(),
+            })),
+        };
+        let calls = requests
+            .into_iter()
+            .map(|request| {
+                let request = grpc_frame(request);
+                let request_bytes = request.len() as u64;
+                RecordedCall {
+                    path: "/google.cloud.speech.v1.Speech/LongRunningRecognize".to_string(),
+                    requests: vec![RecordedRequest {
+                        offset_ms: 0,
+                        data: base64::encode(request),
+                    }],
+                    responses: vec![RecordedResponse {
+                        offset_ms: 0,
+                        request_bytes,
+                        data: base64::encode(grpc_frame(operation.clone())),
+                    }],
+                    status: 0,
+                    trailers: BTreeMap::new(),
+                    finished_ms: 0,
+                    request_bytes,
+                }
+            })
+            .collect();
+        Recording { calls }
+    }
+
+    // cargo test -- --show-output test_merge_channel_results
+    #[test]
+    fn test_merge_channel_results() {
+        let labels = vec!["agent".to_string(), "customer".to_string()];
+
+        // separate recognition per channel, channel taken from channel_tag
+        let transcript = merge_channel_results(
+            vec![(
+                0,
+                vec![
+                    result(1, &[("hello", 0), ("how", 400), ("can", 700), ("I", 900)]),
+                    result(1, &[("sure", 4500)]),
+                    result(2, &[("hi", 2000), ("I", 2300), ("need", 2600)]),
+                ],
+            )],
+            &labels,
+        );
+        assert_eq!(
+            transcript.to_text(),
+            "agent: hello how can I\ncustomer: hi I need\nagent: sure"
+        );
+        assert_eq!(transcript.words.len(), 8);
+        assert_eq!(transcript.words[4].word, "hi");
+        assert_eq!(transcript.words[4].label, "customer");
+        assert_eq!(
+            transcript.utterances[1].end_time,
+            Duration::from_millis(2900)
+        );
+
+        // split channels, channel provided explicitly
+        let transcript = merge_channel_results(
+            vec![
+                (1, vec![result(0, &[("hello", 100)])]),
+                (2, vec![result(0, &[("hi", 50)])]),
+                (3, vec![result(0, &[("beep", 10)])]),
+            ],
+            &labels,
+        );
+        assert_eq!(
+            transcript.to_text(),
+            "channel 3: beep\ncustomer: hi\nagent: hello"
+        );
+    }
+
+    // cargo test -- --show-output test_multi_channel_inline_limit
+    #[tokio::test]
+    async fn test_multi_channel_inline_limit() {
+        let config = RecognitionConfig {
+            audio_channel_count: 2,
+            sample_rate_hertz: 8000,
+            ..Default::default()
+        };
+        let audio = vec![0u8; MAX_INLINE_AUDIO_BYTES + 4];
+        let err = recognize_multi_channel(
+            "",
+            &audio,
+            config.clone(),
+            vec![],
+            MultiChannelStrategy::SeparateRecognitionPerChannel,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.message.contains("recognize_multi_channel_uri"));
+
+        let err = recognize_multi_channel_uri("", "/tmp/call.wav", config, vec![], None, None)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("gs://"));
+    }
+
+    // cargo test -- --show-output test_multi_channel_request_encoding
+    #[tokio::test]
+    async fn test_multi_channel_request_encoding() {
+        // same audio in both channels, split channel requests are identical
+        let samples = [1i16, 1, 2, 2, 3, 3, 4, 4];
+        let wav = stereo_wav(&samples);
+        let labels = vec!["agent".to_string(), "customer".to_string()];
+        let expected_config = RecognitionConfig {
+            encoding: AudioEncoding::Linear16 as i32,
+            sample_rate_hertz: 8000,
+            audio_channel_count: 2,
+            enable_separate_recognition_per_channel: true,
+            enable_word_time_offsets: true,
+            ..Default::default()
+        };
+
+        // WAV header is stripped, encoding must be sent explicitly (strict replay
+        // fails the call if the request differs)
+        let request = LongRunningRecognizeRequest {
+            config: Some(expected_config.clone()),
+            audio: Some(RecognitionAudio {
+                audio_source: Some(AudioSource::Content(linear16_to_bytes(&samples))),
+            }),
+        };
+        let replayer = Replayer::new(recognize_recording(vec![request]));
+        let transcript = ClientConfig::new()
+            .replay(replayer.clone())
+            .scope(recognize_multi_channel(
+                "",
+                &wav,
+                RecognitionConfig::default(),
+                labels.clone(),
+                MultiChannelStrategy::SeparateRecognitionPerChannel,
+                Some(1),
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(transcript.to_text(), "agent: hello");
+        assert_eq!(replayer.remaining(), 0);
+
+        let request = LongRunningRecognizeRequest {
+            config: Some(RecognitionConfig {
+                audio_channel_count: 1,
+                enable_separate_recognition_per_channel: false,
+                ..expected_config
+            }),
+            audio: Some(RecognitionAudio {
+                audio_source: Some(AudioSource::Content(linear16_to_bytes(&[1, 2, 3, 4]))),
+            }),
+        };
+        let replayer = Replayer::new(recognize_recording(vec![request.clone(), request]));
+        let transcript = ClientConfig::new()
+            .replay(replayer.clone())
+            .scope(recognize_multi_channel(
+                "",
+                &wav,
+                RecognitionConfig::default(),
+                labels.clone(),
+                MultiChannelStrategy::SplitChannels,
+                Some(1),
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(transcript.to_text(), "agent: hello\ncustomer: hello");
+        assert_eq!(replayer.remaining(), 0);
+
+        // raw audio of other encodings cannot be split
+        let config = RecognitionConfig {
+            encoding: AudioEncoding::Mulaw as i32,
+            sample_rate_hertz: 8000,
+            audio_channel_count: 2,
+            ..Default::default()
+        };
+        let err = recognize_multi_channel(
+            "",
+            &[0u8; 16],
+            config,
+            labels,
+            MultiChannelStrategy::SplitChannels,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.message.contains("LINEAR16"));
+    }
+}
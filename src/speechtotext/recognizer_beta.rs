@@ -20,26 +20,33 @@ use crate::api::grpc::google::cloud::speechtotext::v1p1beta1::{
     LongRunningRecognizeRequest, LongRunningRecognizeResponse, RecognizeRequest, RecognizeResponse,
     StreamingRecognitionConfig, StreamingRecognizeRequest, StreamingRecognizeResponse,
 };
-use crate::api::grpc::google::longrunning::{
-    operation::Result as OperationResult, operations_client::OperationsClient, GetOperationRequest,
-    Operation,
+use crate::api::grpc::google::longrunning::Operation;
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
 };
-use crate::common::{get_token, new_grpc_channel, new_interceptor, TokenInterceptor};
 use crate::errors::{Error, Result};
+use crate::longrunning::OperationsClient;
+use crate::metering::{
+    duration_seconds, meter_recognition, PendingUsage, RecognitionAudioRequest, UsageMeter,
+    UsageMetric,
+};
+use crate::streaming::{
+    cancellable_request_stream, forward_responses, CancellationToken, StreamingOutcome,
+};
+use crate::telemetry::StreamingSession;
+use crate::transport::{ClientConfig, GrpcChannel};
 use async_stream::try_stream;
 use futures_core::stream::Stream;
 use log::*;
-use prost::Message;
-use std::io::Cursor;
+use std::collections::HashMap;
 use std::result::Result as StdResult;
-use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::sleep;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::codegen::InterceptedService;
 use tonic::Response as TonicResponse;
 use tonic::Status as TonicStatus;
-use tonic::{transport::Channel, Response as GrpcResponse, Streaming};
+use tonic::{Response as GrpcResponse, Streaming};
 
 const GRPC_API_DOMAIN: &str = "speech.googleapis.com";
 const GRPC_API_URL: &str = "https://speech.googleapis.com";
@@ -48,10 +55,10 @@ const GRPC_API_URL: &str = "https://speech.googleapis.com";
 #[derive(Debug)]
 pub struct Recognizer {
     /// internal GRPC speech client
-    speech_client: SpeechClient<InterceptedService<Channel, TokenInterceptor>>,
+    speech_client: SpeechClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
 
     /// internal GRPC google long running operations client
-    operations_client: Option<OperationsClient<InterceptedService<Channel, TokenInterceptor>>>,
+    operations_client: Option<OperationsClient>,
 
     /// channel for sending audio data
     audio_sender: Option<mpsc::Sender<StreamingRecognizeRequest>>,
@@ -63,6 +70,12 @@ pub struct Recognizer {
     /// where STT results will be sent. Library client is using respective
     /// receiver to get the results. See example recognizer_streaming for details
     result_sender: Option<mpsc::Sender<StreamingRecognizeResponse>>,
+
+    /// usage meter of billable units, see metering module
+    usage_meter: Option<UsageMeter>,
+
+    /// usage of long running operations (by operation name) recorded once operation succeeds
+    pending_usage: HashMap<String, PendingUsage>,
 }
 
 impl Recognizer {
@@ -78,9 +91,32 @@ impl Recognizer {
         // If not provided defaults to 1000.
         buffer_size: Option<usize>,
     ) -> Result<Self> {
-        let channel = new_grpc_channel(GRPC_API_DOMAIN, GRPC_API_URL, None).await?;
+        Recognizer::create_streaming_recognizer_with_config(
+            google_credentials,
+            config,
+            buffer_size,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
 
-        let token_header_val = get_token(google_credentials)?;
+    /// Same as create_streaming_recognizer with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_streaming_recognizer_with_config(
+        // Google Cloud Platform JSON credentials for project with Speech APIs enabled
+        google_credentials: impl AsRef<str>,
+        //  Streaming recognition configuration
+        config: StreamingRecognitionConfig,
+        // Capacity of audio sink (tokio channel used by caller to send audio data).
+        // If not provided defaults to 1000.
+        buffer_size: Option<usize>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
 
         let speech_client =
             SpeechClient::with_interceptor(channel, new_interceptor(token_header_val));
@@ -100,6 +136,8 @@ impl Recognizer {
             audio_sender: Some(audio_sender),
             audio_receiver: Some(audio_receiver),
             result_sender: None,
+            usage_meter: None,
+            pending_usage: HashMap::new(),
         })
     }
 
@@ -109,17 +147,31 @@ impl Recognizer {
     pub async fn create_asynchronous_recognizer(
         google_credentials: impl AsRef<str>,
     ) -> Result<Self> {
-        let channel = new_grpc_channel(GRPC_API_DOMAIN, GRPC_API_URL, None).await?;
+        Recognizer::create_asynchronous_recognizer_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
 
-        let token_header_val = get_token(google_credentials)?;
+    /// Same as create_asynchronous_recognizer with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_asynchronous_recognizer_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
+
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
 
         let speech_client = SpeechClient::with_interceptor(
             channel.clone(),
             new_interceptor(token_header_val.clone()),
         );
 
-        let operations_client =
-            OperationsClient::with_interceptor(channel, new_interceptor(token_header_val));
+        let operations_client = OperationsClient::new(channel, new_interceptor(token_header_val));
 
         Ok(Recognizer {
             speech_client,
@@ -127,6 +179,8 @@ impl Recognizer {
             audio_sender: None,
             audio_receiver: None,
             result_sender: None,
+            usage_meter: None,
+            pending_usage: HashMap::new(),
         })
     }
 
@@ -136,9 +190,24 @@ impl Recognizer {
     pub async fn create_synchronous_recognizer(
         google_credentials: impl AsRef<str>,
     ) -> Result<Self> {
-        let channel = new_grpc_channel(GRPC_API_DOMAIN, GRPC_API_URL, None).await?;
+        Recognizer::create_synchronous_recognizer_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create_synchronous_recognizer with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_synchronous_recognizer_with_config(
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(GRPC_API_DOMAIN, GRPC_API_URL, None, &client_config)
+                .await?;
 
-        let token_header_val = get_token(google_credentials)?;
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
 
         let speech_client =
             SpeechClient::with_interceptor(channel, new_interceptor(token_header_val));
@@ -149,6 +218,8 @@ impl Recognizer {
             audio_sender: None,
             audio_receiver: None,
             result_sender: None,
+            usage_meter: None,
+            pending_usage: HashMap::new(),
         })
     }
 
@@ -192,6 +263,12 @@ impl Recognizer {
         result_receiver
     }
 
+    /// Sets usage meter recording billable units consumed by this client
+    /// (see metering module).
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.usage_meter = Some(usage_meter);
+    }
+
     /// Convenience function so that client does not have to create full StreamingRecognizeRequest
     /// and can just pass audio bytes vector instead.
     pub fn streaming_request_from_bytes(audio_bytes: Vec<u8>) -> StreamingRecognizeRequest {
@@ -207,54 +284,179 @@ impl Recognizer {
     #[allow(unreachable_code)]
     pub async fn streaming_recognize_async_stream(
         &mut self,
+        options: Option<CallOptions>,
     ) -> impl Stream<Item = Result<StreamingRecognizeResponse>> + '_ {
         try_stream! {
                 // yank self.audio_receiver so that we can consume it
                 if let Some(audio_receiver) = self.audio_receiver.take() {
+                    let options = options.or_else(CallOptions::current);
+                    let session = StreamingSession::start("Recognizer.streaming_recognize_async_stream");
+                    session.meter_usage(self.usage_meter.as_ref(), UsageMetric::SpeechToTextSeconds, options.as_ref());
+                    let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
                     let streaming_recognize_result: StdResult<
                         TonicResponse<Streaming<StreamingRecognizeResponse>>,
                         TonicStatus,
-                    > = self.speech_client.streaming_recognize(ReceiverStream::new(audio_receiver)).await;
+                    > = session.instrument(self.speech_client.streaming_recognize(new_request(requests, options)?)).await;
 
                     let mut response_stream: Streaming<StreamingRecognizeResponse> =
-                        streaming_recognize_result?.into_inner();
+                        session.track(streaming_recognize_result)?.into_inner();
 
                     trace!("streaming_recognize: entering loop");
-                    while let Some(streaming_recognize_response) = response_stream.message().await? {
+                    while let Some(streaming_recognize_response) = session.track(response_stream.message().await)? {
+                        session.response_received(&streaming_recognize_response);
                         yield streaming_recognize_response;
                     }
                     trace!("streaming_recognize: leaving loop");
+                    session.finish(Ok(StreamingOutcome::Completed));
                 }
         }
     }
 
+    /// Consumes recognizer and initiates bidirectional streaming. Returns audio sink
+    /// and owned (Send + 'static) stream of streaming recognition results, i.e. stream can be
+    /// moved into spawned task or stored in a struct. Stream is closed once the audio sink
+    /// (and all its clones retrieved via get_audio_sink) is dropped and remaining results are received.
+    pub fn into_streaming_session(
+        mut self,
+        options: Option<CallOptions>,
+    ) -> Result<(
+        mpsc::Sender<StreamingRecognizeRequest>,
+        impl Stream<Item = Result<StreamingRecognizeResponse>> + Send + 'static,
+    )> {
+        let (audio_sender, audio_receiver) =
+            match (self.audio_sender.take(), self.audio_receiver.take()) {
+                (Some(audio_sender), Some(audio_receiver)) => (audio_sender, audio_receiver),
+                _ => {
+                    return Err(Error::new(
+                        "Recognizer.into_streaming_session: audio sink not available, use create_streaming_recognizer".to_string(),
+                    ))
+                }
+            };
+        let mut speech_client = self.speech_client;
+        let usage_meter = self.usage_meter;
+        let options = options.or_else(CallOptions::current);
+
+        let stream = try_stream! {
+            let session = StreamingSession::start("Recognizer.into_streaming_session");
+            session.meter_usage(usage_meter.as_ref(), UsageMetric::SpeechToTextSeconds, options.as_ref());
+            let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+            let streaming_recognize_result: StdResult<
+                TonicResponse<Streaming<StreamingRecognizeResponse>>,
+                TonicStatus,
+            > = session.instrument(speech_client.streaming_recognize(new_request(requests, options)?)).await;
+
+            let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                session.track(streaming_recognize_result)?.into_inner();
+
+            trace!("into_streaming_session: entering loop");
+            while let Some(streaming_recognize_response) = session.track(response_stream.message().await)? {
+                session.response_received(&streaming_recognize_response);
+                yield streaming_recognize_response;
+            }
+            trace!("into_streaming_session: leaving loop");
+            session.finish(Ok(StreamingOutcome::Completed));
+        };
+
+        Ok((audio_sender, stream))
+    }
+
     /// Initiates bidirectional streaming. This call should be spawned
     /// into separate tokio task. Results can be then retrieved via
     /// channel receiver returned by method get_streaming_result_receiver.
-    pub async fn streaming_recognize(&mut self) -> Result<()> {
+    pub async fn streaming_recognize(&mut self, options: Option<CallOptions>) -> Result<()> {
         // yank self.audio_receiver so that we can consume it
         if let Some(audio_receiver) = self.audio_receiver.take() {
-            let streaming_recognize_result: StdResult<
-                tonic::Response<Streaming<StreamingRecognizeResponse>>,
-                tonic::Status,
-            > = self
-                .speech_client
-                .streaming_recognize(ReceiverStream::new(audio_receiver))
-                .await;
+            let options = options.or_else(CallOptions::current);
+            let session = StreamingSession::start("Recognizer.streaming_recognize");
+            session.meter_usage(
+                self.usage_meter.as_ref(),
+                UsageMetric::SpeechToTextSeconds,
+                options.as_ref(),
+            );
+            let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+            let result = session
+                .instrument(async {
+                    let streaming_recognize_result: StdResult<
+                        tonic::Response<Streaming<StreamingRecognizeResponse>>,
+                        tonic::Status,
+                    > = self
+                        .speech_client
+                        .streaming_recognize(new_request(requests, options)?)
+                        .await;
 
-            let mut response_stream: Streaming<StreamingRecognizeResponse> =
-                streaming_recognize_result?.into_inner();
+                    let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                        streaming_recognize_result?.into_inner();
 
-            while let Some(streaming_recognize_response) = response_stream.message().await? {
-                if let Some(result_sender) = &self.result_sender {
-                    result_sender.send(streaming_recognize_response).await?;
-                }
-            }
+                    while let Some(streaming_recognize_response) = response_stream.message().await?
+                    {
+                        session.response_received(&streaming_recognize_response);
+                        if let Some(result_sender) = &self.result_sender {
+                            result_sender.send(streaming_recognize_response).await?;
+                        }
+                    }
+                    Ok(())
+                })
+                .await;
+            session.finish(result.as_ref().map(|_| StreamingOutcome::Completed));
+            result?;
         }
 
         Ok(())
     }
 
+    /// Initiates bidirectional streaming which can be aborted or gracefully shut down
+    /// via cancellation token. This call should be spawned into separate tokio task.
+    /// Results are sent into channel receiver returned by method get_streaming_result_receiver,
+    /// result channel is closed once this method returns. Returns Completed if the stream
+    /// was closed by the API, Cancelled or GracePeriodElapsed if it was aborted.
+    pub async fn streaming_recognize_cancellable(
+        &mut self,
+        token: CancellationToken,
+        options: Option<CallOptions>,
+    ) -> Result<StreamingOutcome> {
+        // yank self.audio_receiver so that we can consume it
+        if let Some(audio_receiver) = self.audio_receiver.take() {
+            let result_sender = self.result_sender.take();
+            let options = options.or_else(CallOptions::current);
+            let session = StreamingSession::start("Recognizer.streaming_recognize_cancellable");
+            session.meter_usage(
+                self.usage_meter.as_ref(),
+                UsageMetric::SpeechToTextSeconds,
+                options.as_ref(),
+            );
+            let requests =
+                session.meter_requests(cancellable_request_stream(audio_receiver, token.clone()));
+            let result = session
+                .instrument(async {
+                    let streaming_result: StdResult<
+                        tonic::Response<Streaming<StreamingRecognizeResponse>>,
+                        tonic::Status,
+                    > = self
+                        .speech_client
+                        .streaming_recognize(new_request(requests, options)?)
+                        .await;
+
+                    let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                        streaming_result?.into_inner();
+
+                    forward_responses(
+                        &mut response_stream,
+                        result_sender.as_ref(),
+                        &token,
+                        &session,
+                    )
+                    .await
+                })
+                .await;
+            session.finish(result.as_ref().copied());
+            result
+        } else {
+            Err(Error::new(
+                "Recognizer.streaming_recognize_cancellable: audio receiver not available, use create_streaming_recognizer".to_string(),
+            ))
+        }
+    }
+
     /// Initiates asynchronous recognition.
     /// Returns long running operation representing
     /// asynchronous computation performed by Google Cloud Platform.
@@ -262,8 +464,25 @@ impl Recognizer {
     pub async fn long_running_recognize(
         &mut self,
         request: LongRunningRecognizeRequest,
+        options: Option<CallOptions>,
     ) -> Result<GrpcResponse<Operation>> {
-        Ok(self.speech_client.long_running_recognize(request).await?)
+        let audio_seconds = request.audio_seconds();
+        let options = options.or_else(CallOptions::current);
+        let response = self
+            .speech_client
+            .long_running_recognize(new_request(request, options.clone())?)
+            .await?;
+        if let Some(meter) = &self.usage_meter {
+            let usage = PendingUsage::new(
+                meter,
+                UsageMetric::SpeechToTextSeconds,
+                audio_seconds,
+                options.as_ref(),
+            );
+            self.pending_usage
+                .insert(response.get_ref().name.clone(), usage);
+        }
+        Ok(response)
     }
 
     /// Waits for completion of long running operation returned
@@ -272,52 +491,59 @@ impl Recognizer {
     /// Function checks operation status regularly using get_operation
     /// which is called every check_interval_ms ms. If check_interval_ms
     /// is not specified default interval check is 1 sec.
+    /// Usage of the operation is metered once it succeeds.
     pub async fn long_running_wait(
         &mut self,
         operation: Operation,
         check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
     ) -> Result<Option<LongRunningRecognizeResponse>> {
-        let operation_req = GetOperationRequest {
-            name: operation.name.clone(),
-        };
-
-        loop {
-            if let Some(oper_client) = &mut self.operations_client {
-                let tonic_response: TonicResponse<Operation> =
-                    oper_client.get_operation(operation_req.clone()).await?;
-                let operation = tonic_response.into_inner();
-                if operation.done {
-                    return if let Some(operation_result) = operation.result {
-                        match operation_result {
-                            OperationResult::Error(rpc_status) => {
-                                error!("Recognizer.long_running_wait rpc error {:?}", rpc_status);
-                                Err(Error::new_with_code(
-                                    rpc_status.message,
-                                    rpc_status.code.to_string(),
-                                ))
-                            }
-                            OperationResult::Response(any_response) => {
-                                let lrr_response: LongRunningRecognizeResponse =
-                                    LongRunningRecognizeResponse::decode(&mut Cursor::new(
-                                        any_response.value,
-                                    ))?;
-                                Ok(Some(lrr_response))
-                            }
-                        }
-                    } else {
-                        Ok(None)
-                    };
-                } else {
-                    sleep(Duration::from_millis(check_interval_ms.unwrap_or(1000))).await;
+        if let Some(oper_client) = &mut self.operations_client {
+            let options = options.or_else(CallOptions::current);
+            let operation_name = operation.name.clone();
+            let result: Result<Option<LongRunningRecognizeResponse>> = oper_client
+                .long_running_wait(operation, check_interval_ms, options.clone())
+                .await;
+            // failed or cancelled operation is not billed
+            let usage = self.pending_usage.remove(&operation_name);
+            if let Ok(Some(response)) = &result {
+                let usage = usage.or_else(|| {
+                    let meter = self.usage_meter.as_ref()?;
+                    let metric = UsageMetric::SpeechToTextSeconds;
+                    Some(PendingUsage::new(meter, metric, None, options.as_ref()))
+                });
+                if let Some(usage) = usage {
+                    usage.record(response.total_billed_time.as_ref().map(duration_seconds));
                 }
             }
+            result
+        } else {
+            Err(Error::new(
+                "Recognizer.long_running_wait: operations client not available, use create_asynchronous_recognizer".to_string(),
+            ))
         }
     }
 
     /// Performs synchronous speech recognition.
-    pub async fn recognize(&mut self, request: RecognizeRequest) -> Result<RecognizeResponse> {
-        let tonic_response: TonicResponse<RecognizeResponse> =
-            self.speech_client.recognize(request).await?;
-        Ok(tonic_response.into_inner())
+    pub async fn recognize(
+        &mut self,
+        request: RecognizeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<RecognizeResponse> {
+        let audio_seconds = request.audio_seconds();
+        let options = options.or_else(CallOptions::current);
+        let tonic_response: TonicResponse<RecognizeResponse> = self
+            .speech_client
+            .recognize(new_request(request, options.clone())?)
+            .await?;
+        let response = tonic_response.into_inner();
+        let billed_seconds = response.total_billed_time.as_ref().map(duration_seconds);
+        meter_recognition(
+            self.usage_meter.as_ref(),
+            billed_seconds,
+            audio_seconds,
+            options.as_ref(),
+        );
+        Ok(response)
     }
 }
@@ -0,0 +1,753 @@
+//! Speech-to-text recognizer module - v2 STT API.
+//!
+//! Compared to v1 API, v2 API introduces Recognizer resources (named,
+//! persisted recognition configurations), batch recognition of multiple
+//! Cloud Storage files, new models (e.g. chirp) and location specific
+//! endpoints (e.g. europe-west4-speech.googleapis.com). Every recognition request
+//! references recognizer resource by its name, use `_` as recognizer id to use
+//! empty implicit recognizer, e.g. `projects/my-project/locations/global/recognizers/_`.
+#![allow(clippy::manual_map)]
+use crate::api::grpc::google::cloud::speechtotext::v2::{
+    speech_client::SpeechClient, streaming_recognize_request::StreamingRequest,
+    BatchRecognizeRequest, BatchRecognizeResponse, CreateRecognizerRequest,
+    DeleteRecognizerRequest, GetRecognizerRequest, ListRecognizersRequest, ListRecognizersResponse,
+    RecognizeRequest, RecognizeResponse, Recognizer as RecognizerResource,
+    StreamingRecognitionConfig, StreamingRecognizeRequest, StreamingRecognizeResponse,
+    UndeleteRecognizerRequest, UpdateRecognizerRequest,
+};
+use crate::api::grpc::google::longrunning::Operation;
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::errors::{Error, Result};
+use crate::longrunning::OperationsClient;
+use crate::metering::{duration_seconds, meter_recognition, UsageMeter, UsageMetric};
+use crate::streaming::{
+    cancellable_request_stream, forward_responses, CancellationToken, StreamingOutcome,
+};
+use crate::telemetry::StreamingSession;
+use crate::transport::{ClientConfig, GrpcChannel};
+use async_stream::try_stream;
+use futures_core::stream::Stream;
+use log::*;
+use std::result::Result as StdResult;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::codegen::InterceptedService;
+use tonic::Response as TonicResponse;
+use tonic::Status as TonicStatus;
+use tonic::Streaming;
+
+/// Location using global (not location specific) endpoint.
+pub const GLOBAL_LOCATION: &str = "global";
+
+const GRPC_API_DOMAIN: &str = "speech.googleapis.com";
+
+/// Returns domain name and channel URL of the API endpoint for given location,
+/// e.g. europe-west4-speech.googleapis.com & https://europe-west4-speech.googleapis.com.
+/// Global location (or empty location) uses speech.googleapis.com.
+pub fn get_api_endpoint(location: &str) -> (String, String) {
+    let domain = if location.is_empty() || location == GLOBAL_LOCATION {
+        GRPC_API_DOMAIN.to_string()
+    } else {
+        format!("{}-{}", location, GRPC_API_DOMAIN)
+    };
+    let url = format!("https://{}", domain);
+    (domain, url)
+}
+
+/// Returns parent (location) string in format projects/{project}/locations/{location}.
+pub fn get_location_string(project: &str, location: &str) -> String {
+    format!("projects/{}/locations/{}", project, location)
+}
+
+/// Returns recognizer resource name in format
+/// projects/{project}/locations/{location}/recognizers/{recognizer}.
+pub fn get_recognizer_string(project: &str, location: &str, recognizer: &str) -> String {
+    format!(
+        "projects/{}/locations/{}/recognizers/{}",
+        project, location, recognizer
+    )
+}
+
+/// Extracts location from resource name like projects/{project}/locations/{location}/...
+/// Returns None if resource name does not contain location.
+pub fn get_location_from_name(name: &str) -> Option<&str> {
+    let mut segments = name.split('/');
+    while let Some(segment) = segments.next() {
+        if segment == "locations" {
+            return segments.next().filter(|location| !location.is_empty());
+        }
+    }
+    None
+}
+
+/// Returns error if recognizer name is not in format
+/// projects/{project}/locations/{location}/recognizers/{recognizer}.
+pub fn validate_recognizer_name(name: &str) -> Result<()> {
+    let segments: Vec<&str> = name.split('/').collect();
+    if segments.len() == 6
+        && segments[0] == "projects"
+        && segments[2] == "locations"
+        && segments[4] == "recognizers"
+        && segments.iter().all(|segment| !segment.is_empty())
+    {
+        Ok(())
+    } else {
+        Err(Error::new(format!(
+            "invalid recognizer name {}, expected projects/{{project}}/locations/{{location}}/recognizers/{{recognizer}}",
+            name
+        )))
+    }
+}
+
+/// Google Speech API (v2) recognizer
+#[derive(Debug)]
+pub struct Recognizer {
+    /// internal GRPC speech client
+    speech_client: SpeechClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// internal GRPC google long running operations client
+    /// (batch recognition & recognizer resource management)
+    operations_client: OperationsClient,
+
+    /// channel for sending audio data
+    audio_sender: Option<mpsc::Sender<StreamingRecognizeRequest>>,
+
+    /// channel for streaming audio data into GRPC API
+    audio_receiver: Option<mpsc::Receiver<StreamingRecognizeRequest>>,
+
+    /// For channel based streaming this is the internal channel sender
+    /// where STT results will be sent. Library client is using respective
+    /// receiver to get the results.
+    result_sender: Option<mpsc::Sender<StreamingRecognizeResponse>>,
+
+    /// usage meter of billable units, see metering module
+    usage_meter: Option<UsageMeter>,
+}
+
+impl Recognizer {
+    /// Creates new speech recognizer connected to the endpoint of given location
+    /// (e.g. global, us-central1, europe-west4). Location of the endpoint must match the
+    /// location of recognizer resources used in the requests.
+    async fn create(
+        google_credentials: impl AsRef<str>,
+        location: &str,
+        client_config: &ClientConfig,
+    ) -> Result<Self> {
+        let (domain, url) = get_api_endpoint(location);
+        let channel = new_grpc_channel_with_config(&domain, &url, None, client_config).await?;
+
+        let token_header_val = get_token_with_config(google_credentials, client_config)?;
+
+        let speech_client = SpeechClient::with_interceptor(
+            channel.clone(),
+            new_interceptor(token_header_val.clone()),
+        );
+
+        let operations_client = OperationsClient::new(channel, new_interceptor(token_header_val));
+
+        Ok(Recognizer {
+            speech_client,
+            operations_client,
+            audio_sender: None,
+            audio_receiver: None,
+            result_sender: None,
+            usage_meter: None,
+        })
+    }
+
+    /// Creates new speech recognizer from provided Google credentials,
+    /// recognizer resource name and streaming configuration.
+    /// This kind of recognizer can be used for streaming recognition.
+    /// Location of the endpoint is taken from recognizer name.
+    pub async fn create_streaming_recognizer(
+        // Google Cloud Platform JSON credentials for project with Speech APIs enabled
+        google_credentials: impl AsRef<str>,
+        // Recognizer resource name, e.g. projects/my-project/locations/global/recognizers/_
+        recognizer: impl Into<String>,
+        //  Streaming recognition configuration
+        config: StreamingRecognitionConfig,
+        // Capacity of audio sink (tokio channel used by caller to send audio data).
+        // If not provided defaults to 1000.
+        buffer_size: Option<usize>,
+    ) -> Result<Self> {
+        Recognizer::create_streaming_recognizer_with_config(
+            google_credentials,
+            recognizer,
+            config,
+            buffer_size,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create_streaming_recognizer with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_streaming_recognizer_with_config(
+        // Google Cloud Platform JSON credentials for project with Speech APIs enabled
+        google_credentials: impl AsRef<str>,
+        // Recognizer resource name, e.g. projects/my-project/locations/global/recognizers/_
+        recognizer: impl Into<String>,
+        //  Streaming recognition configuration
+        config: StreamingRecognitionConfig,
+        // Capacity of audio sink (tokio channel used by caller to send audio data).
+        // If not provided defaults to 1000.
+        buffer_size: Option<usize>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let recognizer = recognizer.into();
+        let location = get_location_from_name(&recognizer).unwrap_or(GLOBAL_LOCATION);
+        let mut speech_recognizer =
+            Recognizer::create(google_credentials, location, &client_config).await?;
+
+        let (audio_sender, audio_receiver) =
+            mpsc::channel::<StreamingRecognizeRequest>(buffer_size.unwrap_or(1000));
+
+        let streaming_config = StreamingRecognizeRequest {
+            recognizer,
+            streaming_request: Some(StreamingRequest::StreamingConfig(config)),
+        };
+
+        audio_sender.send(streaming_config).await?;
+
+        speech_recognizer.audio_sender = Some(audio_sender);
+        speech_recognizer.audio_receiver = Some(audio_receiver);
+        Ok(speech_recognizer)
+    }
+
+    /// Creates new speech recognizer from provided Google credentials
+    /// connected to the endpoint of given location. This kind of recognizer can be used
+    /// for batch recognition and recognizer resource management.
+    pub async fn create_asynchronous_recognizer(
+        google_credentials: impl AsRef<str>,
+        location: impl AsRef<str>,
+    ) -> Result<Self> {
+        Recognizer::create_asynchronous_recognizer_with_config(
+            google_credentials,
+            location,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create_asynchronous_recognizer with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_asynchronous_recognizer_with_config(
+        google_credentials: impl AsRef<str>,
+        location: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        Recognizer::create(google_credentials, location.as_ref(), &client_config).await
+    }
+
+    /// Creates new speech recognizer from provided Google credentials
+    /// connected to the endpoint of given location. This kind of recognizer can be used
+    /// for synchronous recognition.
+    pub async fn create_synchronous_recognizer(
+        google_credentials: impl AsRef<str>,
+        location: impl AsRef<str>,
+    ) -> Result<Self> {
+        Recognizer::create_synchronous_recognizer_with_config(
+            google_credentials,
+            location,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create_synchronous_recognizer with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_synchronous_recognizer_with_config(
+        google_credentials: impl AsRef<str>,
+        location: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        Recognizer::create(google_credentials, location.as_ref(), &client_config).await
+    }
+
+    /// Returns sender than can be used to stream in audio bytes. This method can be called
+    /// multiple times to retrieve multiple senders.
+    pub fn get_audio_sink(&mut self) -> Option<mpsc::Sender<StreamingRecognizeRequest>> {
+        if let Some(audio_sender) = &self.audio_sender {
+            Some(audio_sender.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Returns sender than can be used to stream in audio bytes. This method will take
+    /// the sender out of the option leaving None in its place. No additional sender
+    /// can be retrieved from recognizer after this call. When sender is dropped respective
+    /// stream will be closed.
+    pub fn take_audio_sink(&mut self) -> Option<mpsc::Sender<StreamingRecognizeRequest>> {
+        if let Some(audio_sender) = self.audio_sender.take() {
+            Some(audio_sender)
+        } else {
+            None
+        }
+    }
+
+    /// Drops audio sender so that respective stream can be closed.
+    pub fn drop_audio_sink(&mut self) {
+        self.audio_sender.take();
+    }
+
+    /// Returns receiver that can be used to receive speech-to-text results
+    /// used with streaming_recognize function.
+    pub fn get_streaming_result_receiver(
+        &mut self,
+        // buffer size for tokio channel. If not provided defaults to 1000.
+        buffer_size: Option<usize>,
+    ) -> mpsc::Receiver<StreamingRecognizeResponse> {
+        let (result_sender, result_receiver) =
+            mpsc::channel::<StreamingRecognizeResponse>(buffer_size.unwrap_or(1000));
+        self.result_sender = Some(result_sender);
+        result_receiver
+    }
+
+    /// Sets usage meter recording billable units consumed by this client
+    /// (see metering module).
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.usage_meter = Some(usage_meter);
+    }
+
+    /// Convenience function so that client does not have to create full StreamingRecognizeRequest
+    /// and can just pass audio bytes vector instead. Recognizer name is sent only with
+    /// the first (configuration) request.
+    pub fn streaming_request_from_bytes(audio_bytes: Vec<u8>) -> StreamingRecognizeRequest {
+        StreamingRecognizeRequest {
+            recognizer: "".to_string(),
+            streaming_request: Some(StreamingRequest::Audio(audio_bytes)),
+        }
+    }
+
+    /// Initiates bidirectional streaming. Returns
+    /// asynchronous stream of streaming recognition results
+    /// Audio data must be fed into recognizer via channel sender
+    /// returned by function get_audio_sink.
+    #[allow(unreachable_code)]
+    pub async fn streaming_recognize_async_stream(
+        &mut self,
+        options: Option<CallOptions>,
+    ) -> impl Stream<Item = Result<StreamingRecognizeResponse>> + '_ {
+        try_stream! {
+                // yank self.audio_receiver so that we can consume it
+                if let Some(audio_receiver) = self.audio_receiver.take() {
+                    let options = options.or_else(CallOptions::current);
+                    let session = StreamingSession::start("Recognizer.streaming_recognize_async_stream");
+                    session.meter_usage(self.usage_meter.as_ref(), UsageMetric::SpeechToTextSeconds, options.as_ref());
+                    let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+                    let streaming_recognize_result: StdResult<
+                        TonicResponse<Streaming<StreamingRecognizeResponse>>,
+                        TonicStatus,
+                    > = session.instrument(self.speech_client.streaming_recognize(new_request(requests, options)?)).await;
+
+                    let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                        session.track(streaming_recognize_result)?.into_inner();
+
+                    trace!("streaming_recognize: entering loop");
+                    while let Some(streaming_recognize_response) = session.track(response_stream.message().await)? {
+                        session.response_received(&streaming_recognize_response);
+                        yield streaming_recognize_response;
+                    }
+                    trace!("streaming_recognize: leaving loop");
+                    session.finish(Ok(StreamingOutcome::Completed));
+                }
+        }
+    }
+
+    /// Consumes recognizer and initiates bidirectional streaming. Returns audio sink
+    /// and owned (Send + 'static) stream of streaming recognition results, i.e. stream can be
+    /// moved into spawned task or stored in a struct. Stream is closed once the audio sink
+    /// (and all its clones retrieved via get_audio_sink) is dropped and remaining results are received.
+    pub fn into_streaming_session(
+        mut self,
+        options: Option<CallOptions>,
+    ) -> Result<(
+        mpsc::Sender<StreamingRecognizeRequest>,
+        impl Stream<Item = Result<StreamingRecognizeResponse>> + Send + 'static,
+    )> {
+        let (audio_sender, audio_receiver) =
+            match (self.audio_sender.take(), self.audio_receiver.take()) {
+                (Some(audio_sender), Some(audio_receiver)) => (audio_sender, audio_receiver),
+                _ => {
+                    return Err(Error::new(
+                        "Recognizer.into_streaming_session: audio sink not available, use create_streaming_recognizer".to_string(),
+                    ))
+                }
+            };
+        let mut speech_client = self.speech_client;
+        let usage_meter = self.usage_meter;
+        let options = options.or_else(CallOptions::current);
+
+        let stream = try_stream! {
+            let session = StreamingSession::start("Recognizer.into_streaming_session");
+            session.meter_usage(usage_meter.as_ref(), UsageMetric::SpeechToTextSeconds, options.as_ref());
+            let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+            let streaming_recognize_result: StdResult<
+                TonicResponse<Streaming<StreamingRecognizeResponse>>,
+                TonicStatus,
+            > = session.instrument(speech_client.streaming_recognize(new_request(requests, options)?)).await;
+
+            let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                session.track(streaming_recognize_result)?.into_inner();
+
+            trace!("into_streaming_session: entering loop");
+            while let Some(streaming_recognize_response) = session.track(response_stream.message().await)? {
+                session.response_received(&streaming_recognize_response);
+                yield streaming_recognize_response;
+            }
+            trace!("into_streaming_session: leaving loop");
+            session.finish(Ok(StreamingOutcome::Completed));
+        };
+
+        Ok((audio_sender, stream))
+    }
+
+    /// Initiates bidirectional streaming. This call should be spawned
+    /// into separate tokio task. Results can be then retrieved via
+    /// channel receiver returned by method get_streaming_result_receiver.
+    pub async fn streaming_recognize(&mut self, options: Option<CallOptions>) -> Result<()> {
+        // yank self.audio_receiver so that we can consume it
+        if let Some(audio_receiver) = self.audio_receiver.take() {
+            let options = options.or_else(CallOptions::current);
+            let session = StreamingSession::start("Recognizer.streaming_recognize");
+            session.meter_usage(
+                self.usage_meter.as_ref(),
+                UsageMetric::SpeechToTextSeconds,
+                options.as_ref(),
+            );
+            let requests = session.meter_requests(ReceiverStream::new(audio_receiver));
+            let result = session
+                .instrument(async {
+                    let streaming_recognize_result: StdResult<
+                        tonic::Response<Streaming<StreamingRecognizeResponse>>,
+                        tonic::Status,
+                    > = self
+                        .speech_client
+                        .streaming_recognize(new_request(requests, options)?)
+                        .await;
+
+                    let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                        streaming_recognize_result?.into_inner();
+
+                    while let Some(streaming_recognize_response) = response_stream.message().await?
+                    {
+                        session.response_received(&streaming_recognize_response);
+                        if let Some(result_sender) = &self.result_sender {
+                            result_sender.send(streaming_recognize_response).await?;
+                        }
+                    }
+                    Ok(())
+                })
+                .await;
+            session.finish(result.as_ref().map(|_| StreamingOutcome::Completed));
+            result?;
+        }
+
+        Ok(())
+    }
+
+    /// Initiates bidirectional streaming which can be aborted or gracefully shut down
+    /// via cancellation token. This call should be spawned into separate tokio task.
+    /// Results are sent into channel receiver returned by method get_streaming_result_receiver,
+    /// result channel is closed once this method returns. Returns Completed if the stream
+    /// was closed by the API, Cancelled or GracePeriodElapsed if it was aborted.
+    pub async fn streaming_recognize_cancellable(
+        &mut self,
+        token: CancellationToken,
+        options: Option<CallOptions>,
+    ) -> Result<StreamingOutcome> {
+        // yank self.audio_receiver so that we can consume it
+        if let Some(audio_receiver) = self.audio_receiver.take() {
+            let result_sender = self.result_sender.take();
+            let options = options.or_else(CallOptions::current);
+            let session = StreamingSession::start("Recognizer.streaming_recognize_cancellable");
+            session.meter_usage(
+                self.usage_meter.as_ref(),
+                UsageMetric::SpeechToTextSeconds,
+                options.as_ref(),
+            );
+            let requests =
+                session.meter_requests(cancellable_request_stream(audio_receiver, token.clone()));
+            let result = session
+                .instrument(async {
+                    let streaming_result: StdResult<
+                        tonic::Response<Streaming<StreamingRecognizeResponse>>,
+                        tonic::Status,
+                    > = self
+                        .speech_client
+                        .streaming_recognize(new_request(requests, options)?)
+                        .await;
+
+                    let mut response_stream: Streaming<StreamingRecognizeResponse> =
+                        streaming_result?.into_inner();
+
+                    forward_responses(
+                        &mut response_stream,
+                        result_sender.as_ref(),
+                        &token,
+                        &session,
+                    )
+                    .await
+                })
+                .await;
+            session.finish(result.as_ref().copied());
+            result
+        } else {
+            Err(Error::new(
+                "Recognizer.streaming_recognize_cancellable: audio receiver not available, use create_streaming_recognizer".to_string(),
+            ))
+        }
+    }
+
+    /// Performs synchronous speech recognition.
+    pub async fn recognize(
+        &mut self,
+        request: RecognizeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<RecognizeResponse> {
+        let options = options.or_else(CallOptions::current);
+        let tonic_response: TonicResponse<RecognizeResponse> = self
+            .speech_client
+            .recognize(new_request(request, options.clone())?)
+            .await?;
+        let response = tonic_response.into_inner();
+        meter_recognition(
+            self.usage_meter.as_ref(),
+            response
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.total_billed_duration.as_ref())
+                .map(duration_seconds),
+            None,
+            options.as_ref(),
+        );
+        Ok(response)
+    }
+
+    /// Initiates batch recognition of Cloud Storage files.
+    /// Returns long running operation representing
+    /// asynchronous computation performed by Google Cloud Platform.
+    /// Use batch_recognize_wait to wait until operation is done.
+    pub async fn batch_recognize(
+        &mut self,
+        request: BatchRecognizeRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let tonic_response: TonicResponse<Operation> = self
+            .speech_client
+            .batch_recognize(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Waits for completion of long running operation returned
+    /// by batch_recognize function. Long running operation
+    /// result is then casted into BatchRecognizeResponse struct.
+    /// Function checks operation status regularly using get_operation
+    /// which is called every check_interval_ms ms. If check_interval_ms
+    /// is not specified default interval check is 1 sec.
+    pub async fn batch_recognize_wait(
+        &mut self,
+        operation: Operation,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<BatchRecognizeResponse>> {
+        let options = options.or_else(CallOptions::current);
+        let response: Option<BatchRecognizeResponse> = self
+            .operations_client
+            .long_running_wait(operation, check_interval_ms, options.clone())
+            .await?;
+        if let Some(response) = &response {
+            meter_recognition(
+                self.usage_meter.as_ref(),
+                response
+                    .total_billed_duration
+                    .as_ref()
+                    .map(duration_seconds),
+                None,
+                options.as_ref(),
+            );
+        }
+        Ok(response)
+    }
+
+    /// Creates recognizer resource. Returns long running operation,
+    /// use recognizer_operation_wait to retrieve created recognizer.
+    pub async fn create_recognizer(
+        &mut self,
+        request: CreateRecognizerRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let tonic_response: TonicResponse<Operation> = self
+            .speech_client
+            .create_recognizer(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Returns recognizer resource of given name.
+    pub async fn get_recognizer(
+        &mut self,
+        name: impl Into<String>,
+        options: Option<CallOptions>,
+    ) -> Result<RecognizerResource> {
+        let request = GetRecognizerRequest { name: name.into() };
+        let tonic_response: TonicResponse<RecognizerResource> = self
+            .speech_client
+            .get_recognizer(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Returns one page of recognizer resources.
+    pub async fn list_recognizers(
+        &mut self,
+        request: ListRecognizersRequest,
+        options: Option<CallOptions>,
+    ) -> Result<ListRecognizersResponse> {
+        let tonic_response: TonicResponse<ListRecognizersResponse> = self
+            .speech_client
+            .list_recognizers(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Returns all recognizer resources of given parent
+    /// (projects/{project}/locations/{location}) iterating through all result pages.
+    pub async fn list_all_recognizers(
+        &mut self,
+        parent: impl Into<String>,
+        show_deleted: bool,
+        options: Option<CallOptions>,
+    ) -> Result<Vec<RecognizerResource>> {
+        let parent = parent.into();
+        let mut recognizers = vec![];
+        let mut page_token = "".to_string();
+        loop {
+            let response = self
+                .list_recognizers(
+                    ListRecognizersRequest {
+                        parent: parent.clone(),
+                        page_size: 100,
+                        page_token,
+                        show_deleted,
+                    },
+                    options.clone(),
+                )
+                .await?;
+            recognizers.extend(response.recognizers);
+            if response.next_page_token.is_empty() {
+                break;
+            }
+            page_token = response.next_page_token;
+        }
+        Ok(recognizers)
+    }
+
+    /// Updates recognizer resource. Returns long running operation,
+    /// use recognizer_operation_wait to retrieve updated recognizer.
+    pub async fn update_recognizer(
+        &mut self,
+        request: UpdateRecognizerRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let tonic_response: TonicResponse<Operation> = self
+            .speech_client
+            .update_recognizer(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Deletes recognizer resource. Returns long running operation,
+    /// use recognizer_operation_wait to retrieve deleted recognizer.
+    /// Deleted recognizers can be undeleted until their expire_time.
+    pub async fn delete_recognizer(
+        &mut self,
+        request: DeleteRecognizerRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let tonic_response: TonicResponse<Operation> = self
+            .speech_client
+            .delete_recognizer(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Undeletes recognizer resource. Returns long running operation,
+    /// use recognizer_operation_wait to retrieve undeleted recognizer.
+    pub async fn undelete_recognizer(
+        &mut self,
+        request: UndeleteRecognizerRequest,
+        options: Option<CallOptions>,
+    ) -> Result<Operation> {
+        let tonic_response: TonicResponse<Operation> = self
+            .speech_client
+            .undelete_recognizer(new_request(request, options)?)
+            .await?;
+        Ok(tonic_response.into_inner())
+    }
+
+    /// Waits for completion of long running operation returned by
+    /// create_recognizer, update_recognizer, delete_recognizer or undelete_recognizer.
+    /// Long running operation result is then casted into recognizer resource.
+    pub async fn recognizer_operation_wait(
+        &mut self,
+        operation: Operation,
+        check_interval_ms: Option<u64>,
+        options: Option<CallOptions>,
+    ) -> Result<Option<RecognizerResource>> {
+        self.operations_client
+            .long_running_wait(operation, check_interval_ms, options)
+            .await
+    }
+
+    /// Returns long running operations client connected to the same endpoint,
+    /// e.g. to check progress or cancel batch recognition.
+    pub fn operations_client(&self) -> OperationsClient {
+        self.operations_client.clone()
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_recognizer_v2_names
+    #[test]
+    fn test_recognizer_v2_names() {
+        let name = get_recognizer_string("my-project", "europe-west4", "_");
+        assert_eq!(
+            name,
+            "projects/my-project/locations/europe-west4/recognizers/_"
+        );
+        assert!(validate_recognizer_name(&name).is_ok());
+        assert!(validate_recognizer_name("projects/my-project/locations/global").is_err());
+        assert!(validate_recognizer_name("projects//locations/global/recognizers/r").is_err());
+
+        assert_eq!(get_location_from_name(&name), Some("europe-west4"));
+        assert_eq!(
+            get_location_from_name(&get_location_string("my-project", "global")),
+            Some("global")
+        );
+        assert_eq!(get_location_from_name("projects/my-project"), None);
+
+        assert_eq!(
+            get_api_endpoint("europe-west4"),
+            (
+                "europe-west4-speech.googleapis.com".to_string(),
+                "https://europe-west4-speech.googleapis.com".to_string()
+            )
+        );
+        assert_eq!(
+            get_api_endpoint(GLOBAL_LOCATION),
+            (
+                "speech.googleapis.com".to_string(),
+                "https://speech.googleapis.com".to_string()
+            )
+        );
+    }
+}
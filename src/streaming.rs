@@ -0,0 +1,201 @@
+//! Contains cancellation support for bidirectional streaming sessions
+//! (streaming recognition, streaming detect intent). Cancellation token
+//! can be used either to abort the session immediately or to shut it down gracefully:
+//! sending of the audio is stopped (request stream is half-closed) and final results
+//! are awaited up to specified grace period. In both cases underlying GRPC stream is released
+//! once the streaming method returns.
+use crate::errors::{Error, Result};
+use crate::telemetry::{StreamingResponse, StreamingSession};
+use async_stream::stream;
+use futures_core::stream::Stream;
+use log::*;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep_until, Instant};
+use tonic::Streaming;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CancellationState {
+    Running,
+    Shutdown(Duration),
+    Cancelled,
+}
+
+/// Outcome of cancellable streaming session. Errors are reported via Err variant of the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamingOutcome {
+    /// Stream was closed by the API after all results have been received
+    /// (either all audio was sent or graceful shutdown was requested).
+    Completed,
+
+    /// Stream was aborted by CancellationToken::cancel.
+    Cancelled,
+
+    /// Graceful shutdown was requested but the API did not close
+    /// the stream within the grace period. Stream was aborted.
+    GracePeriodElapsed,
+}
+
+/// Token used to cancel or gracefully shut down streaming session. Is cloneable,
+/// all clones control the same session(s).
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    sender: Arc<watch::Sender<CancellationState>>,
+    receiver: watch::Receiver<CancellationState>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        CancellationToken::new()
+    }
+}
+
+impl CancellationToken {
+    /// Creates new cancellation token.
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(CancellationState::Running);
+        CancellationToken {
+            sender: Arc::new(sender),
+            receiver,
+        }
+    }
+
+    /// Aborts the session immediately. Results not yet received are lost.
+    pub fn cancel(&self) {
+        self.sender.send_replace(CancellationState::Cancelled);
+    }
+
+    /// Stops sending of the audio and waits for final results up to grace_period.
+    /// If the API does not close the stream within grace period session is aborted.
+    /// Has no effect if the session was already cancelled.
+    pub fn shutdown(&self, grace_period: Duration) {
+        if *self.receiver.borrow() == CancellationState::Running {
+            self.sender
+                .send_replace(CancellationState::Shutdown(grace_period));
+        }
+    }
+
+    /// Returns true if either cancel or shutdown was requested.
+    pub fn is_cancelled(&self) -> bool {
+        *self.receiver.borrow() != CancellationState::Running
+    }
+
+    /// Waits until either cancel or shutdown is requested.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.receiver.clone();
+        while *receiver.borrow() == CancellationState::Running {
+            if receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Wraps receiver of streaming requests (audio) into stream which ends
+/// once cancel or shutdown is requested, i.e. request stream is half-closed
+/// regardless of the senders held by the library client.
+pub(crate) fn cancellable_request_stream<T: Send + 'static>(
+    mut receiver: mpsc::Receiver<T>,
+    token: CancellationToken,
+) -> impl Stream<Item = T> + Send + 'static {
+    stream! {
+        loop {
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => {
+                    trace!("cancellable_request_stream: sending stopped");
+                    break;
+                }
+                request = receiver.recv() => match request {
+                    Some(request) => yield request,
+                    None => break,
+                },
+            }
+        }
+    }
+}
+
+/// Reads responses of the streaming session and forwards them into result sender
+/// (if provided) until the stream is closed by the API, cancelled or the grace period elapses.
+/// Received responses and cancellation requests are reported into the streaming session telemetry.
+pub(crate) async fn forward_responses<T: StreamingResponse>(
+    response_stream: &mut Streaming<T>,
+    result_sender: Option<&mpsc::Sender<T>>,
+    token: &CancellationToken,
+    session: &StreamingSession,
+) -> Result<StreamingOutcome> {
+    let mut receiver = token.receiver.clone();
+    let mut deadline: Option<Instant> = None;
+    loop {
+        let state = *receiver.borrow();
+        match state {
+            CancellationState::Cancelled => {
+                session.event("cancellation requested");
+                return Ok(StreamingOutcome::Cancelled);
+            }
+            CancellationState::Shutdown(grace_period) if deadline.is_none() => {
+                session.event("graceful shutdown requested");
+                deadline = Some(Instant::now() + grace_period);
+            }
+            _ => {}
+        }
+
+        tokio::select! {
+            message = response_stream.message() => match message? {
+                Some(response) => {
+                    session.response_received(&response);
+                    if let Some(result_sender) = result_sender {
+                        result_sender.send(response).await.map_err(|_| {
+                            Error::new("forward_responses: result receiver dropped".to_string())
+                        })?;
+                    }
+                }
+                None => return Ok(StreamingOutcome::Completed),
+            },
+            changed = receiver.changed(), if state != CancellationState::Cancelled => {
+                // all senders dropped cannot happen (token holds one), ignore defensively
+                if changed.is_err() {
+                    deadline.get_or_insert_with(Instant::now);
+                }
+            }
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                return Ok(StreamingOutcome::GracePeriodElapsed);
+            }
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use tokio_stream::StreamExt;
+
+    // cargo test -- --show-output test_cancellable_request_stream
+    #[tokio::test]
+    async fn test_cancellable_request_stream() {
+        let token = CancellationToken::new();
+        let (sender, receiver) = mpsc::channel::<u32>(10);
+        let stream = cancellable_request_stream(receiver, token.clone());
+        tokio::pin!(stream);
+
+        sender.send(1).await.unwrap();
+        sender.send(2).await.unwrap();
+        assert_eq!(stream.next().await, Some(1));
+        assert_eq!(stream.next().await, Some(2));
+        assert!(!token.is_cancelled());
+
+        // stream ends on shutdown even though sender is still alive
+        token.shutdown(Duration::from_millis(100));
+        assert!(token.is_cancelled());
+        sender.send(3).await.unwrap();
+        assert_eq!(stream.next().await, None);
+
+        // cancel after shutdown keeps token cancelled, shutdown after cancel is ignored
+        token.cancel();
+        token.shutdown(Duration::from_secs(1));
+        assert_eq!(*token.receiver.borrow(), CancellationState::Cancelled);
+        token.cancelled().await;
+    }
+}
@@ -0,0 +1,814 @@
+//! Contains telemetry of GRPC calls made by the clients of this crate.
+//!
+//! Every RPC is measured by the transport (see transport::GrpcChannel): method, API version,
+//! GRPC status code, latency and bytes sent/received. Streaming sessions (streaming recognition,
+//! streaming detect intent) additionally report lifecycle events (session started, request stream
+//! closed, first response received, session finished) together with seconds of audio streamed.
+//!
+//! With `tracing` feature enabled measurements are emitted as spans (`grpc.call`,
+//! `grpc.streaming_session`) and events of the tracing crate, RPC span of the streaming call
+//! is a child of the streaming session span. Without the feature they are logged (debug level).
+//!
+//! W3C trace context (traceparent/tracestate headers) is propagated into GRPC metadata either
+//! when specified explicitly via CallOptions::trace_context or when provided by global trace context
+//! provider (see set_trace_context_provider), e.g. for OpenTelemetry:
+//!
+//! ```ignore
+//! set_trace_context_provider(|| {
+//!     let context = tracing::Span::current().context();
+//!     let span_context = context.span().span_context().clone();
+//!     TraceContext::parse(&format!(
+//!         "00-{}-{}-{:02x}",
+//!         span_context.trace_id(),
+//!         span_context.span_id(),
+//!         span_context.trace_flags().to_u8()
+//!     ), None).ok()
+//! });
+//! ```
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+use crate::api::grpc::google::cloud::speechtotext::v2::{
+    explicit_decoding_config::AudioEncoding as AudioEncodingV2,
+    recognition_config::DecodingConfig as DecodingConfigV2,
+    streaming_recognize_request::StreamingRequest as StreamingRequestV2,
+    StreamingRecognizeRequest as StreamingRecognizeRequestV2,
+    StreamingRecognizeResponse as StreamingRecognizeResponseV2,
+};
+use crate::api::grpc::google::cloud::{
+    dialogflow::v2beta1::{
+        query_input::Input, AudioEncoding as DialogflowAudioEncoding, StreamingDetectIntentRequest,
+        StreamingDetectIntentResponse,
+    },
+    speechtotext::v1::{
+        recognition_config::AudioEncoding, streaming_recognize_request::StreamingRequest,
+        RecognitionConfig, StreamingRecognizeRequest, StreamingRecognizeResponse,
+    },
+    speechtotext::v1p1beta1::{
+        recognition_config::AudioEncoding as AudioEncodingBeta,
+        streaming_recognize_request::StreamingRequest as StreamingRequestBeta,
+        RecognitionConfig as RecognitionConfigBeta,
+        StreamingRecognizeRequest as StreamingRecognizeRequestBeta,
+        StreamingRecognizeResponse as StreamingRecognizeResponseBeta,
+    },
+};
+use crate::call_options::CallOptions;
+use crate::errors::{Error, Result};
+use crate::metering::{record_quantity, UsageLabels, UsageMeter, UsageMetric};
+use crate::streaming::StreamingOutcome;
+use async_stream::stream;
+use futures_core::stream::Stream;
+#[cfg(not(feature = "tracing"))]
+use log::*;
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio_stream::StreamExt;
+use tonic::Code;
+
+/// W3C trace context header carrying trace id, parent id and trace flags.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// W3C trace context header carrying vendor specific trace state.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+type TraceContextProvider = Box<dyn Fn() -> Option<TraceContext> + Send + Sync>;
+
+static TRACE_CONTEXT_PROVIDER: RwLock<Option<TraceContextProvider>> = RwLock::new(None);
+
+/// W3C trace context (https://www.w3.org/TR/trace-context/) propagated into GRPC metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// Id of the whole trace.
+    pub trace_id: u128,
+
+    /// Id of the caller's span (parent of the GRPC call).
+    pub span_id: u64,
+
+    /// Trace flags, 0x01 means sampled.
+    pub flags: u8,
+
+    /// Optional vendor specific trace state (tracestate header).
+    pub trace_state: Option<String>,
+}
+
+impl TraceContext {
+    /// Creates new (sampled) trace context with random trace id and span id.
+    pub fn new_root() -> Self {
+        TraceContext {
+            trace_id: (random_u64() as u128) << 64 | random_u64() as u128,
+            span_id: random_u64(),
+            flags: 0x01,
+            trace_state: None,
+        }
+    }
+
+    /// Creates context of the child span, i.e. same trace with new random span id.
+    pub fn child(&self) -> Self {
+        TraceContext {
+            span_id: random_u64(),
+            ..self.clone()
+        }
+    }
+
+    /// Parses traceparent (and optionally tracestate) header value.
+    pub fn parse(traceparent: &str, tracestate: Option<&str>) -> Result<Self> {
+        let invalid = || Error::new(format!("invalid traceparent: {}", traceparent));
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        if parts.len() < 4
+            || parts[0].len() != 2
+            || parts[1].len() != 32
+            || parts[2].len() != 16
+            || parts[3].len() != 2
+            || parts[0] == "ff"
+            || (parts[0] == "00" && parts.len() != 4)
+        {
+            return Err(invalid());
+        }
+        u8::from_str_radix(parts[0], 16).map_err(|_| invalid())?;
+        let trace_id = u128::from_str_radix(parts[1], 16).map_err(|_| invalid())?;
+        let span_id = u64::from_str_radix(parts[2], 16).map_err(|_| invalid())?;
+        let flags = u8::from_str_radix(parts[3], 16).map_err(|_| invalid())?;
+        if trace_id == 0 || span_id == 0 {
+            return Err(invalid());
+        }
+        Ok(TraceContext {
+            trace_id,
+            span_id,
+            flags,
+            trace_state: tracestate
+                .map(str::trim)
+                .filter(|state| !state.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    /// Returns value of traceparent header, e.g. 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id, self.span_id, self.flags
+        )
+    }
+
+    /// Returns true if sampled flag is set.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & 0x01 == 0x01
+    }
+
+    /// Returns trace context of the current call. Context specified via
+    /// CallOptions::trace_context takes precedence over global trace context provider.
+    pub fn current() -> Option<TraceContext> {
+        TraceContext::for_options(CallOptions::current().as_ref())
+    }
+
+    /// Returns trace context of the call with given options. Context specified via
+    /// CallOptions::trace_context takes precedence over global trace context provider.
+    pub fn for_options(options: Option<&CallOptions>) -> Option<TraceContext> {
+        if let Some(trace_context) = options.and_then(|options| options.trace_context.clone()) {
+            return Some(trace_context);
+        }
+        match TRACE_CONTEXT_PROVIDER.read() {
+            Ok(provider) => provider.as_ref().and_then(|provider| provider()),
+            Err(_) => None,
+        }
+    }
+}
+
+/// Sets global provider of trace context propagated into GRPC metadata of every call
+/// (unless specified explicitly via CallOptions::trace_context). Typically provider returns
+/// context of the current OpenTelemetry span, see module documentation.
+pub fn set_trace_context_provider<F>(provider: F)
+where
+    F: Fn() -> Option<TraceContext> + Send + Sync + 'static,
+{
+    if let Ok(mut current) = TRACE_CONTEXT_PROVIDER.write() {
+        *current = Some(Box::new(provider));
+    }
+}
+
+/// Removes global trace context provider.
+pub fn clear_trace_context_provider() {
+    if let Ok(mut current) = TRACE_CONTEXT_PROVIDER.write() {
+        *current = None;
+    }
+}
+
+fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u128(nanos);
+    // zero id is invalid in W3C trace context
+    hasher.finish().max(1)
+}
+
+/// Splits GRPC path (/google.cloud.speech.v1.Speech/Recognize) into
+/// service (google.cloud.speech.v1.Speech), method (Recognize) and API version (v1).
+pub(crate) fn parse_grpc_path(path: &str) -> (String, String, String) {
+    let mut parts = path.trim_start_matches('/').splitn(2, '/');
+    let service = parts.next().unwrap_or_default().to_string();
+    let method = parts.next().unwrap_or_default().to_string();
+    let api_version = service
+        .split('.')
+        .find(|segment| {
+            segment.len() > 1
+                && segment.starts_with('v')
+                && segment[1..2].chars().all(|c| c.is_ascii_digit())
+        })
+        .unwrap_or_default()
+        .to_string();
+    (service, method, api_version)
+}
+
+/// Measurement of single RPC. Started by the transport when the call is made,
+/// finished when response body is fully received (or dropped).
+#[derive(Debug)]
+pub(crate) struct RpcCall {
+    #[cfg(not(feature = "tracing"))]
+    service: String,
+    #[cfg(not(feature = "tracing"))]
+    method: String,
+    #[cfg(not(feature = "tracing"))]
+    api_version: String,
+    started: Instant,
+    bytes_sent: Arc<AtomicU64>,
+    bytes_received: u64,
+    finished: bool,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl RpcCall {
+    /// Starts measurement of the call with given GRPC path.
+    pub(crate) fn start(path: &str, trace_context: Option<&TraceContext>) -> Self {
+        let (service, method, api_version) = parse_grpc_path(path);
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "grpc.call",
+            otel.kind = "client",
+            rpc.system = "grpc",
+            rpc.service = %service,
+            rpc.method = %method,
+            api.version = %api_version,
+            trace_id = tracing::field::Empty,
+            rpc.grpc.status_code = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            bytes_sent = tracing::field::Empty,
+            bytes_received = tracing::field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        if let Some(trace_context) = trace_context {
+            span.record(
+                "trace_id",
+                &tracing::field::display(format!("{:032x}", trace_context.trace_id)),
+            );
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = trace_context;
+
+        RpcCall {
+            #[cfg(not(feature = "tracing"))]
+            service,
+            #[cfg(not(feature = "tracing"))]
+            method,
+            #[cfg(not(feature = "tracing"))]
+            api_version,
+            started: Instant::now(),
+            bytes_sent: Arc::new(AtomicU64::new(0)),
+            bytes_received: 0,
+            finished: false,
+            #[cfg(feature = "tracing")]
+            span,
+        }
+    }
+
+    /// Counter of request body bytes (shared with request body).
+    pub(crate) fn bytes_sent_counter(&self) -> Arc<AtomicU64> {
+        self.bytes_sent.clone()
+    }
+
+    pub(crate) fn record_received(&mut self, bytes: usize) {
+        self.bytes_received += bytes as u64;
+    }
+
+    /// Finishes measurement with GRPC status code of the call.
+    pub(crate) fn finish(&mut self, code: Code) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        let latency_ms = self.started.elapsed().as_millis() as u64;
+        let bytes_sent = self.bytes_sent.load(Ordering::Relaxed);
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span
+                .record("rpc.grpc.status_code", &(code as i32))
+                .record("latency_ms", &latency_ms)
+                .record("bytes_sent", &bytes_sent)
+                .record("bytes_received", &self.bytes_received);
+            let _enter = self.span.enter();
+            if code == Code::Ok {
+                tracing::debug!(status = ?code, "grpc call finished");
+            } else {
+                tracing::warn!(status = ?code, "grpc call failed");
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        debug!(
+            "grpc call {}/{} ({}) finished: status={:?}, latency={}ms, bytes_sent={}, bytes_received={}",
+            self.service,
+            self.method,
+            self.api_version,
+            code,
+            latency_ms,
+            bytes_sent,
+            self.bytes_received
+        );
+    }
+}
+
+impl Drop for RpcCall {
+    fn drop(&mut self) {
+        // response dropped before it was fully received
+        self.finish(Code::Cancelled);
+    }
+}
+
+/// Request message of bidirectional streaming session carrying audio.
+pub trait StreamingAudioRequest {
+    /// Number of audio bytes carried by the request.
+    fn audio_len(&self) -> usize;
+
+    /// Audio bytes per second if the request carries configuration
+    /// of uncompressed (LINEAR16) or G.711 (MULAW, ALAW) audio.
+    fn audio_bytes_per_second(&self) -> Option<f64>;
+}
+
+fn bytes_per_second(bytes_per_sample: i32, sample_rate_hertz: i32, channels: i32) -> Option<f64> {
+    if sample_rate_hertz > 0 {
+        Some((bytes_per_sample * sample_rate_hertz * channels.max(1)) as f64)
+    } else {
+        None
+    }
+}
+
+/// Audio bytes per second of LINEAR16 or MULAW audio described by recognition config (v1).
+pub(crate) fn recognition_bytes_per_second(config: &RecognitionConfig) -> Option<f64> {
+    let bytes_per_sample = match AudioEncoding::from_i32(config.encoding)? {
+        AudioEncoding::Linear16 => 2,
+        AudioEncoding::Mulaw => 1,
+        _ => return None,
+    };
+    bytes_per_second(
+        bytes_per_sample,
+        config.sample_rate_hertz,
+        config.audio_channel_count,
+    )
+}
+
+/// Audio bytes per second of LINEAR16 or MULAW audio described by recognition config (v1p1beta1).
+pub(crate) fn recognition_bytes_per_second_beta(config: &RecognitionConfigBeta) -> Option<f64> {
+    let bytes_per_sample = match AudioEncodingBeta::from_i32(config.encoding)? {
+        AudioEncodingBeta::Linear16 => 2,
+        AudioEncodingBeta::Mulaw => 1,
+        _ => return None,
+    };
+    bytes_per_second(
+        bytes_per_sample,
+        config.sample_rate_hertz,
+        config.audio_channel_count,
+    )
+}
+
+impl StreamingAudioRequest for StreamingRecognizeRequest {
+    fn audio_len(&self) -> usize {
+        match &self.streaming_request {
+            Some(StreamingRequest::AudioContent(audio)) => audio.len(),
+            _ => 0,
+        }
+    }
+
+    fn audio_bytes_per_second(&self) -> Option<f64> {
+        match &self.streaming_request {
+            Some(StreamingRequest::StreamingConfig(config)) => {
+                recognition_bytes_per_second(config.config.as_ref()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl StreamingAudioRequest for StreamingRecognizeRequestBeta {
+    fn audio_len(&self) -> usize {
+        match &self.streaming_request {
+            Some(StreamingRequestBeta::AudioContent(audio)) => audio.len(),
+            _ => 0,
+        }
+    }
+
+    fn audio_bytes_per_second(&self) -> Option<f64> {
+        match &self.streaming_request {
+            Some(StreamingRequestBeta::StreamingConfig(config)) => {
+                recognition_bytes_per_second_beta(config.config.as_ref()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+impl StreamingAudioRequest for StreamingRecognizeRequestV2 {
+    fn audio_len(&self) -> usize {
+        match &self.streaming_request {
+            Some(StreamingRequestV2::Audio(audio)) => audio.len(),
+            _ => 0,
+        }
+    }
+
+    fn audio_bytes_per_second(&self) -> Option<f64> {
+        match &self.streaming_request {
+            Some(StreamingRequestV2::StreamingConfig(config)) => {
+                match config.config.as_ref()?.decoding_config.as_ref()? {
+                    DecodingConfigV2::ExplicitDecodingConfig(config) => {
+                        let bytes_per_sample = match AudioEncodingV2::from_i32(config.encoding)? {
+                            AudioEncodingV2::Linear16 => 2,
+                            AudioEncodingV2::Mulaw | AudioEncodingV2::Alaw => 1,
+                            _ => return None,
+                        };
+                        bytes_per_second(
+                            bytes_per_sample,
+                            config.sample_rate_hertz,
+                            config.audio_channel_count,
+                        )
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl StreamingAudioRequest for StreamingDetectIntentRequest {
+    fn audio_len(&self) -> usize {
+        self.input_audio.len()
+    }
+
+    fn audio_bytes_per_second(&self) -> Option<f64> {
+        match self.query_input.as_ref()?.input.as_ref()? {
+            Input::AudioConfig(config) => {
+                let bytes_per_sample =
+                    match DialogflowAudioEncoding::from_i32(config.audio_encoding)? {
+                        DialogflowAudioEncoding::Linear16 => 2,
+                        DialogflowAudioEncoding::Mulaw => 1,
+                        _ => return None,
+                    };
+                bytes_per_second(bytes_per_sample, config.sample_rate_hertz, 1)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Response message of bidirectional streaming session.
+pub trait StreamingResponse {
+    /// Billed audio seconds of the session reported by the API (if any).
+    fn billed_seconds(&self) -> Option<f64>;
+}
+
+impl StreamingResponse for StreamingRecognizeResponse {
+    fn billed_seconds(&self) -> Option<f64> {
+        let duration = self.total_billed_time.as_ref()?;
+        Some(crate::metering::duration_seconds(duration))
+    }
+}
+
+impl StreamingResponse for StreamingRecognizeResponseBeta {
+    fn billed_seconds(&self) -> Option<f64> {
+        let duration = self.total_billed_time.as_ref()?;
+        Some(crate::metering::duration_seconds(duration))
+    }
+}
+
+#[cfg(any(feature = "default", feature = "google-cloud-speechtotext-v2",))]
+impl StreamingResponse for StreamingRecognizeResponseV2 {
+    fn billed_seconds(&self) -> Option<f64> {
+        let duration = self.metadata.as_ref()?.total_billed_duration.as_ref()?;
+        Some(crate::metering::duration_seconds(duration))
+    }
+}
+
+impl StreamingResponse for StreamingDetectIntentResponse {
+    fn billed_seconds(&self) -> Option<f64> {
+        None
+    }
+}
+
+#[derive(Debug)]
+struct SessionUsage {
+    meter: UsageMeter,
+    metric: UsageMetric,
+    labels: UsageLabels,
+}
+
+#[derive(Debug)]
+struct SessionState {
+    #[cfg(not(feature = "tracing"))]
+    name: &'static str,
+    started: Instant,
+    audio_bytes: AtomicU64,
+    audio_bytes_per_second: Mutex<Option<f64>>,
+    responses: AtomicU64,
+    billed_seconds: Mutex<Option<f64>>,
+    error: Mutex<Option<String>>,
+    usage: Mutex<Option<SessionUsage>>,
+    finished: AtomicBool,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl SessionState {
+    fn event(&self, event: &str) {
+        #[cfg(feature = "tracing")]
+        {
+            let _enter = self.span.enter();
+            tracing::debug!(
+                elapsed_ms = self.started.elapsed().as_millis() as u64,
+                "{}",
+                event
+            );
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        debug!(
+            "streaming session {}: {} after {}ms",
+            self.name,
+            event,
+            self.started.elapsed().as_millis()
+        );
+    }
+
+    fn audio_seconds(&self) -> Option<f64> {
+        let audio_bytes = self.audio_bytes.load(Ordering::Relaxed) as f64;
+        (*self.audio_bytes_per_second.lock().ok()?).map(|rate| audio_bytes / rate)
+    }
+
+    /// Records usage of the session: billed seconds reported by the API
+    /// (or seconds of audio sent) for speech, single request otherwise.
+    fn record_usage(&self, audio_seconds: Option<f64>) {
+        let usage = match self.usage.lock().ok().and_then(|mut usage| usage.take()) {
+            Some(usage) => usage,
+            None => return,
+        };
+        let quantity = match usage.metric {
+            UsageMetric::SpeechToTextSeconds => {
+                let billed_seconds = self.billed_seconds.lock().ok().and_then(|billed| *billed);
+                billed_seconds.or(audio_seconds)
+            }
+            _ => Some(1.0),
+        };
+        record_quantity(&usage.meter, usage.metric, quantity, usage.labels);
+    }
+
+    fn finish(&self, outcome: &str) {
+        if self.finished.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let duration_ms = self.started.elapsed().as_millis() as u64;
+        let audio_bytes = self.audio_bytes.load(Ordering::Relaxed);
+        let responses = self.responses.load(Ordering::Relaxed);
+        let audio_seconds = self.audio_seconds();
+        self.record_usage(audio_seconds);
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span
+                .record("outcome", &outcome)
+                .record("duration_ms", &duration_ms)
+                .record("audio_bytes", &audio_bytes)
+                .record("responses", &responses);
+            if let Some(audio_seconds) = audio_seconds {
+                self.span.record(
+                    "audio_seconds",
+                    &tracing::field::display(format!("{:.3}", audio_seconds)),
+                );
+            }
+            let _enter = self.span.enter();
+            tracing::debug!(outcome, "streaming session finished");
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        debug!(
+            "streaming session {} finished: outcome={}, duration={}ms, audio_bytes={}, audio_seconds={:?}, responses={}",
+            self.name, outcome, duration_ms, audio_bytes, audio_seconds, responses
+        );
+    }
+}
+
+/// Lifecycle of bidirectional streaming session (streaming recognition, streaming detect intent).
+/// Session is finished either explicitly (finish) or when dropped (e.g. stream of the results
+/// was dropped by library client before it ended).
+#[derive(Debug)]
+pub(crate) struct StreamingSession {
+    state: Arc<SessionState>,
+}
+
+impl StreamingSession {
+    /// Starts the session, name identifies API method (e.g. speech.v1.StreamingRecognize).
+    pub(crate) fn start(name: &'static str) -> Self {
+        let state = SessionState {
+            #[cfg(not(feature = "tracing"))]
+            name,
+            started: Instant::now(),
+            audio_bytes: AtomicU64::new(0),
+            audio_bytes_per_second: Mutex::new(None),
+            responses: AtomicU64::new(0),
+            billed_seconds: Mutex::new(None),
+            error: Mutex::new(None),
+            usage: Mutex::new(None),
+            finished: AtomicBool::new(false),
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "grpc.streaming_session",
+                session = name,
+                outcome = tracing::field::Empty,
+                duration_ms = tracing::field::Empty,
+                audio_bytes = tracing::field::Empty,
+                audio_seconds = tracing::field::Empty,
+                responses = tracing::field::Empty,
+            ),
+        };
+        state.event("session started");
+        StreamingSession {
+            state: Arc::new(state),
+        }
+    }
+
+    /// Runs future (typically the GRPC call) within the session span,
+    /// i.e. RPC span of the call becomes child of the session span.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn instrument<F: Future>(&self, future: F) -> tracing::instrument::Instrumented<F> {
+        use tracing::Instrument;
+        future.instrument(self.state.span.clone())
+    }
+
+    /// Runs future (typically the GRPC call) within the session span,
+    /// i.e. RPC span of the call becomes child of the session span.
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn instrument<F: Future>(&self, future: F) -> F {
+        future
+    }
+
+    /// Wraps stream of the requests so that streamed audio is measured
+    /// and closing of the request stream (half-close) is reported.
+    pub(crate) fn meter_requests<T, S>(&self, requests: S) -> impl Stream<Item = T> + Send + 'static
+    where
+        T: StreamingAudioRequest + Send + 'static,
+        S: Stream<Item = T> + Send + 'static,
+    {
+        let state = self.state.clone();
+        stream! {
+            tokio::pin!(requests);
+            while let Some(request) = requests.next().await {
+                if let Some(rate) = request.audio_bytes_per_second() {
+                    if let Ok(mut audio_bytes_per_second) = state.audio_bytes_per_second.lock() {
+                        *audio_bytes_per_second = Some(rate);
+                    }
+                }
+                state
+                    .audio_bytes
+                    .fetch_add(request.audio_len() as u64, Ordering::Relaxed);
+                yield request;
+            }
+            state.event("request stream closed");
+        }
+    }
+
+    /// Reports lifecycle event of the session.
+    pub(crate) fn event(&self, event: &str) {
+        self.state.event(event);
+    }
+
+    /// Records received response, first response is reported as lifecycle event.
+    pub(crate) fn response_received<R: StreamingResponse>(&self, response: &R) {
+        if self.state.responses.fetch_add(1, Ordering::Relaxed) == 0 {
+            self.state.event("first response received");
+        }
+        if let Some(seconds) = response.billed_seconds() {
+            if let Ok(mut billed_seconds) = self.state.billed_seconds.lock() {
+                *billed_seconds = Some(billed_seconds.unwrap_or_default().max(seconds));
+            }
+        }
+    }
+
+    /// Meters usage of the session once it is finished (if usage meter is set).
+    /// Labels of the call options are captured immediately.
+    pub(crate) fn meter_usage(
+        &self,
+        meter: Option<&UsageMeter>,
+        metric: UsageMetric,
+        options: Option<&CallOptions>,
+    ) {
+        if let (Some(meter), Ok(mut usage)) = (meter, self.state.usage.lock()) {
+            *usage = Some(SessionUsage {
+                meter: meter.clone(),
+                metric,
+                labels: meter.labels(options),
+            });
+        }
+    }
+
+    /// Records error (if any) of the result, error is reported when the session
+    /// is finished or dropped. Result is returned unchanged.
+    pub(crate) fn track<T, E: Display>(
+        &self,
+        result: std::result::Result<T, E>,
+    ) -> std::result::Result<T, E> {
+        if let Err(err) = &result {
+            if let Ok(mut error) = self.state.error.lock() {
+                *error = Some(err.to_string());
+            }
+        }
+        result
+    }
+
+    /// Finishes the session with outcome of the streaming method.
+    pub(crate) fn finish(self, result: std::result::Result<StreamingOutcome, &Error>) {
+        match result {
+            Ok(outcome) => self.state.finish(&format!("{:?}", outcome)),
+            Err(err) => self.state.finish(&format!("Error: {}", err.message)),
+        }
+    }
+}
+
+impl Drop for StreamingSession {
+    fn drop(&mut self) {
+        let error = self.state.error.lock().ok().and_then(|error| error.clone());
+        match error {
+            Some(error) => self.state.finish(&format!("Error: {}", error)),
+            None => self.state.finish("Dropped"),
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_trace_context
+    #[test]
+    fn test_trace_context() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let trace_context = TraceContext::parse(traceparent, Some("congo=t61rcWkgMzE")).unwrap();
+        assert_eq!(trace_context.trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(trace_context.span_id, 0x00f067aa0ba902b7);
+        assert!(trace_context.is_sampled());
+        assert_eq!(trace_context.traceparent(), traceparent);
+        assert_eq!(
+            trace_context.trace_state.as_deref(),
+            Some("congo=t61rcWkgMzE")
+        );
+
+        let child = trace_context.child();
+        assert_eq!(child.trace_id, trace_context.trace_id);
+        assert_ne!(child.span_id, trace_context.span_id);
+
+        assert!(TraceContext::parse(
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            None
+        )
+        .is_err());
+        assert!(TraceContext::parse(
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            None
+        )
+        .is_err());
+        assert!(
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7", None)
+                .is_err()
+        );
+        assert!(TraceContext::parse(&TraceContext::new_root().traceparent(), None).is_ok());
+
+        assert_eq!(
+            parse_grpc_path("/google.cloud.speech.v1p1beta1.Speech/StreamingRecognize"),
+            (
+                "google.cloud.speech.v1p1beta1.Speech".to_string(),
+                "StreamingRecognize".to_string(),
+                "v1p1beta1".to_string()
+            )
+        );
+        assert_eq!(
+            parse_grpc_path("/google.longrunning.Operations/GetOperation").2,
+            ""
+        );
+    }
+}
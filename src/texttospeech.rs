@@ -1,2 +1,5 @@
 //! Contains text-to-speech synthesizer.
 pub mod synthesizer;
+
+/// sentence-level chunked (streaming) synthesis
+pub mod streaming;
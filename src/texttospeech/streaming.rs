@@ -0,0 +1,244 @@
+//! Streaming (sentence-level chunked) speech synthesis. Text of the request
+//! is split into sentences which are synthesized in parallel, audio of the
+//! sentences is then yielded in original order as soon as it is ready. This way
+//! playback of the first sentence can start while the rest of the text is still
+//! being synthesized.
+use crate::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, AudioEncoding, SynthesisInput, SynthesizeSpeechRequest,
+    SynthesizeSpeechResponse,
+};
+use crate::audio::wav::strip_wav_header;
+use crate::call_options::CallOptions;
+use crate::errors::Result;
+use crate::texttospeech::synthesizer::Synthesizer;
+use async_stream::try_stream;
+use futures_core::stream::Stream;
+use std::collections::VecDeque;
+use tokio::task::JoinHandle;
+
+/// Default number of sentences synthesized in parallel.
+pub const DEFAULT_MAX_PARALLEL_REQUESTS: usize = 3;
+
+/// Words ending with dot that do not terminate the sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "e.g", "i.e", "no", "inc",
+    "ltd", "co",
+];
+
+/// Characters that can follow sentence terminal punctuation (e.g. closing quote).
+const CLOSING_CHARS: &[char] = &['"', '\'', ')', ']', '»', '”', '’'];
+
+/// Synthesized audio of single sentence (or other part) of the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioChunk {
+    /// Order of the chunk starting from 0.
+    pub index: usize,
+
+    /// Text synthesized in this chunk.
+    pub text: String,
+
+    /// Synthesized audio. For LINEAR16 encoding WAV header is stripped so that
+    /// chunks can be concatenated (raw PCM samples), other encodings are
+    /// returned as provided by the API.
+    pub audio: Vec<u8>,
+
+    /// True for the last chunk of the text.
+    pub is_last: bool,
+}
+
+fn is_terminal(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？')
+}
+
+fn is_abbreviation(sentence: &str) -> bool {
+    let last_word = sentence
+        .trim_end_matches('.')
+        .rsplit(|c: char| c.is_whitespace())
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    (last_word.chars().count() == 1 && last_word.chars().all(char::is_alphabetic))
+        || ABBREVIATIONS.contains(&last_word.as_str())
+}
+
+/// Splits text into sentences. Sentence ends with terminal punctuation (., !, ?, …)
+/// optionally followed by closing quotes or brackets and whitespace, or with a line break.
+/// Dot after common abbreviations (Mr., Dr., e.g.) or single letter initials does not end the sentence.
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut sentences = vec![];
+    let mut current = String::new();
+    let mut idx = 0;
+
+    let mut push = |current: &mut String| {
+        let sentence = current.trim();
+        if !sentence.is_empty() {
+            sentences.push(sentence.to_string());
+        }
+        current.clear();
+    };
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        if c == '\n' {
+            push(&mut current);
+            idx += 1;
+            continue;
+        }
+        current.push(c);
+        idx += 1;
+
+        if is_terminal(c) {
+            // consume repeated punctuation (?!, ...) and closing characters
+            while idx < chars.len()
+                && (is_terminal(chars[idx]) || CLOSING_CHARS.contains(&chars[idx]))
+            {
+                current.push(chars[idx]);
+                idx += 1;
+            }
+            let at_boundary = idx >= chars.len() || chars[idx].is_whitespace();
+            if at_boundary && !(c == '.' && is_abbreviation(&current)) {
+                push(&mut current);
+            }
+        }
+    }
+    push(&mut current);
+    sentences
+}
+
+/// Aborts all pending synthesis requests when the stream is dropped.
+struct PendingRequests(VecDeque<JoinHandle<Result<SynthesizeSpeechResponse>>>);
+
+impl Drop for PendingRequests {
+    fn drop(&mut self) {
+        for handle in &self.0 {
+            handle.abort();
+        }
+    }
+}
+
+fn sentence_request(request: &SynthesizeSpeechRequest, sentence: &str) -> SynthesizeSpeechRequest {
+    let mut request = request.clone();
+    request.input = Some(SynthesisInput {
+        input_source: Some(InputSource::Text(sentence.to_string())),
+    });
+    request
+}
+
+impl Synthesizer {
+    /// Synthesizes speech in sentence-level chunks. Plain text input is split into sentences
+    /// (see split_sentences) which are synthesized in parallel (at most max_parallel_requests
+    /// requests at a time, defaults to DEFAULT_MAX_PARALLEL_REQUESTS). Returned stream yields
+    /// audio chunks in order of the sentences, first chunk is yielded as soon as
+    /// the first sentence is synthesized. SSML input is not split and is returned as single chunk.
+    /// Pending requests are aborted when the stream is dropped.
+    pub fn synthesize_speech_stream(
+        &self,
+        request: SynthesizeSpeechRequest,
+        max_parallel_requests: Option<usize>,
+        options: Option<CallOptions>,
+    ) -> impl Stream<Item = Result<AudioChunk>> + Send + 'static {
+        let synthesizer = self.clone();
+        // requests are sent from spawned tasks which do not inherit scope of the caller
+        let options = options.or_else(CallOptions::current);
+        let max_parallel_requests = max_parallel_requests
+            .unwrap_or(DEFAULT_MAX_PARALLEL_REQUESTS)
+            .max(1);
+        let strip_header = request
+            .audio_config
+            .as_ref()
+            .map(|config| config.audio_encoding == AudioEncoding::Linear16 as i32)
+            .unwrap_or(false);
+
+        let texts: Vec<String> = match request
+            .input
+            .as_ref()
+            .and_then(|input| input.input_source.as_ref())
+        {
+            Some(InputSource::Text(text)) => split_sentences(text),
+            Some(InputSource::Ssml(ssml)) => vec![ssml.clone()],
+            None => vec![],
+        };
+        // split requests keep input type of original request for SSML (single chunk)
+        let requests: Vec<SynthesizeSpeechRequest> = match request
+            .input
+            .as_ref()
+            .and_then(|input| input.input_source.as_ref())
+        {
+            Some(InputSource::Text(_)) if !texts.is_empty() => texts
+                .iter()
+                .map(|sentence| sentence_request(&request, sentence))
+                .collect(),
+            _ => vec![request.clone()],
+        };
+
+        try_stream! {
+            let total = requests.len();
+            let mut requests = requests.into_iter();
+            let mut pending = PendingRequests(VecDeque::new());
+
+            for index in 0..total {
+                while pending.0.len() < max_parallel_requests {
+                    match requests.next() {
+                        Some(request) => {
+                            let mut synthesizer = synthesizer.clone();
+                            let options = options.clone();
+                            pending.0.push_back(tokio::spawn(async move {
+                                synthesizer.synthesize_speech(request, options).await
+                            }));
+                        }
+                        None => break,
+                    }
+                }
+
+                if let Some(handle) = pending.0.pop_front() {
+                    let response = handle.await??;
+                    let audio = if strip_header {
+                        strip_wav_header(&response.audio_content).to_vec()
+                    } else {
+                        response.audio_content
+                    };
+                    yield AudioChunk {
+                        index,
+                        text: texts.get(index).cloned().unwrap_or_default(),
+                        audio,
+                        is_last: index + 1 == total,
+                    };
+                }
+            }
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    // cargo test -- --show-output test_split_sentences
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Hello Mr. Smith! How are you? I'm fine... Thanks.\nBye"),
+            vec![
+                "Hello Mr. Smith!",
+                "How are you?",
+                "I'm fine...",
+                "Thanks.",
+                "Bye"
+            ]
+        );
+        assert_eq!(
+            split_sentences("It costs 3.5 dollars. J. R. R. Tolkien wrote it, e.g. The Hobbit."),
+            vec![
+                "It costs 3.5 dollars.",
+                "J. R. R. Tolkien wrote it, e.g. The Hobbit."
+            ]
+        );
+        assert_eq!(
+            split_sentences("He said \"stop.\" Then (quietly) left?!  "),
+            vec!["He said \"stop.\"", "Then (quietly) left?!"]
+        );
+        assert!(split_sentences("  \n ").is_empty());
+    }
+}
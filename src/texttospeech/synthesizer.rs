@@ -1,12 +1,19 @@
 //! Text-to-speech synthesizer module.
 use crate::api::grpc::google::cloud::texttospeech::v1::{
-    text_to_speech_client::TextToSpeechClient, ListVoicesRequest, ListVoicesResponse,
-    SynthesizeSpeechRequest, SynthesizeSpeechResponse,
+    text_to_speech_client::TextToSpeechClient,
+    text_to_speech_long_audio_synthesize_client::TextToSpeechLongAudioSynthesizeClient,
+    ListVoicesRequest, ListVoicesResponse, SynthesizeLongAudioMetadata, SynthesizeLongAudioRequest,
+    SynthesizeLongAudioResponse, SynthesizeSpeechRequest, SynthesizeSpeechResponse,
+};
+use crate::call_options::{new_request, CallOptions};
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
 };
-use crate::common::{get_token, new_grpc_channel, new_interceptor, TokenInterceptor};
 use crate::errors::Result;
+use crate::longrunning::{OperationHandle, OperationsClient};
+use crate::metering::{synthesis_usage, PendingUsage, UsageMeter};
+use crate::transport::{ClientConfig, GrpcChannel};
 use tonic::codegen::InterceptedService;
-use tonic::transport::Channel;
 use tonic::Response as TonicResponse;
 
 /// Google speech synthesizer
@@ -14,52 +21,139 @@ use tonic::Response as TonicResponse;
 pub struct Synthesizer {
     /// underlying gRPC Tonic text-to-speech client
     // text_to_speech_client: TextToSpeechClient<Channel>,
-    text_to_speech_client: TextToSpeechClient<InterceptedService<Channel, TokenInterceptor>>,
+    text_to_speech_client: TextToSpeechClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// underlying gRPC Tonic long audio synthesis client
+    long_audio_client:
+        TextToSpeechLongAudioSynthesizeClient<InterceptedService<GrpcChannel, TokenInterceptor>>,
+
+    /// google long running operations client (long audio synthesis)
+    operations_client: OperationsClient,
+
+    /// usage meter of billable units, see metering module
+    usage_meter: Option<UsageMeter>,
 }
 
+/// Handle of long running operation returned by Synthesizer::synthesize_long_audio.
+pub type SynthesizeLongAudioOperation =
+    OperationHandle<SynthesizeLongAudioResponse, SynthesizeLongAudioMetadata>;
+
 impl Synthesizer {
     /// Creates new text-to-speech synthesizer using GCP project JSON credentials
     pub async fn create(
         // Google Cloud Platform JSON credentials for project with Speech APIs enabled
         google_credentials: impl AsRef<str>,
     ) -> Result<Self> {
-        let channel = new_grpc_channel(
+        Synthesizer::create_with_config(
+            google_credentials,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        // Google Cloud Platform JSON credentials for project with Speech APIs enabled
+        google_credentials: impl AsRef<str>,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel = new_grpc_channel_with_config(
             "texttospeech.googleapis.com",
             "https://texttospeech.googleapis.com",
             None,
+            &client_config,
         )
         .await?;
 
-        let token_header_val = get_token(google_credentials)?;
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
 
-        let text_to_speech_client =
-            TextToSpeechClient::with_interceptor(channel, new_interceptor(token_header_val));
+        let text_to_speech_client = TextToSpeechClient::with_interceptor(
+            channel.clone(),
+            new_interceptor(token_header_val.clone()),
+        );
+
+        let long_audio_client = TextToSpeechLongAudioSynthesizeClient::with_interceptor(
+            channel.clone(),
+            new_interceptor(token_header_val.clone()),
+        );
+
+        let operations_client = OperationsClient::new(channel, new_interceptor(token_header_val));
 
         Ok(Synthesizer {
             text_to_speech_client,
+            long_audio_client,
+            operations_client,
+            usage_meter: None,
         })
     }
 
+    /// Sets usage meter recording billable units consumed by this client
+    /// (see metering module). Clones of the synthesizer created afterwards share the meter.
+    pub fn set_usage_meter(&mut self, usage_meter: UsageMeter) {
+        self.usage_meter = Some(usage_meter);
+    }
+
     /// Synthesizes speech synchronously.
     pub async fn synthesize_speech(
         &mut self,
         request: SynthesizeSpeechRequest,
+        options: Option<CallOptions>,
     ) -> Result<SynthesizeSpeechResponse> {
-        let synthesize_speech_req = tonic::Request::new(request);
+        let (metric, characters) = synthesis_usage(request.input.as_ref(), request.voice.as_ref());
+        let options = options.or_else(CallOptions::current);
+        let synthesize_speech_req = new_request(request, options.clone())?;
         let response: TonicResponse<SynthesizeSpeechResponse> = self
             .text_to_speech_client
             .synthesize_speech(synthesize_speech_req)
             .await?;
+        if let Some(usage_meter) = &self.usage_meter {
+            usage_meter.record_with_labels(
+                metric,
+                characters,
+                usage_meter.labels(options.as_ref()),
+            );
+        }
         Ok(response.into_inner())
     }
 
     /// Returns a list of Voice supported for synthesis.
-    pub async fn list_voices(&mut self, request: ListVoicesRequest) -> Result<ListVoicesResponse> {
-        let list_voices_req = tonic::Request::new(request);
+    pub async fn list_voices(
+        &mut self,
+        request: ListVoicesRequest,
+        options: Option<CallOptions>,
+    ) -> Result<ListVoicesResponse> {
+        let list_voices_req = new_request(request, options)?;
         let response: TonicResponse<ListVoicesResponse> = self
             .text_to_speech_client
             .list_voices(list_voices_req)
             .await?;
         Ok(response.into_inner())
     }
+
+    /// Synthesizes long form text asynchronously. Synthesized audio is written
+    /// into Cloud Storage (output_gcs_uri of the request, bucket must exist).
+    /// Parent of the request is specified in format projects/<Project ID>/locations/<Location ID>.
+    /// Returns typed operation handle which can be used to check progress
+    /// (metadata().progress_percentage), cancel or wait for completion of the synthesis.
+    pub async fn synthesize_long_audio(
+        &mut self,
+        request: SynthesizeLongAudioRequest,
+        options: Option<CallOptions>,
+    ) -> Result<SynthesizeLongAudioOperation> {
+        let (metric, characters) = synthesis_usage(request.input.as_ref(), request.voice.as_ref());
+        let options = options.or_else(CallOptions::current);
+        let response = self
+            .long_audio_client
+            .synthesize_long_audio(new_request(request, options.clone())?)
+            .await?;
+        // characters are billed once the synthesis succeeds (see OperationHandle::wait)
+        let usage = self.usage_meter.as_ref().map(|usage_meter| {
+            PendingUsage::new(usage_meter, metric, Some(characters), options.as_ref())
+        });
+        Ok(
+            OperationHandle::new(response.into_inner(), self.operations_client.clone())
+                .with_usage(usage),
+        )
+    }
 }
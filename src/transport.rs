@@ -0,0 +1,447 @@
+//! Contains GRPC transport shared by all clients of this crate. Transport wraps
+//! tonic channel so that every call is measured (see telemetry module)
+//! and W3C trace context is propagated into GRPC metadata.
+//!
+//! Transport is configured by ClientConfig passed to client constructors (*_with_config functions):
+//!
+//! ```ignore
+//! let config = ClientConfig::new().rate_limiter(limiter);
+//! let recognizer = Recognizer::create_synchronous_recognizer_with_config(credentials, config).await?;
+//! ```
+//!
+//! Constructors without explicit config (create functions) use config of the current scope (if any),
+//! e.g. ClientConfig::new().rate_limiter(limiter).scope(Synthesizer::create(credentials)).await
+use crate::call_options::CallOptions;
+use crate::circuit_breaker::{CircuitBreaker, CircuitCall, CircuitDecision};
+use crate::rate_limit::{RateLimitPermit, RateLimiter};
+use crate::recording::{CallRecorder, Recorder, Replayer};
+use crate::telemetry::{RpcCall, TraceContext, TRACEPARENT_HEADER, TRACESTATE_HEADER};
+use http_body::combinators::UnsyncBoxBody;
+use http_body::Body;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::codegen::{http, BoxFuture, Bytes, Service, StdError};
+use tonic::transport::Channel;
+use tonic::{Code, Status};
+
+/// Body of GRPC response returned by the transport.
+pub type ResponseBody = UnsyncBoxBody<Bytes, StdError>;
+
+tokio::task_local! {
+    static CURRENT_CLIENT_CONFIG: ClientConfig;
+}
+
+/// Configuration of the transport of the clients created within its scope.
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfig {
+    /// Client-side rate limiter, see rate_limit module.
+    pub rate_limiter: Option<RateLimiter>,
+
+    /// Circuit breaker guarding primary endpoint, see circuit_breaker module.
+    pub circuit_breaker: Option<CircuitBreaker>,
+
+    /// URL of primary endpoint (e.g. https://eu-speech.googleapis.com) overriding default endpoint of the client.
+    pub endpoint: Option<String>,
+
+    /// URL of fallback endpoint used while circuit breaker is open.
+    pub fallback_endpoint: Option<String>,
+
+    /// Recorder capturing the calls, see recording module.
+    pub recorder: Option<Recorder>,
+
+    /// Replayer of recorded calls. Clients replaying the calls do not connect to the API.
+    pub replayer: Option<Replayer>,
+}
+
+impl ClientConfig {
+    /// Creates empty config.
+    pub fn new() -> Self {
+        ClientConfig::default()
+    }
+
+    /// Sets client-side rate limiter. Limiter can be shared by multiple clients.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Sets circuit breaker guarding primary endpoint.
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Overrides default endpoint of the client, e.g. regional endpoint
+    /// https://eu-speech.googleapis.com instead of https://speech.googleapis.com.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sets fallback endpoint used while circuit breaker is open. If no circuit breaker
+    /// is set breaker with default settings is used.
+    pub fn fallback_endpoint(mut self, fallback_endpoint: impl Into<String>) -> Self {
+        self.fallback_endpoint = Some(fallback_endpoint.into());
+        self
+    }
+
+    /// Sets recorder capturing the calls of the clients.
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Replays recorded calls instead of calling the API.
+    pub fn replay(mut self, replayer: Replayer) -> Self {
+        self.replayer = Some(replayer);
+        self
+    }
+
+    /// Returns true if the calls are replayed instead of calling the API.
+    pub fn is_replay(&self) -> bool {
+        self.replayer.is_some()
+    }
+
+    /// Runs future (typically client constructor) within the scope of this config.
+    /// Scope is task local, i.e. it is not inherited by spawned tasks.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CLIENT_CONFIG.scope(self, future).await
+    }
+
+    /// Returns config of the current scope (if any).
+    pub fn current() -> Option<ClientConfig> {
+        CURRENT_CLIENT_CONFIG.try_with(|config| config.clone()).ok()
+    }
+}
+
+/// GRPC channel used by all clients of this crate. Cloning is cheap,
+/// all clones share the same underlying connection.
+#[derive(Debug, Clone)]
+pub struct GrpcChannel {
+    channel: Option<Channel>,
+    fallback: Option<Channel>,
+    config: ClientConfig,
+}
+
+impl GrpcChannel {
+    /// Wraps tonic channel.
+    pub fn new(channel: Channel) -> Self {
+        GrpcChannel {
+            channel: Some(channel),
+            fallback: None,
+            config: ClientConfig::default(),
+        }
+    }
+
+    /// Creates channel without connection, used when the calls are replayed.
+    pub(crate) fn offline(config: ClientConfig) -> Self {
+        GrpcChannel {
+            channel: None,
+            fallback: None,
+            config,
+        }
+    }
+
+    /// Sets channel of fallback endpoint used while circuit breaker is open.
+    pub fn with_fallback(mut self, fallback: Channel) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Sets transport configuration.
+    pub fn with_config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl From<Channel> for GrpcChannel {
+    fn from(channel: Channel) -> Self {
+        GrpcChannel::new(channel)
+    }
+}
+
+/// Propagates W3C trace context of the current call (if any) unless
+/// the request already carries traceparent header.
+fn inject_trace_context(request: &mut http::Request<BoxBody>, trace_context: &TraceContext) {
+    if request.headers().contains_key(TRACEPARENT_HEADER) {
+        return;
+    }
+    if let Ok(traceparent) = http::HeaderValue::from_str(&trace_context.traceparent()) {
+        request
+            .headers_mut()
+            .insert(TRACEPARENT_HEADER, traceparent);
+    }
+    if let Some(trace_state) = &trace_context.trace_state {
+        if let Ok(trace_state) = http::HeaderValue::from_str(trace_state) {
+            request.headers_mut().insert(TRACESTATE_HEADER, trace_state);
+        }
+    }
+}
+
+fn grpc_status_code(headers: &http::HeaderMap) -> Option<Code> {
+    headers
+        .get("grpc-status")
+        .map(|code| Code::from_bytes(code.as_bytes()))
+}
+
+impl Service<http::Request<BoxBody>> for GrpcChannel {
+    type Response = http::Response<ResponseBody>;
+    type Error = StdError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // channel is driven to readiness by the call once it is allowed by the rate limiter,
+        // i.e. calls queued by the limiter do not hold buffer slots of the channel
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: http::Request<BoxBody>) -> Self::Future {
+        // explicit call options are passed in request extensions, see call_options::new_request
+        let trace_context = TraceContext::for_options(request.extensions().get::<CallOptions>());
+        if let Some(trace_context) = &trace_context {
+            inject_trace_context(&mut request, trace_context);
+        }
+
+        let path = request.uri().path().to_string();
+        let mut call = RpcCall::start(&path, trace_context.as_ref());
+        let bytes_sent = call.bytes_sent_counter();
+        let recording = self.config.recorder.as_ref().map(|r| r.start(&path));
+        let request_recording = recording.clone();
+        let request = request.map(|body| {
+            body.map_data(move |data| {
+                bytes_sent.fetch_add(data.len() as u64, Ordering::Relaxed);
+                if let Some(recording) = &request_recording {
+                    recording.record_request(&data);
+                }
+                data
+            })
+            .boxed_unsync()
+        });
+
+        if let Some(replayer) = self.config.replayer.clone() {
+            return Box::pin(async move {
+                match replayer.replay(request) {
+                    Ok(response) => Ok(response.map(|body| {
+                        InstrumentedBody::new(body, call, None, None, None, recording)
+                            .boxed_unsync()
+                    })),
+                    Err(status) => {
+                        finish_failed(&mut call, recording, status.code());
+                        Err(status as StdError)
+                    }
+                }
+            });
+        }
+
+        let (route, circuit) = match self.config.circuit_breaker.as_ref().map(|b| b.decide()) {
+            Some(CircuitDecision::Open) => match &self.fallback {
+                Some(fallback) => (Route::Fallback(fallback.clone()), None),
+                None => (Route::Rejected, None),
+            },
+            Some(CircuitDecision::Primary(circuit)) => (Route::Primary, Some(circuit)),
+            None => (Route::Primary, None),
+        };
+        let channel = match route {
+            Route::Primary => self.channel.clone(),
+            Route::Fallback(fallback) => {
+                log::debug!("GrpcChannel: circuit breaker open, calling fallback endpoint");
+                Some(fallback)
+            }
+            Route::Rejected => None,
+        };
+        let rate_limiter = self.config.rate_limiter.clone();
+        Box::pin(async move {
+            let mut channel = match channel {
+                Some(channel) => channel,
+                None => {
+                    finish_failed(&mut call, recording, Code::Unavailable);
+                    return Err(Status::unavailable("circuit breaker open").into());
+                }
+            };
+            // permit is acquired before the channel is driven to readiness
+            let permit = match rate_limiter {
+                Some(rate_limiter) => match rate_limiter.acquire(&path).await {
+                    Ok(permit) => Some(permit),
+                    Err(status) => {
+                        finish_failed(&mut call, recording, status.code());
+                        return Err(status.into());
+                    }
+                },
+                None => None,
+            };
+            let ready = std::future::poll_fn(|cx| {
+                Service::<http::Request<BoxBody>>::poll_ready(&mut channel, cx)
+            })
+            .await;
+            let result = match ready {
+                Ok(()) => channel.call(request).await,
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(response) => {
+                    // trailers-only response carries GRPC status in headers
+                    let status = grpc_status_code(response.headers());
+                    if let (Some(recording), Some(_)) = (&recording, status) {
+                        recording.record_trailers(response.headers());
+                    }
+                    Ok(response.map(|body| {
+                        let body = body.map_err(StdError::from).boxed_unsync();
+                        InstrumentedBody::new(body, call, status, permit, circuit, recording)
+                            .boxed_unsync()
+                    }))
+                }
+                Err(err) => {
+                    finish_failed(&mut call, recording, Code::Unavailable);
+                    if let Some(permit) = permit {
+                        permit.finish(Code::Unavailable);
+                    }
+                    if let Some(circuit) = circuit {
+                        circuit.finish(Code::Unavailable);
+                    }
+                    Err(err.into())
+                }
+            }
+        })
+    }
+}
+
+/// Finishes measurement (and recording) of the call which failed before any response was received.
+fn finish_failed(call: &mut RpcCall, recording: Option<CallRecorder>, code: Code) {
+    call.finish(code);
+    if let Some(recording) = recording {
+        recording.finish(code);
+    }
+}
+
+/// Response body which measures received bytes and finishes the RPC
+/// measurement with GRPC status once the trailers are received.
+struct InstrumentedBody {
+    inner: ResponseBody,
+    call: RpcCall,
+    status: Option<Code>,
+    permit: Option<RateLimitPermit>,
+    circuit: Option<CircuitCall>,
+    recording: Option<CallRecorder>,
+}
+
+/// Endpoint the call is routed to.
+enum Route {
+    Primary,
+    Fallback(Channel),
+    Rejected,
+}
+
+impl InstrumentedBody {
+    fn new(
+        inner: ResponseBody,
+        call: RpcCall,
+        status: Option<Code>,
+        permit: Option<RateLimitPermit>,
+        circuit: Option<CircuitCall>,
+        recording: Option<CallRecorder>,
+    ) -> Self {
+        InstrumentedBody {
+            inner,
+            call,
+            status,
+            permit,
+            circuit,
+            recording,
+        }
+    }
+
+    fn finish(&mut self, code: Code) {
+        self.call.finish(code);
+        if let Some(permit) = self.permit.take() {
+            permit.finish(code);
+        }
+        if let Some(circuit) = self.circuit.take() {
+            circuit.finish(code);
+        }
+        if let Some(recording) = self.recording.take() {
+            recording.finish(code);
+        }
+    }
+}
+
+impl Body for InstrumentedBody {
+    type Data = Bytes;
+    type Error = StdError;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_data(cx) {
+            Poll::Ready(Some(Ok(data))) => {
+                this.call.record_received(data.len());
+                if let Some(recording) = &this.recording {
+                    recording.record_response(&data);
+                }
+                Poll::Ready(Some(Ok(data)))
+            }
+            Poll::Ready(Some(Err(err))) => {
+                this.finish(Code::Unknown);
+                Poll::Ready(Some(Err(err)))
+            }
+            Poll::Ready(None) => {
+                if let Some(status) = this.status {
+                    this.finish(status);
+                }
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_trailers(cx) {
+            Poll::Ready(Ok(trailers)) => {
+                let status = trailers
+                    .as_ref()
+                    .and_then(grpc_status_code)
+                    .or(this.status)
+                    .unwrap_or(Code::Unknown);
+                if let (Some(recording), Some(trailers)) = (&this.recording, &trailers) {
+                    recording.record_trailers(trailers);
+                }
+                this.finish(status);
+                Poll::Ready(Ok(trailers))
+            }
+            Poll::Ready(Err(err)) => {
+                this.finish(Code::Unknown);
+                Poll::Ready(Err(err))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for InstrumentedBody {
+    fn drop(&mut self) {
+        // trailers-only response (e.g. error status) is not read to the end by tonic
+        if let Some(status) = self.status {
+            self.finish(status);
+        }
+        if let Some(recording) = self.recording.take() {
+            recording.finish(Code::Cancelled);
+        }
+    }
+}
@@ -0,0 +1,875 @@
+//! Contains voice agent pipeline orchestrating speech-to-text, dialogflow and text-to-speech.
+//! Voice agent consumes inbound audio stream (e.g. audio of the phone call), recognizes
+//! caller utterances, sends them to dialogflow and synthesizes dialogflow responses
+//! into outbound audio. Voice agent emits outbound audio together with events
+//! describing the conversation (transcripts, detected intents, parameters, end of conversation).
+//!
+//! Underlying services are abstracted by traits SpeechToText, DialogService and TextToSpeech
+//! so that pipeline can be tested with fake services. Implementations backed by
+//! Recognizer, SessionsClient and Synthesizer are provided in this module as well.
+use crate::api::grpc::google::cloud::dialogflow::v2beta1::{
+    query_input::Input, DetectIntentRequest, DetectIntentResponse, QueryInput, TextInput,
+};
+use crate::api::grpc::google::cloud::speechtotext::v1::StreamingRecognitionConfig;
+use crate::api::grpc::google::cloud::texttospeech::v1::{
+    synthesis_input::InputSource, AudioConfig, SynthesisInput, SynthesizeSpeechRequest,
+    VoiceSelectionParams,
+};
+use crate::api::protobuf::struct_to_json;
+use crate::audio::vad::{Vad, VadEvent};
+use crate::call_options::CallOptions;
+use crate::common::{
+    get_token_with_config, new_grpc_channel_with_config, new_interceptor, TokenInterceptor,
+};
+use crate::dialogflow::is_eoc;
+use crate::dialogflow::sessions_client::SessionsClient;
+use crate::errors::{Error, Result};
+use crate::speechtotext::recognizer::Recognizer;
+use crate::texttospeech::synthesizer::Synthesizer;
+use crate::transport::{ClientConfig, GrpcChannel};
+use log::*;
+use serde_json::Value as JsonValue;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+
+const SPEECH_API_DOMAIN: &str = "speech.googleapis.com";
+const SPEECH_API_URL: &str = "https://speech.googleapis.com";
+
+/// Boxed future returned by voice agent service traits.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Audio sink and transcript receiver of single recognition (one conversational turn).
+pub type RecognitionSession = (mpsc::Sender<Vec<u8>>, mpsc::Receiver<Result<Transcript>>);
+
+/// Recognized (interim or final) transcript of caller utterance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub text: String,
+    pub is_final: bool,
+    pub confidence: f32,
+}
+
+/// Speech recognition service used by voice agent.
+pub trait SpeechToText: Send + Sync {
+    /// Starts streaming recognition of single utterance. Audio is sent into
+    /// returned sender, transcripts are delivered via returned receiver. Recognition
+    /// should end (receiver closed) after final transcript is delivered or when audio sender is dropped.
+    fn start_recognition(&self) -> BoxFuture<'_, Result<RecognitionSession>>;
+}
+
+/// Conversational service (dialogflow) used by voice agent.
+pub trait DialogService: Send + Sync {
+    /// Detects intent of caller utterance.
+    fn detect_intent(&self, text: String) -> BoxFuture<'_, Result<DetectIntentResponse>>;
+}
+
+/// Speech synthesis service used by voice agent.
+pub trait TextToSpeech: Send + Sync {
+    /// Synthesizes text into audio.
+    fn synthesize(&self, text: String) -> BoxFuture<'_, Result<Vec<u8>>>;
+}
+
+/// Events emitted by voice agent.
+#[derive(Debug, Clone)]
+pub enum VoiceAgentEvent {
+    /// Interim or final transcript of caller utterance.
+    Transcript(Transcript),
+
+    /// Caller started to speak while agent response was still playing.
+    /// Playback of outbound audio should be stopped.
+    BargeIn,
+
+    /// Caller did not say anything within configured no input timeout
+    /// (measured from the end of agent response playback).
+    NoInput,
+
+    /// Intent detected by dialogflow.
+    Intent {
+        display_name: String,
+        confidence: f32,
+        fulfillment_text: String,
+        response: Box<DetectIntentResponse>,
+    },
+
+    /// Parameters of detected intent.
+    Parameters(JsonValue),
+
+    /// Outbound (synthesized) audio which should be played to caller.
+    Audio(Vec<u8>),
+
+    /// Dialogflow signaled end of conversation. This is the last event emitted.
+    EndOfConversation,
+}
+
+/// Hooks which can be used to customize behavior of voice agent.
+/// All hooks have default (no-op) implementation.
+pub trait VoiceAgentHooks: Send {
+    /// Called with final transcript before it is sent to dialog service.
+    /// Text can be modified, returning false skips the turn.
+    fn before_detect_intent(&mut self, _text: &mut String) -> bool {
+        true
+    }
+
+    /// Called with dialog service response before fulfillment text is synthesized.
+    fn after_detect_intent(&mut self, _response: &mut DetectIntentResponse) {}
+
+    /// Called with text to be synthesized. Text can be modified, returning false skips synthesis.
+    fn before_synthesize(&mut self, _text: &mut String) -> bool {
+        true
+    }
+
+    /// Called when caller did not say anything within no input timeout. Returned text
+    /// (if any) is sent to dialog service as if it was said by the caller.
+    fn on_no_input(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// Hooks doing nothing.
+#[derive(Debug, Default)]
+pub struct NoHooks;
+impl VoiceAgentHooks for NoHooks {}
+
+/// Voice agent configuration.
+#[derive(Debug, Clone)]
+pub struct VoiceAgentConfig {
+    /// If caller starts to speak during playback of agent response, BargeIn event
+    /// is emitted and caller utterance is processed. If disabled, inbound audio
+    /// is ignored until the playback is finished.
+    pub barge_in: bool,
+
+    /// Maximal time to wait for caller to start speaking in single turn. Measured from
+    /// the end of agent response playback, cancelled by first (interim) transcript
+    /// or speech start detected by VAD (see VoiceAgent::with_vad).
+    pub no_input_timeout: Duration,
+
+    /// Maximal time to wait for dialog service and speech synthesis in single turn.
+    pub response_timeout: Duration,
+
+    /// Byte rate of outbound audio (e.g. 32000 for LINEAR16 16kHz mono). Used to
+    /// estimate playback duration of agent responses. If not specified, barge-in
+    /// detection and suppression of inbound audio during playback is disabled.
+    pub outbound_bytes_per_sec: Option<u32>,
+}
+
+impl Default for VoiceAgentConfig {
+    fn default() -> Self {
+        VoiceAgentConfig {
+            barge_in: true,
+            no_input_timeout: Duration::from_secs(10),
+            response_timeout: Duration::from_secs(10),
+            outbound_bytes_per_sec: None,
+        }
+    }
+}
+
+/// Voice agent pipeline.
+pub struct VoiceAgent<S: SpeechToText, D: DialogService, T: TextToSpeech> {
+    speech_to_text: S,
+    dialog_service: D,
+    text_to_speech: T,
+    config: VoiceAgentConfig,
+    hooks: Box<dyn VoiceAgentHooks>,
+    /// optional VAD detecting speech start in inbound audio
+    vad: Option<Vad>,
+    /// estimated time when playback of last agent response ends
+    playback_until: Option<Instant>,
+}
+
+impl<S: SpeechToText, D: DialogService, T: TextToSpeech> VoiceAgent<S, D, T> {
+    /// Creates new voice agent from provided services.
+    pub fn new(
+        speech_to_text: S,
+        dialog_service: D,
+        text_to_speech: T,
+        config: VoiceAgentConfig,
+    ) -> Self {
+        VoiceAgent {
+            speech_to_text,
+            dialog_service,
+            text_to_speech,
+            config,
+            hooks: Box::new(NoHooks),
+            vad: None,
+            playback_until: None,
+        }
+    }
+
+    /// Sets hooks customizing voice agent behavior.
+    pub fn with_hooks(mut self, hooks: impl VoiceAgentHooks + 'static) -> Self {
+        self.hooks = Box::new(hooks);
+        self
+    }
+
+    /// Sets VAD used to detect start of caller speech in inbound audio, speech start
+    /// cancels no input timeout before the first transcript is received.
+    /// VAD is used for detection only, inbound audio is forwarded to recognizer unchanged.
+    pub fn with_vad(mut self, vad: Vad) -> Self {
+        self.vad = Some(vad);
+        self
+    }
+
+    /// Synthesizes text (e.g. welcome prompt) and emits it as outbound audio.
+    pub async fn say(
+        &mut self,
+        text: String,
+        events: &mpsc::Sender<VoiceAgentEvent>,
+    ) -> Result<()> {
+        let mut text = text;
+        if text.is_empty() || !self.hooks.before_synthesize(&mut text) {
+            return Ok(());
+        }
+        let audio = tokio::time::timeout(
+            self.config.response_timeout,
+            self.text_to_speech.synthesize(text),
+        )
+        .await
+        .map_err(|_| {
+            crate::errors::Error::new("VoiceAgent.say: synthesis timed out".to_string())
+        })??;
+        if let Some(bytes_per_sec) = self.config.outbound_bytes_per_sec {
+            let playback = Duration::from_secs_f64(audio.len() as f64 / bytes_per_sec as f64);
+            let start = self
+                .playback_until
+                .unwrap_or_else(Instant::now)
+                .max(Instant::now());
+            self.playback_until = Some(start + playback);
+        }
+        emit(events, VoiceAgentEvent::Audio(audio)).await;
+        Ok(())
+    }
+
+    fn is_playing(&self) -> bool {
+        matches!(self.playback_until, Some(until) if until > Instant::now())
+    }
+
+    /// Runs the conversation until end of conversation is signaled by dialogflow,
+    /// inbound audio stream is closed or events receiver is dropped. Each conversational
+    /// turn consists of recognition of caller utterance, intent detection and synthesis
+    /// of dialogflow response.
+    pub async fn run(
+        &mut self,
+        mut inbound_audio: mpsc::Receiver<Vec<u8>>,
+        events: mpsc::Sender<VoiceAgentEvent>,
+    ) -> Result<()> {
+        let mut inbound_closed = false;
+
+        while !inbound_closed && !events.is_closed() {
+            let (audio_sink, mut transcripts) = self.speech_to_text.start_recognition().await?;
+            let mut audio_sink = Some(audio_sink);
+            // no input timer starts once the agent response is played
+            let playback_end = self
+                .playback_until
+                .unwrap_or_else(Instant::now)
+                .max(Instant::now());
+            let no_input = sleep_until(playback_end + self.config.no_input_timeout);
+            tokio::pin!(no_input);
+            let mut speech_detected = false;
+            let mut barged_in = false;
+
+            let query_text: Option<String> = loop {
+                tokio::select! {
+                    chunk = inbound_audio.recv(), if !inbound_closed => match chunk {
+                        Some(chunk) => {
+                            if self.config.barge_in || !self.is_playing() {
+                                if let Some(vad) = &mut self.vad {
+                                    let output = vad.process(&chunk);
+                                    if output.events.iter().any(|event| matches!(event, VadEvent::SpeechStart { .. })) {
+                                        speech_detected = true;
+                                    }
+                                }
+                                if let Some(sink) = &audio_sink {
+                                    if sink.send(chunk).await.is_err() {
+                                        audio_sink = None;
+                                    }
+                                }
+                            }
+                        }
+                        None => {
+                            trace!("VoiceAgent.run: inbound audio closed");
+                            inbound_closed = true;
+                            // closing audio sink so that recognizer can finalize the utterance
+                            audio_sink = None;
+                        }
+                    },
+                    transcript = transcripts.recv() => match transcript {
+                        Some(Ok(transcript)) => {
+                            speech_detected = true;
+                            if !barged_in && self.is_playing() {
+                                barged_in = true;
+                                self.playback_until = None;
+                                emit(&events, VoiceAgentEvent::BargeIn).await;
+                            }
+                            let is_final = transcript.is_final;
+                            let text = transcript.text.clone();
+                            emit(&events, VoiceAgentEvent::Transcript(transcript)).await;
+                            if is_final {
+                                break Some(text);
+                            }
+                        }
+                        Some(Err(err)) => return Err(err),
+                        None => break None,
+                    },
+                    _ = &mut no_input, if !speech_detected => {
+                        emit(&events, VoiceAgentEvent::NoInput).await;
+                        break self.hooks.on_no_input();
+                    }
+                }
+            };
+            drop(audio_sink);
+
+            if let Some(mut text) = query_text {
+                if text.is_empty() || !self.hooks.before_detect_intent(&mut text) {
+                    continue;
+                }
+                if self.handle_query(text, &events).await? {
+                    emit(&events, VoiceAgentEvent::EndOfConversation).await;
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends query to dialog service, emits intent events and synthesizes response.
+    /// Returns true if end of conversation was detected.
+    async fn handle_query(
+        &mut self,
+        text: String,
+        events: &mpsc::Sender<VoiceAgentEvent>,
+    ) -> Result<bool> {
+        let mut response = tokio::time::timeout(
+            self.config.response_timeout,
+            self.dialog_service.detect_intent(text),
+        )
+        .await
+        .map_err(|_| {
+            crate::errors::Error::new(
+                "VoiceAgent.handle_query: detect intent timed out".to_string(),
+            )
+        })??;
+        self.hooks.after_detect_intent(&mut response);
+
+        let mut fulfillment_text = "".to_string();
+        if let Some(query_result) = &response.query_result {
+            fulfillment_text = query_result.fulfillment_text.clone();
+            let (display_name, confidence) = match &query_result.intent {
+                Some(intent) => (
+                    intent.display_name.clone(),
+                    query_result.intent_detection_confidence,
+                ),
+                None => ("".to_string(), 0.0),
+            };
+            let parameters = query_result.parameters.as_ref().map(struct_to_json);
+            emit(
+                events,
+                VoiceAgentEvent::Intent {
+                    display_name,
+                    confidence,
+                    fulfillment_text: fulfillment_text.clone(),
+                    response: Box::new(response.clone()),
+                },
+            )
+            .await;
+            if let Some(parameters) = parameters {
+                emit(events, VoiceAgentEvent::Parameters(parameters)).await;
+            }
+        }
+
+        self.say(fulfillment_text, events).await?;
+        Ok(is_eoc(&response))
+    }
+}
+
+async fn emit(events: &mpsc::Sender<VoiceAgentEvent>, event: VoiceAgentEvent) {
+    if events.send(event).await.is_err() {
+        trace!("VoiceAgent: events receiver dropped");
+    }
+}
+
+/// Speech to text service backed by streaming Recognizer (v1 API).
+/// New streaming recognition is started for every conversational turn, GRPC channel
+/// is created only once. Streaming config should have single_utterance and interim_results enabled.
+#[derive(Clone)]
+pub struct RecognizerSpeechToText {
+    channel: GrpcChannel,
+    token_interceptor: TokenInterceptor,
+    config: StreamingRecognitionConfig,
+    options: Option<CallOptions>,
+}
+
+impl RecognizerSpeechToText {
+    /// Connects speech-to-text API using GCP project JSON credentials.
+    pub async fn create(
+        google_credentials: impl AsRef<str>,
+        config: StreamingRecognitionConfig,
+    ) -> Result<Self> {
+        RecognizerSpeechToText::create_with_config(
+            google_credentials,
+            config,
+            ClientConfig::current().unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Same as create with explicit client config (see transport module)
+    /// instead of the config of the current scope.
+    pub async fn create_with_config(
+        google_credentials: impl AsRef<str>,
+        config: StreamingRecognitionConfig,
+        client_config: ClientConfig,
+    ) -> Result<Self> {
+        let channel =
+            new_grpc_channel_with_config(SPEECH_API_DOMAIN, SPEECH_API_URL, None, &client_config)
+                .await?;
+        let token_header_val = get_token_with_config(google_credentials, &client_config)?;
+        Ok(RecognizerSpeechToText {
+            channel,
+            token_interceptor: new_interceptor(token_header_val),
+            config,
+            options: None,
+        })
+    }
+
+    /// Sets call options of the streaming recognitions.
+    pub fn with_call_options(mut self, options: CallOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+impl SpeechToText for RecognizerSpeechToText {
+    fn start_recognition(&self) -> BoxFuture<'_, Result<RecognitionSession>> {
+        Box::pin(async move {
+            let mut recognizer = Recognizer::new_streaming_recognizer(
+                self.channel.clone(),
+                self.token_interceptor.clone(),
+                self.config.clone(),
+                None,
+            )
+            .await?;
+            let recognizer_sink = recognizer.take_audio_sink().ok_or_else(|| {
+                Error::new(
+                    "RecognizerSpeechToText.start_recognition: audio sink not available"
+                        .to_string(),
+                )
+            })?;
+            let mut result_receiver = recognizer.get_streaming_result_receiver(None);
+
+            let (audio_sender, mut audio_receiver) = mpsc::channel::<Vec<u8>>(1000);
+            let (transcript_sender, transcript_receiver) =
+                mpsc::channel::<Result<Transcript>>(1000);
+
+            tokio::spawn(async move {
+                while let Some(chunk) = audio_receiver.recv().await {
+                    let request = Recognizer::streaming_request_from_bytes(chunk);
+                    if recognizer_sink.send(request).await.is_err() {
+                        break;
+                    }
+                }
+            });
+
+            let error_sender = transcript_sender.clone();
+            let options = self.options.clone();
+            tokio::spawn(async move {
+                if let Err(err) = recognizer.streaming_recognize(options).await {
+                    let _ = error_sender.send(Err(err)).await;
+                }
+            });
+
+            tokio::spawn(async move {
+                while let Some(response) = result_receiver.recv().await {
+                    for result in response.results {
+                        if let Some(alternative) = result.alternatives.first() {
+                            let transcript = Transcript {
+                                text: alternative.transcript.clone(),
+                                is_final: result.is_final,
+                                confidence: alternative.confidence,
+                            };
+                            if transcript_sender.send(Ok(transcript)).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            });
+
+            Ok((audio_sender, transcript_receiver))
+        })
+    }
+}
+
+/// Dialog service backed by dialogflow SessionsClient (text queries).
+#[derive(Debug, Clone)]
+pub struct SessionsDialogService {
+    sessions_client: SessionsClient,
+    session: String,
+    language_code: String,
+    options: Option<CallOptions>,
+}
+
+impl SessionsDialogService {
+    pub fn new(
+        sessions_client: SessionsClient,
+        session: impl Into<String>,
+        language_code: impl Into<String>,
+    ) -> Self {
+        SessionsDialogService {
+            sessions_client,
+            session: session.into(),
+            language_code: language_code.into(),
+            options: None,
+        }
+    }
+
+    /// Sets call options of the detect intent calls.
+    pub fn with_call_options(mut self, options: CallOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+impl DialogService for SessionsDialogService {
+    fn detect_intent(&self, text: String) -> BoxFuture<'_, Result<DetectIntentResponse>> {
+        let mut sessions_client = self.sessions_client.clone();
+        let request = DetectIntentRequest {
+            session: self.session.clone(),
+            query_params: None,
+            query_input: Some(QueryInput {
+                input: Some(Input::Text(TextInput {
+                    text,
+                    language_code: self.language_code.clone(),
+                })),
+            }),
+            output_audio_config: None,
+            output_audio_config_mask: None,
+            input_audio: vec![],
+        };
+        let options = self.options.clone();
+        Box::pin(async move { sessions_client.detect_intent(request, options).await })
+    }
+}
+
+/// Speech synthesis service backed by Synthesizer.
+#[derive(Debug, Clone)]
+pub struct SynthesizerTextToSpeech {
+    synthesizer: Synthesizer,
+    voice: VoiceSelectionParams,
+    audio_config: AudioConfig,
+    options: Option<CallOptions>,
+}
+
+impl SynthesizerTextToSpeech {
+    pub fn new(
+        synthesizer: Synthesizer,
+        voice: VoiceSelectionParams,
+        audio_config: AudioConfig,
+    ) -> Self {
+        SynthesizerTextToSpeech {
+            synthesizer,
+            voice,
+            audio_config,
+            options: None,
+        }
+    }
+
+    /// Sets call options of the synthesis calls.
+    pub fn with_call_options(mut self, options: CallOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+impl TextToSpeech for SynthesizerTextToSpeech {
+    fn synthesize(&self, text: String) -> BoxFuture<'_, Result<Vec<u8>>> {
+        let mut synthesizer = self.synthesizer.clone();
+        let request = SynthesizeSpeechRequest {
+            input: Some(SynthesisInput {
+                input_source: Some(InputSource::Text(text)),
+            }),
+            voice: Some(self.voice.clone()),
+            audio_config: Some(self.audio_config.clone()),
+        };
+        let options = self.options.clone();
+        Box::pin(async move {
+            let response = synthesizer.synthesize_speech(request, options).await?;
+            Ok(response.audio_content)
+        })
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+    #[allow(unused_imports)]
+    use crate::api::grpc::google::cloud::dialogflow::v2beta1::{Intent, QueryResult};
+    #[allow(unused_imports)]
+    use crate::api::protobuf::json_to_struct;
+    #[allow(unused_imports)]
+    use std::collections::VecDeque;
+    #[allow(unused_imports)]
+    use std::sync::{Arc, Mutex};
+
+    /// Recognizes one utterance from queue per turn once any audio is received.
+    #[allow(dead_code)]
+    struct FakeSpeechToText {
+        utterances: Arc<Mutex<VecDeque<String>>>,
+        /// delay between interim and final transcript
+        final_delay: Duration,
+    }
+
+    impl SpeechToText for FakeSpeechToText {
+        fn start_recognition(&self) -> BoxFuture<'_, Result<RecognitionSession>> {
+            let utterance = self.utterances.lock().unwrap().pop_front();
+            let final_delay = self.final_delay;
+            Box::pin(async move {
+                let (audio_sender, mut audio_receiver) = mpsc::channel::<Vec<u8>>(100);
+                let (transcript_sender, transcript_receiver) = mpsc::channel(100);
+                tokio::spawn(async move {
+                    if audio_receiver.recv().await.is_some() {
+                        if let Some(utterance) = utterance {
+                            let interim = Transcript {
+                                text: utterance.split(' ').next().unwrap().to_string(),
+                                is_final: false,
+                                confidence: 0.0,
+                            };
+                            let _ = transcript_sender.send(Ok(interim)).await;
+                            tokio::time::sleep(final_delay).await;
+                            let final_transcript = Transcript {
+                                text: utterance,
+                                is_final: true,
+                                confidence: 0.9,
+                            };
+                            let _ = transcript_sender.send(Ok(final_transcript)).await;
+                        } else {
+                            // no more utterances, wait until audio is closed
+                            while audio_receiver.recv().await.is_some() {}
+                        }
+                    }
+                });
+                Ok((audio_sender, transcript_receiver))
+            })
+        }
+    }
+
+    /// Echoes query text, ends conversation on "bye".
+    #[allow(dead_code)]
+    struct FakeDialogService;
+
+    impl DialogService for FakeDialogService {
+        fn detect_intent(&self, text: String) -> BoxFuture<'_, Result<DetectIntentResponse>> {
+            Box::pin(async move {
+                let eoc = text == "bye";
+                let diagnostic_info =
+                    json_to_struct(serde_json::json!({ "end_conversation": eoc })).unwrap();
+                let parameters = json_to_struct(serde_json::json!({ "query": text })).unwrap();
+                Ok(DetectIntentResponse {
+                    query_result: Some(QueryResult {
+                        fulfillment_text: format!("you said {}", text),
+                        intent: Some(Intent {
+                            display_name: if eoc { "goodbye" } else { "echo" }.to_string(),
+                            ..Default::default()
+                        }),
+                        intent_detection_confidence: 1.0,
+                        parameters: Some(parameters),
+                        diagnostic_info: Some(diagnostic_info),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            })
+        }
+    }
+
+    /// Returns text bytes as audio.
+    #[allow(dead_code)]
+    struct FakeTextToSpeech;
+
+    impl TextToSpeech for FakeTextToSpeech {
+        fn synthesize(&self, text: String) -> BoxFuture<'_, Result<Vec<u8>>> {
+            Box::pin(async move { Ok(text.into_bytes()) })
+        }
+    }
+
+    #[allow(dead_code)]
+    fn fake_agent(
+        utterances: Vec<&str>,
+        config: VoiceAgentConfig,
+    ) -> VoiceAgent<FakeSpeechToText, FakeDialogService, FakeTextToSpeech> {
+        let utterances = utterances.into_iter().map(|u| u.to_string()).collect();
+        VoiceAgent::new(
+            FakeSpeechToText {
+                utterances: Arc::new(Mutex::new(utterances)),
+                final_delay: Duration::ZERO,
+            },
+            FakeDialogService,
+            FakeTextToSpeech,
+            config,
+        )
+    }
+
+    #[allow(dead_code)]
+    async fn collect_events(mut receiver: mpsc::Receiver<VoiceAgentEvent>) -> Vec<VoiceAgentEvent> {
+        let mut events = vec![];
+        while let Some(event) = receiver.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    // cargo test -- --show-output test_voice_agent_conversation
+    #[tokio::test]
+    async fn test_voice_agent_conversation() {
+        let mut agent = fake_agent(vec!["hello there", "bye"], VoiceAgentConfig::default());
+        let (audio_sender, audio_receiver) = mpsc::channel(100);
+        let (event_sender, event_receiver) = mpsc::channel(100);
+
+        tokio::spawn(async move {
+            for _ in 0..100 {
+                if audio_sender.send(vec![0u8; 320]).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+
+        agent.run(audio_receiver, event_sender).await.unwrap();
+        let events = collect_events(event_receiver).await;
+
+        let audio: Vec<String> = events
+            .iter()
+            .filter_map(|event| match event {
+                VoiceAgentEvent::Audio(audio) => Some(String::from_utf8(audio.clone()).unwrap()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(audio, vec!["you said hello there", "you said bye"]);
+
+        let intents: Vec<&str> = events
+            .iter()
+            .filter_map(|event| match event {
+                VoiceAgentEvent::Intent { display_name, .. } => Some(display_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(intents, vec!["echo", "goodbye"]);
+
+        assert!(events.iter().any(|event| matches!(
+            event,
+            VoiceAgentEvent::Parameters(params) if params["query"] == "hello there"
+        )));
+        assert!(matches!(
+            events.last().unwrap(),
+            VoiceAgentEvent::EndOfConversation
+        ));
+    }
+
+    // cargo test -- --show-output test_voice_agent_barge_in
+    #[tokio::test]
+    async fn test_voice_agent_barge_in() {
+        let config = VoiceAgentConfig {
+            // 1 byte per second, i.e. agent response is still playing when caller speaks
+            outbound_bytes_per_sec: Some(1),
+            ..Default::default()
+        };
+        let mut agent = fake_agent(vec!["hello", "bye"], config);
+        let (audio_sender, audio_receiver) = mpsc::channel(100);
+        let (event_sender, event_receiver) = mpsc::channel(100);
+
+        tokio::spawn(async move {
+            for _ in 0..100 {
+                if audio_sender.send(vec![0u8; 320]).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+
+        agent.run(audio_receiver, event_sender).await.unwrap();
+        let events = collect_events(event_receiver).await;
+        let barge_ins = events
+            .iter()
+            .filter(|event| matches!(event, VoiceAgentEvent::BargeIn))
+            .count();
+        assert_eq!(barge_ins, 1);
+    }
+
+    // cargo test -- --show-output test_voice_agent_no_input
+    #[tokio::test]
+    async fn test_voice_agent_no_input() {
+        struct NoInputHooks;
+        impl VoiceAgentHooks for NoInputHooks {
+            fn on_no_input(&mut self) -> Option<String> {
+                Some("bye".to_string())
+            }
+        }
+
+        let config = VoiceAgentConfig {
+            no_input_timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let mut agent = fake_agent(vec![], config).with_hooks(NoInputHooks);
+        // inbound audio is kept open but silent
+        let (_audio_sender, audio_receiver) = mpsc::channel(100);
+        let (event_sender, event_receiver) = mpsc::channel(100);
+
+        // welcome prompt playing for 100 ms, no input timer starts after it
+        agent.config.outbound_bytes_per_sec = Some(100);
+        let start = Instant::now();
+        agent
+            .say("0123456789".to_string(), &event_sender)
+            .await
+            .unwrap();
+        agent.run(audio_receiver, event_sender).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        let events = collect_events(event_receiver).await;
+        assert!(matches!(events[0], VoiceAgentEvent::Audio(_)));
+        assert!(matches!(events[1], VoiceAgentEvent::NoInput));
+        assert!(matches!(
+            events.last().unwrap(),
+            VoiceAgentEvent::EndOfConversation
+        ));
+    }
+
+    // cargo test -- --show-output test_voice_agent_no_input_cancelled
+    #[tokio::test]
+    async fn test_voice_agent_no_input_cancelled() {
+        let config = VoiceAgentConfig {
+            no_input_timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        // interim transcript arrives immediately, final one after no input timeout
+        let mut agent = VoiceAgent::new(
+            FakeSpeechToText {
+                utterances: Arc::new(Mutex::new(VecDeque::from(vec!["bye".to_string()]))),
+                final_delay: Duration::from_millis(150),
+            },
+            FakeDialogService,
+            FakeTextToSpeech,
+            config,
+        );
+        let (audio_sender, audio_receiver) = mpsc::channel(100);
+        let (event_sender, event_receiver) = mpsc::channel(100);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(40)).await;
+            let _ = audio_sender.send(vec![0u8; 320]).await;
+            // keep inbound audio open until conversation ends
+            audio_sender.closed().await;
+        });
+
+        agent.run(audio_receiver, event_sender).await.unwrap();
+        let events = collect_events(event_receiver).await;
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, VoiceAgentEvent::NoInput)));
+        assert!(matches!(
+            events.last().unwrap(),
+            VoiceAgentEvent::EndOfConversation
+        ));
+    }
+}